@@ -17,6 +17,7 @@ use mage::rendering::opengl::texture::{TextureParameter, TextureParameterValue};
 use mage::rendering::opengl::{clear, enable, set_clear_color, DrawingBuffer, Feature};
 use mage::rendering::TransformBuilder;
 use mage::resources::shader::ShaderLoader;
+use mage::resources::texture::TextureLoader;
 use mage::MageError;
 
 static SHADER_DIR: Dir<'static> = include_dir!("$CARGO_MANIFEST_DIR/examples/resources/shaders");
@@ -74,7 +75,7 @@ impl GameEngine {
         );
         program.set_uniform_matrix4("view", camera.look_at_matrix());
         program.set_uniform_matrix4("projection", camera.projection());
-        let rendering_mesh = cube.to_rendering_mesh()?;
+        let rendering_mesh = cube.to_rendering_mesh(&mut TextureLoader::new())?;
         program.use_program();
         rendering_mesh.attach_to_program(&program);
 
@@ -86,13 +87,22 @@ impl GameEngine {
 }
 
 impl Engine for GameEngine {
-    fn setup(&self, _world: &mut World) -> Result<(), MageError> {
+    fn setup(
+        &mut self,
+        _world: &mut World,
+        _texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
         enable(Feature::Depth);
         set_clear_color(Vector4::new(0.3, 0.3, 0.5, 1.0));
         Ok(())
     }
 
-    fn render(&self, _world: &mut World, _delta_time: f32) -> Result<(), MageError> {
+    fn render(
+        &self,
+        _world: &mut World,
+        _delta_time: f32,
+        _texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
         clear(&vec![DrawingBuffer::Color, DrawingBuffer::Depth]);
         self.program.use_program();
         self.rendering_mesh.draw();
@@ -12,6 +12,7 @@ use mage::rendering::opengl::program::Program;
 use mage::rendering::opengl::shader::{Shader, ShaderType};
 use mage::rendering::opengl::texture::{TextureParameter, TextureParameterValue};
 use mage::rendering::opengl::{clear, set_clear_color, DrawingBuffer};
+use mage::resources::texture::TextureLoader;
 use mage::MageError;
 
 const VERTEX_SHADER: &str = "#version 330 core
@@ -83,7 +84,7 @@ impl GameEngine {
         }]);
         program.use_program();
         program.set_uniform_i1("texture1", 0);
-        let rendering_mesh = quad.to_rendering_mesh()?;
+        let rendering_mesh = quad.to_rendering_mesh(&mut TextureLoader::new())?;
         program.use_program();
         rendering_mesh.attach_to_program(&program);
         Ok(GameEngine {
@@ -94,12 +95,21 @@ impl GameEngine {
 }
 
 impl Engine for GameEngine {
-    fn setup(&self, _world: &mut World) -> Result<(), MageError> {
+    fn setup(
+        &mut self,
+        _world: &mut World,
+        _texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
         set_clear_color(Vector4::new(0.3, 0.3, 0.5, 1.0));
         Ok(())
     }
 
-    fn render(&self, _world: &mut World, _delta_time: f32) -> Result<(), MageError> {
+    fn render(
+        &self,
+        _world: &mut World,
+        _delta_time: f32,
+        _texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
         clear(&[DrawingBuffer::Color]);
         self.program.use_program();
         self.rendering_mesh.draw();
@@ -0,0 +1,38 @@
+#version 410 core
+out vec4 FragColor;
+
+in vec2 TexCoord;
+
+#include "pbr-functions.glsl"
+
+const uint SAMPLE_COUNT = 1024u;
+
+vec2 integrateBRDF(float NdotV, float roughness)
+{
+	vec3 V = vec3(sqrt(1.0 - NdotV * NdotV), 0.0, NdotV);
+	vec3 N = vec3(0.0, 0.0, 1.0);
+	float k = (roughness * roughness) / 2.0;
+	float A = 0.0;
+	float B = 0.0;
+	for (uint i = 0u; i < SAMPLE_COUNT; ++i) {
+		vec2 Xi = hammersley(i, SAMPLE_COUNT);
+		vec3 H = importanceSampleGGX(Xi, N, roughness);
+		vec3 L = normalize(2.0 * dot(V, H) * H - V);
+		float NdotL = max(L.z, 0.0);
+		float NdotH = max(H.z, 0.0);
+		float VdotH = max(dot(V, H), 0.0);
+		if (NdotL > 0.0) {
+			float G = geometrySmith(N, V, L, k);
+			float G_Vis = (G * VdotH) / (NdotH * NdotV);
+			float Fc = pow(1.0 - VdotH, 5.0);
+			A += (1.0 - Fc) * G_Vis;
+			B += Fc * G_Vis;
+		}
+	}
+	return vec2(A, B) / float(SAMPLE_COUNT);
+}
+
+void main()
+{
+	FragColor = vec4(integrateBRDF(TexCoord.x, TexCoord.y), 0.0, 1.0);
+}
@@ -0,0 +1,29 @@
+#version 410 core
+out vec4 FragColor;
+
+in vec3 WorldPos;
+
+uniform samplerCube environmentMap;
+
+#include "pbr-functions.glsl"
+
+void main()
+{
+	vec3 N = normalize(WorldPos);
+	vec3 up = vec3(0.0, 1.0, 0.0);
+	vec3 right = normalize(cross(up, N));
+	up = normalize(cross(N, right));
+
+	vec3 irradiance = vec3(0.0);
+	float sampleDelta = 0.025;
+	float samples = 0.0;
+	for (float phi = 0.0; phi < 2.0 * PI; phi += sampleDelta) {
+		for (float theta = 0.0; theta < 0.5 * PI; theta += sampleDelta) {
+			vec3 tangentSample = vec3(sin(theta) * cos(phi), sin(theta) * sin(phi), cos(theta));
+			vec3 sampleVec = tangentSample.x * right + tangentSample.y * up + tangentSample.z * N;
+			irradiance += texture(environmentMap, sampleVec).rgb * cos(theta) * sin(theta);
+			samples++;
+		}
+	}
+	FragColor = vec4(PI * irradiance / samples, 1.0);
+}
@@ -0,0 +1,12 @@
+#define MAX_POINT_LIGHTS 8
+#define MAX_DIRECTIONAL_LIGHTS 4
+
+struct PointLight {
+	vec3 position;
+	vec3 color;
+};
+
+struct DirectionalLight {
+	vec3 direction;
+	vec3 color;
+};
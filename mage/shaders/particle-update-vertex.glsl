@@ -0,0 +1,27 @@
+#version 410 core
+layout (location = 0) in vec3 aPosition;
+layout (location = 1) in vec3 aVelocity;
+layout (location = 2) in float aAge;
+layout (location = 3) in float aLifetime;
+
+uniform float deltaTime;
+uniform vec3 gravity;
+
+out vec3 outPosition;
+out vec3 outVelocity;
+out float outAge;
+out float outLifetime;
+
+void main()
+{
+	outLifetime = aLifetime;
+	if (aAge >= aLifetime) {
+		outPosition = aPosition;
+		outVelocity = aVelocity;
+		outAge = aAge;
+		return;
+	}
+	outVelocity = aVelocity + gravity * deltaTime;
+	outPosition = aPosition + outVelocity * deltaTime;
+	outAge = aAge + deltaTime;
+}
@@ -0,0 +1,49 @@
+#version 410 core
+#define CURVE_SAMPLES 16
+layout (location = 0) in vec2 aCorner;
+layout (location = 1) in vec3 aPosition;
+layout (location = 2) in vec3 aVelocity;
+layout (location = 3) in float aAge;
+layout (location = 4) in float aLifetime;
+
+layout (std140) uniform Matrices {
+	mat4 view;
+	mat4 projection;
+};
+
+uniform vec4 colorOverLife[CURVE_SAMPLES];
+uniform float sizeOverLife[CURVE_SAMPLES];
+uniform int sheetColumns;
+uniform int sheetRows;
+uniform int sheetFrames;
+uniform float sheetCycles;
+
+out vec2 TexCoord;
+out vec4 Color;
+
+void main()
+{
+	if (aAge >= aLifetime) {
+		// Dead particles are pushed outside of the clip volume.
+		TexCoord = vec2(0.0);
+		Color = vec4(0.0);
+		gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+		return;
+	}
+	float life = clamp(aAge / aLifetime, 0.0, 1.0);
+	float curve = life * float(CURVE_SAMPLES - 1);
+	int index = min(int(curve), CURVE_SAMPLES - 2);
+	float t = curve - float(index);
+	Color = mix(colorOverLife[index], colorOverLife[index + 1], t);
+	float size = mix(sizeOverLife[index], sizeOverLife[index + 1], t);
+
+	int frame = int(life * sheetCycles * float(sheetFrames)) % sheetFrames;
+	int column = frame % sheetColumns;
+	int row = frame / sheetColumns;
+	TexCoord = (aCorner + vec2(0.5) + vec2(column, sheetRows - 1 - row)) / vec2(sheetColumns, sheetRows);
+
+	vec3 right = vec3(view[0][0], view[1][0], view[2][0]);
+	vec3 up = vec3(view[0][1], view[1][1], view[2][1]);
+	vec3 position = aPosition + (right * aCorner.x + up * aCorner.y) * size;
+	gl_Position = projection * view * vec4(position, 1.0);
+}
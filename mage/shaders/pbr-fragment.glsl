@@ -0,0 +1,132 @@
+#version 410 core
+out vec4 FragColor;
+
+in vec3 WorldPos;
+in vec3 Normal;
+in vec2 TexCoord;
+in mat3 TBN;
+
+#include "material.glsl"
+#include "lights.glsl"
+#include "pbr-functions.glsl"
+#include "splat.glsl"
+
+uniform Material material;
+uniform vec3 albedo;
+uniform float metalness;
+uniform float roughness;
+uniform float ao;
+uniform bool hasDiffuse;
+uniform bool hasMetalness;
+uniform bool hasRoughness;
+uniform bool hasAo;
+uniform bool hasNormal;
+uniform bool hasHeight;
+uniform float heightScale;
+uniform float opacity;
+uniform float alphaCutoff;
+
+uniform vec3 viewPos;
+uniform int pointLightsCount;
+uniform PointLight pointLights[MAX_POINT_LIGHTS];
+uniform int directionalLightsCount;
+uniform DirectionalLight directionalLights[MAX_DIRECTIONAL_LIGHTS];
+
+uniform bool hasEnvironment;
+uniform samplerCube irradianceMap;
+uniform samplerCube prefilterMap;
+uniform sampler2D brdfLUT;
+
+const float MAX_REFLECTION_LOD = 4.0;
+const float MIN_PARALLAX_LAYERS = 8.0;
+const float MAX_PARALLAX_LAYERS = 32.0;
+
+vec2 parallaxOcclusionMapping(vec2 texCoords, vec3 viewDir)
+{
+	float layers = mix(MAX_PARALLAX_LAYERS, MIN_PARALLAX_LAYERS, abs(viewDir.z));
+	float layerDepth = 1.0 / layers;
+	vec2 deltaTexCoords = viewDir.xy / max(viewDir.z, 0.0001) * heightScale / layers;
+
+	float currentLayerDepth = 0.0;
+	vec2 currentTexCoords = texCoords;
+	float currentDepth = 1.0 - texture(material.height, currentTexCoords).r;
+	for (float i = 0.0; i < MAX_PARALLAX_LAYERS && currentLayerDepth < currentDepth; i++) {
+		currentTexCoords -= deltaTexCoords;
+		currentDepth = 1.0 - texture(material.height, currentTexCoords).r;
+		currentLayerDepth += layerDepth;
+	}
+
+	vec2 previousTexCoords = currentTexCoords + deltaTexCoords;
+	float afterDepth = currentDepth - currentLayerDepth;
+	float beforeDepth = 1.0 - texture(material.height, previousTexCoords).r - currentLayerDepth + layerDepth;
+	float weight = afterDepth / (afterDepth - beforeDepth);
+	return previousTexCoords * weight + currentTexCoords * (1.0 - weight);
+}
+
+vec3 reflectance(vec3 N, vec3 V, vec3 L, vec3 radiance, vec3 F0, vec3 baseColor, float metallic, float rough)
+{
+	vec3 H = normalize(V + L);
+	float k = (rough + 1.0) * (rough + 1.0) / 8.0;
+	float NDF = distributionGGX(N, H, rough);
+	float G = geometrySmith(N, V, L, k);
+	vec3 F = fresnelSchlick(max(dot(H, V), 0.0), F0);
+	float NdotL = max(dot(N, L), 0.0);
+	vec3 specular = NDF * G * F / (4.0 * max(dot(N, V), 0.0) * NdotL + 0.0001);
+	vec3 kD = (vec3(1.0) - F) * (1.0 - metallic);
+	return (kD * baseColor / PI + specular) * radiance * NdotL;
+}
+
+void main()
+{
+	vec3 V = normalize(viewPos - WorldPos);
+	vec2 texCoord = TexCoord;
+	if (hasHeight) {
+		texCoord = parallaxOcclusionMapping(TexCoord, normalize(transpose(TBN) * V));
+	}
+
+	vec4 diffuse = splatting ? splatColor(texCoord)
+		: hasDiffuse ? texture(material.diffuse, texCoord) : vec4(albedo, 1.0);
+	float alpha = diffuse.a * opacity;
+	if (alpha < alphaCutoff) {
+		discard;
+	}
+	vec3 baseColor = splatting || hasDiffuse ? pow(diffuse.rgb, vec3(2.2)) : albedo;
+	float metallic = hasMetalness ? texture(material.metalness, texCoord).b : metalness;
+	float rough = hasRoughness ? texture(material.roughness, texCoord).g : roughness;
+	float occlusion = hasAo ? texture(material.ao, texCoord).r : ao;
+
+	vec3 N = normalize(Normal);
+	if (hasNormal) {
+		N = normalize(TBN * (texture(material.normal, texCoord).rgb * 2.0 - 1.0));
+	}
+	vec3 R = reflect(-V, N);
+	vec3 F0 = mix(vec3(0.04), baseColor, metallic);
+
+	vec3 Lo = vec3(0.0);
+	for (int i = 0; i < pointLightsCount; i++) {
+		vec3 L = normalize(pointLights[i].position - WorldPos);
+		float distance = length(pointLights[i].position - WorldPos);
+		vec3 radiance = pointLights[i].color / (distance * distance);
+		Lo += reflectance(N, V, L, radiance, F0, baseColor, metallic, rough);
+	}
+	for (int i = 0; i < directionalLightsCount; i++) {
+		vec3 L = normalize(-directionalLights[i].direction);
+		Lo += reflectance(N, V, L, directionalLights[i].color, F0, baseColor, metallic, rough);
+	}
+
+	vec3 ambient = vec3(0.03) * baseColor * occlusion;
+	if (hasEnvironment) {
+		vec3 F = fresnelSchlickRoughness(max(dot(N, V), 0.0), F0, rough);
+		vec3 kD = (1.0 - F) * (1.0 - metallic);
+		vec3 irradiance = texture(irradianceMap, N).rgb * baseColor;
+		vec3 prefilteredColor = textureLod(prefilterMap, R, rough * MAX_REFLECTION_LOD).rgb;
+		vec2 brdf = texture(brdfLUT, vec2(max(dot(N, V), 0.0), rough)).rg;
+		vec3 specular = prefilteredColor * (F * brdf.x + brdf.y);
+		ambient = (kD * irradiance + specular) * occlusion;
+	}
+
+	vec3 color = ambient + Lo;
+	color = color / (color + vec3(1.0));
+	color = pow(color, vec3(1.0 / 2.2));
+	FragColor = vec4(color, alpha);
+}
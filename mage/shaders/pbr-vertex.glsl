@@ -0,0 +1,37 @@
+#version 410 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+layout (location = 2) in vec2 aTexCoord;
+layout (location = 3) in vec3 aTangent;
+layout (location = 4) in vec3 aBitangent;
+layout (location = 5) in mat4 aModel;
+
+#include "skinning.glsl"
+
+layout (std140) uniform Matrices {
+	mat4 view;
+	mat4 projection;
+};
+uniform mat4 model;
+uniform bool instanced;
+
+out vec3 WorldPos;
+out vec3 Normal;
+out vec2 TexCoord;
+out mat3 TBN;
+
+void main()
+{
+	mat4 modelMatrix = instanced ? aModel : model;
+	mat4 skin = skinMatrix();
+	WorldPos = vec3(modelMatrix * skin * vec4(aPos, 1.0));
+	mat3 normalMatrix = transpose(inverse(mat3(modelMatrix * skin)));
+	Normal = normalMatrix * aNormal;
+	TBN = mat3(
+		normalize(mat3(modelMatrix * skin) * aTangent),
+		normalize(mat3(modelMatrix * skin) * aBitangent),
+		normalize(Normal)
+	);
+	TexCoord = aTexCoord;
+	gl_Position = projection * view * vec4(WorldPos, 1.0);
+}
@@ -0,0 +1,39 @@
+#version 410 core
+out vec4 FragColor;
+
+in vec3 WorldPos;
+
+uniform samplerCube environmentMap;
+uniform float roughness;
+uniform float resolution;
+
+#include "pbr-functions.glsl"
+
+const uint SAMPLE_COUNT = 1024u;
+
+void main()
+{
+	vec3 N = normalize(WorldPos);
+	vec3 R = N;
+	vec3 V = R;
+
+	float totalWeight = 0.0;
+	vec3 prefilteredColor = vec3(0.0);
+	for (uint i = 0u; i < SAMPLE_COUNT; ++i) {
+		vec2 Xi = hammersley(i, SAMPLE_COUNT);
+		vec3 H = importanceSampleGGX(Xi, N, roughness);
+		vec3 L = normalize(2.0 * dot(V, H) * H - V);
+		float NdotL = max(dot(N, L), 0.0);
+		if (NdotL > 0.0) {
+			float NdotH = max(dot(N, H), 0.0);
+			float HdotV = max(dot(H, V), 0.0);
+			float pdf = distributionGGX(N, H, roughness) * NdotH / (4.0 * HdotV) + 0.0001;
+			float saTexel = 4.0 * PI / (6.0 * resolution * resolution);
+			float saSample = 1.0 / (float(SAMPLE_COUNT) * pdf + 0.0001);
+			float mipLevel = roughness == 0.0 ? 0.0 : 0.5 * log2(saSample / saTexel);
+			prefilteredColor += textureLod(environmentMap, L, mipLevel).rgb * NdotL;
+			totalWeight += NdotL;
+		}
+	}
+	FragColor = vec4(prefilteredColor / totalWeight, 1.0);
+}
@@ -4,9 +4,15 @@ out vec4 FragColor;
 in vec2 TexCoord;
 
 #include "material.glsl"
+#include "splat.glsl"
 uniform Material material;
+uniform float alphaCutoff;
 
 void main()
 {
-    FragColor = texture(material.diffuse, TexCoord);
+    vec4 color = splatting ? splatColor(TexCoord) : texture(material.diffuse, TexCoord);
+    if (color.a < alphaCutoff) {
+        discard;
+    }
+    FragColor = color;
 }
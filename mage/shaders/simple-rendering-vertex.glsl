@@ -2,21 +2,26 @@
 layout (location = 0) in vec3 aPos;
 layout (location = 1) in vec3 aNormal;
 layout (location = 2) in vec2 aTexCoord;
+layout (location = 5) in mat4 aModel;
+
+#include "skinning.glsl"
 
 layout (std140) uniform Matrices {
 	mat4 view;
 	mat4 projection;
 };
 uniform mat4 model;
+uniform bool instanced;
 
 out vec2 TexCoord;
 
 void main()
 {
+	mat4 modelMatrix = instanced ? aModel : model;
 	/*
 	FragPos = vec3(model * vec4(aPos, 1.0))
 	Normal = transpose(inverse(mat3(model))) * aNormal;
 	*/
-	gl_Position = projection * view *  model * vec4(aPos, 1.0);
+	gl_Position = projection * view * modelMatrix * skinMatrix() * vec4(aPos, 1.0);
 	TexCoord = aTexCoord;
 }
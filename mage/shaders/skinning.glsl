@@ -0,0 +1,20 @@
+#define MAX_JOINTS 128
+
+layout (location = 9) in vec4 aJoints;
+layout (location = 10) in vec4 aWeights;
+
+layout (std140) uniform Joints {
+	mat4 joints[MAX_JOINTS];
+};
+uniform bool skinned;
+
+mat4 skinMatrix()
+{
+	if (!skinned) {
+		return mat4(1.0);
+	}
+	return aWeights.x * joints[int(aJoints.x)] +
+		aWeights.y * joints[int(aJoints.y)] +
+		aWeights.z * joints[int(aJoints.z)] +
+		aWeights.w * joints[int(aJoints.w)];
+}
@@ -0,0 +1,16 @@
+#version 410 core
+layout (location = 0) in vec3 aPos;
+
+layout (std140) uniform Matrices {
+	mat4 view;
+	mat4 projection;
+};
+
+out vec3 TexCoords;
+
+void main()
+{
+	TexCoords = aPos;
+	vec4 position = projection * mat4(mat3(view)) * vec4(aPos, 1.0);
+	gl_Position = position.xyww;
+}
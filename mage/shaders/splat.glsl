@@ -0,0 +1,22 @@
+#define MAX_SPLAT_LAYERS 4
+
+uniform bool splatting;
+uniform sampler2D splatMap;
+uniform sampler2D splatLayers[MAX_SPLAT_LAYERS];
+uniform float splatTiling[MAX_SPLAT_LAYERS];
+uniform int splatLayersCount;
+
+vec4 splatColor(vec2 texCoord)
+{
+	vec4 weights = texture(splatMap, texCoord);
+	vec4 color = vec4(0.0);
+	float total = 0.0;
+	for (int i = 0; i < MAX_SPLAT_LAYERS; i++) {
+		if (i >= splatLayersCount) {
+			break;
+		}
+		color += texture(splatLayers[i], texCoord * splatTiling[i]) * weights[i];
+		total += weights[i];
+	}
+	return total > 0.0 ? color / total : texture(splatLayers[0], texCoord * splatTiling[0]);
+}
@@ -0,0 +1,19 @@
+#version 410 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec2 aTexCoord;
+layout (location = 2) in vec4 aColor;
+
+layout (std140) uniform Matrices {
+	mat4 view;
+	mat4 projection;
+};
+
+out vec2 TexCoord;
+out vec4 Color;
+
+void main()
+{
+	TexCoord = aTexCoord;
+	Color = aColor;
+	gl_Position = projection * view * vec4(aPos, 1.0);
+}
@@ -0,0 +1,16 @@
+#version 410 core
+out vec4 FragColor;
+
+in vec2 TexCoord;
+in vec4 Color;
+
+uniform sampler2D glyphs;
+
+void main()
+{
+	vec4 color = texture(glyphs, TexCoord) * Color;
+	if (color.a == 0.0) {
+		discard;
+	}
+	FragColor = color;
+}
@@ -0,0 +1,26 @@
+#version 410 core
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec2 aTexCoord;
+layout (location = 2) in vec4 aColor;
+
+layout (std140) uniform Matrices {
+	mat4 view;
+	mat4 projection;
+};
+
+uniform bool screenSpace;
+uniform mat4 screenProjection;
+
+out vec2 TexCoord;
+out vec4 Color;
+
+void main()
+{
+	TexCoord = aTexCoord;
+	Color = aColor;
+	if (screenSpace) {
+		gl_Position = screenProjection * vec4(aPos, 1.0);
+	} else {
+		gl_Position = projection * view * vec4(aPos, 1.0);
+	}
+}
@@ -0,0 +1,17 @@
+#version 410 core
+out vec4 FragColor;
+
+in vec2 TexCoord;
+in vec4 Color;
+in float Textured;
+
+uniform sampler2D glyphs;
+
+void main()
+{
+	vec4 color = mix(vec4(1.0), texture(glyphs, TexCoord), Textured) * Color;
+	if (color.a == 0.0) {
+		discard;
+	}
+	FragColor = color;
+}
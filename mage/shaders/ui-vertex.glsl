@@ -0,0 +1,19 @@
+#version 410 core
+layout (location = 0) in vec2 aPos;
+layout (location = 1) in vec2 aTexCoord;
+layout (location = 2) in vec4 aColor;
+layout (location = 3) in float aTextured;
+
+uniform mat4 projection;
+
+out vec2 TexCoord;
+out vec4 Color;
+out float Textured;
+
+void main()
+{
+	TexCoord = aTexCoord;
+	Color = aColor;
+	Textured = aTextured;
+	gl_Position = projection * vec4(aPos, 0.0, 1.0);
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use nalgebra::{Quaternion, UnitQuaternion, Vector3};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Interpolation {
+    CubicSpline,
+    #[default]
+    Linear,
+    Step,
+}
+
+#[derive(Clone, Debug)]
+pub enum Keyframes {
+    Rotation(Vec<Quaternion<f32>>),
+    Scale(Vec<Vector3<f32>>),
+    Translation(Vec<Vector3<f32>>),
+}
+
+impl Keyframes {
+    pub fn len(&self) -> usize {
+        match self {
+            Keyframes::Rotation(values) => values.len(),
+            Keyframes::Scale(values) => values.len(),
+            Keyframes::Translation(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Pose {
+    pub rotation: Option<UnitQuaternion<f32>>,
+    pub scale: Option<Vector3<f32>>,
+    pub translation: Option<Vector3<f32>>,
+}
+
+fn hermite<T>(values: &[T], index: usize, t: f32, duration: f32) -> T
+where
+    T: Copy + std::ops::Add<Output = T> + std::ops::Mul<f32, Output = T>,
+{
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let from = values[index * 3 + 1];
+    let out_tangent = values[index * 3 + 2];
+    let in_tangent = values[(index + 1) * 3];
+    let to = values[(index + 1) * 3 + 1];
+    from * (2f32 * t3 - 3f32 * t2 + 1f32)
+        + out_tangent * ((t3 - 2f32 * t2 + t) * duration)
+        + to * (-2f32 * t3 + 3f32 * t2)
+        + in_tangent * ((t3 - t2) * duration)
+}
+
+#[derive(Clone, Debug)]
+pub struct Channel {
+    pub interpolation: Interpolation,
+    pub keyframes: Keyframes,
+    pub target: String,
+    pub times: Vec<f32>,
+}
+
+impl Channel {
+    fn locate(&self, time: f32) -> (usize, usize, f32, f32) {
+        let last = self.times.len().saturating_sub(1);
+        if time <= self.times[0] {
+            return (0, 0, 0f32, 0f32);
+        }
+        if time >= self.times[last] {
+            return (last, last, 0f32, 0f32);
+        }
+        let next = self.times.partition_point(|&t| t <= time);
+        let previous = next - 1;
+        let duration = self.times[next] - self.times[previous];
+        let t = if duration > 0f32 {
+            (time - self.times[previous]) / duration
+        } else {
+            0f32
+        };
+        (previous, next, t, duration)
+    }
+
+    fn sample_vectors(&self, values: &[Vector3<f32>], time: f32) -> Vector3<f32> {
+        let (previous, next, t, duration) = self.locate(time);
+        match self.interpolation {
+            Interpolation::CubicSpline if previous != next => {
+                hermite(values, previous, t, duration)
+            }
+            Interpolation::CubicSpline => values[previous * 3 + 1],
+            Interpolation::Linear => values[previous].lerp(&values[next], t),
+            Interpolation::Step => values[previous],
+        }
+    }
+
+    fn sample_rotations(&self, values: &[Quaternion<f32>], time: f32) -> UnitQuaternion<f32> {
+        let (previous, next, t, duration) = self.locate(time);
+        match self.interpolation {
+            Interpolation::CubicSpline if previous != next => {
+                UnitQuaternion::new_normalize(hermite(values, previous, t, duration))
+            }
+            Interpolation::CubicSpline => UnitQuaternion::new_normalize(values[previous * 3 + 1]),
+            Interpolation::Linear => {
+                let from = UnitQuaternion::new_normalize(values[previous]);
+                let to = UnitQuaternion::new_normalize(values[next]);
+                from.try_slerp(&to, t, f32::EPSILON).unwrap_or(to)
+            }
+            Interpolation::Step => UnitQuaternion::new_normalize(values[previous]),
+        }
+    }
+
+    pub fn sample(&self, time: f32, pose: &mut Pose) {
+        let values_per_key = match self.interpolation {
+            Interpolation::CubicSpline => 3,
+            _ => 1,
+        };
+        if self.times.is_empty() || self.keyframes.len() < self.times.len() * values_per_key {
+            return;
+        }
+        match &self.keyframes {
+            Keyframes::Rotation(values) => {
+                pose.rotation = Some(self.sample_rotations(values, time));
+            }
+            Keyframes::Scale(values) => pose.scale = Some(self.sample_vectors(values, time)),
+            Keyframes::Translation(values) => {
+                pose.translation = Some(self.sample_vectors(values, time));
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnimationClip {
+    pub channels: Vec<Channel>,
+    pub duration: f32,
+    pub name: String,
+}
+
+impl AnimationClip {
+    pub fn new(name: &str, channels: Vec<Channel>) -> AnimationClip {
+        let duration = channels
+            .iter()
+            .filter_map(|channel| channel.times.last().copied())
+            .fold(0f32, f32::max);
+        AnimationClip {
+            channels,
+            duration,
+            name: name.to_string(),
+        }
+    }
+
+    pub fn sample(&self, time: f32) -> HashMap<String, Pose> {
+        let mut poses: HashMap<String, Pose> = HashMap::new();
+        for channel in self.channels.iter() {
+            channel.sample(time, poses.entry(channel.target.clone()).or_default());
+        }
+        poses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+
+    use super::{Channel, Interpolation, Keyframes, Pose};
+
+    fn channel(interpolation: Interpolation, times: Vec<f32>, values: Vec<f32>) -> Channel {
+        Channel {
+            interpolation,
+            keyframes: Keyframes::Translation(
+                values
+                    .into_iter()
+                    .map(|x| Vector3::new(x, 0f32, 0f32))
+                    .collect(),
+            ),
+            target: "bone".to_string(),
+            times,
+        }
+    }
+
+    fn sample(channel: &Channel, time: f32) -> Option<f32> {
+        let mut pose = Pose::default();
+        channel.sample(time, &mut pose);
+        pose.translation.map(|translation| translation.x)
+    }
+
+    #[test]
+    fn linear_channels_interpolate_and_clamp() {
+        let channel = channel(Interpolation::Linear, vec![1f32, 3f32], vec![2f32, 6f32]);
+        assert_eq!(sample(&channel, 0f32), Some(2f32));
+        assert_eq!(sample(&channel, 2f32), Some(4f32));
+        assert_eq!(sample(&channel, 5f32), Some(6f32));
+    }
+
+    #[test]
+    fn step_channels_hold_the_previous_key() {
+        let channel = channel(Interpolation::Step, vec![0f32, 1f32], vec![2f32, 6f32]);
+        assert_eq!(sample(&channel, 0.99f32), Some(2f32));
+        assert_eq!(sample(&channel, 1f32), Some(6f32));
+    }
+
+    #[test]
+    fn cubic_spline_channels_skip_tangents() {
+        // Each key is stored as in-tangent, value, out-tangent
+        let channel = channel(
+            Interpolation::CubicSpline,
+            vec![0f32, 2f32],
+            vec![9f32, 2f32, 0f32, 0f32, 6f32, 9f32],
+        );
+        assert_eq!(sample(&channel, 0f32), Some(2f32));
+        assert_eq!(sample(&channel, 1f32), Some(4f32));
+        assert_eq!(sample(&channel, 2f32), Some(6f32));
+    }
+
+    #[test]
+    fn channels_without_enough_keyframes_are_ignored() {
+        let channel = channel(
+            Interpolation::CubicSpline,
+            vec![0f32, 1f32],
+            vec![1f32, 2f32],
+        );
+        assert_eq!(sample(&channel, 0f32), None);
+    }
+}
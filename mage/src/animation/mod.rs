@@ -0,0 +1,11 @@
+mod clip;
+pub use clip::{AnimationClip, Channel, Interpolation, Keyframes, Pose};
+
+mod player;
+pub use player::AnimationPlayer;
+
+mod skin;
+pub use skin::{Skin, MAX_JOINTS};
+
+mod system;
+pub use system::AnimationSystem;
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use hecs::Entity;
+use nalgebra::{Quaternion, UnitQuaternion, Vector3};
+
+use crate::animation::clip::{AnimationClip, Pose};
+use crate::rendering::Transform;
+
+#[derive(Clone, Debug)]
+struct AnimationLayer {
+    clip: Arc<AnimationClip>,
+    fade: f32,
+    looping: bool,
+    target_weight: f32,
+    time: f32,
+    weight: f32,
+}
+
+impl AnimationLayer {
+    fn new(clip: Arc<AnimationClip>, looping: bool, weight: f32) -> AnimationLayer {
+        AnimationLayer {
+            clip,
+            fade: 0f32,
+            looping,
+            target_weight: weight,
+            time: 0f32,
+            weight,
+        }
+    }
+
+    fn advance(&mut self, seconds: f32) {
+        self.time += seconds;
+        let duration = self.clip.duration;
+        if self.looping && duration > 0f32 {
+            self.time = self.time.rem_euclid(duration);
+        } else {
+            self.time = self.time.clamp(0f32, duration);
+        }
+        if self.fade > 0f32 {
+            let step = self.fade * seconds.abs();
+            if (self.target_weight - self.weight).abs() <= step {
+                self.weight = self.target_weight;
+                self.fade = 0f32;
+            } else {
+                self.weight += step * (self.target_weight - self.weight).signum();
+            }
+        }
+    }
+
+    fn fade_to(&mut self, weight: f32, duration: f32) {
+        self.target_weight = weight;
+        if duration > 0f32 {
+            self.fade = (weight - self.weight).abs() / duration;
+        } else {
+            self.weight = weight;
+            self.fade = 0f32;
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct BlendedPose {
+    rotation: Quaternion<f32>,
+    rotation_weight: f32,
+    scale: Vector3<f32>,
+    scale_weight: f32,
+    translation: Vector3<f32>,
+    translation_weight: f32,
+}
+
+impl BlendedPose {
+    fn new() -> BlendedPose {
+        BlendedPose {
+            rotation: Quaternion::new(0f32, 0f32, 0f32, 0f32),
+            rotation_weight: 0f32,
+            scale: Vector3::zeros(),
+            scale_weight: 0f32,
+            translation: Vector3::zeros(),
+            translation_weight: 0f32,
+        }
+    }
+
+    fn add(&mut self, pose: &Pose, weight: f32) {
+        if let Some(rotation) = pose.rotation {
+            let mut rotation = rotation.into_inner();
+            if self.rotation.dot(&rotation) < 0f32 {
+                rotation = -rotation;
+            }
+            self.rotation += rotation * weight;
+            self.rotation_weight += weight;
+        }
+        if let Some(scale) = pose.scale {
+            self.scale += scale * weight;
+            self.scale_weight += weight;
+        }
+        if let Some(translation) = pose.translation {
+            self.translation += translation * weight;
+            self.translation_weight += weight;
+        }
+    }
+
+    fn resolve(mut self, rest: &Transform) -> Transform {
+        let rest_rotation = rest.rotation.into_inner();
+        if self.rotation_weight < 1f32 {
+            let sign = if self.rotation.dot(&rest_rotation) < 0f32 {
+                -1f32
+            } else {
+                1f32
+            };
+            self.rotation += rest_rotation * (sign * (1f32 - self.rotation_weight));
+        }
+        let blend = |value: Vector3<f32>, weight: f32, rest: Vector3<f32>| {
+            if weight < 1f32 {
+                value + rest * (1f32 - weight)
+            } else {
+                value / weight
+            }
+        };
+        Transform {
+            position: blend(self.translation, self.translation_weight, rest.position),
+            rotation: UnitQuaternion::try_new(self.rotation, f32::EPSILON).unwrap_or(rest.rotation),
+            scale: blend(self.scale, self.scale_weight, rest.scale),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnimationPlayer {
+    clips: HashMap<String, Arc<AnimationClip>>,
+    layers: Vec<AnimationLayer>,
+    pub paused: bool,
+    pub(crate) rest: HashMap<Entity, Transform>,
+    pub speed: f32,
+    pub(crate) targets: HashMap<String, Entity>,
+}
+
+impl AnimationPlayer {
+    pub fn new() -> AnimationPlayer {
+        AnimationPlayer {
+            clips: HashMap::new(),
+            layers: vec![],
+            paused: false,
+            rest: HashMap::new(),
+            speed: 1f32,
+            targets: HashMap::new(),
+        }
+    }
+
+    pub fn add_clip(&mut self, clip: AnimationClip) {
+        self.add_shared_clip(Arc::new(clip));
+    }
+
+    pub fn add_shared_clip(&mut self, clip: Arc<AnimationClip>) {
+        self.clips.insert(clip.name.clone(), clip);
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&Arc<AnimationClip>> {
+        self.clips.get(name)
+    }
+
+    pub fn clip_names(&self) -> Vec<&str> {
+        self.clips.keys().map(String::as_str).collect()
+    }
+
+    pub fn play(&mut self, name: &str, looping: bool) {
+        if let Some(clip) = self.clips.get(name) {
+            self.layers = vec![AnimationLayer::new(clip.clone(), looping, 1f32)];
+        }
+    }
+
+    pub fn crossfade(&mut self, name: &str, duration: f32, looping: bool) {
+        let clip = match self.clips.get(name) {
+            Some(clip) => clip.clone(),
+            None => return,
+        };
+        for layer in self.layers.iter_mut() {
+            layer.fade_to(0f32, duration);
+        }
+        match self.layers.iter_mut().find(|layer| layer.clip.name == name) {
+            Some(layer) => {
+                layer.looping = looping;
+                layer.fade_to(1f32, duration);
+            }
+            None => {
+                let mut layer = AnimationLayer::new(clip, looping, 0f32);
+                layer.fade_to(1f32, duration);
+                self.layers.push(layer);
+            }
+        }
+    }
+
+    pub fn blend(&mut self, name: &str, weight: f32, looping: bool) {
+        let clip = match self.clips.get(name) {
+            Some(clip) => clip.clone(),
+            None => return,
+        };
+        match self.layers.iter_mut().find(|layer| layer.clip.name == name) {
+            Some(layer) => {
+                layer.looping = looping;
+                layer.fade_to(weight, 0f32);
+            }
+            None => self.layers.push(AnimationLayer::new(clip, looping, weight)),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.layers.clear();
+    }
+
+    pub fn is_playing(&self, name: &str) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| layer.clip.name == name && layer.target_weight > 0f32)
+    }
+
+    pub fn is_finished(&self, name: &str) -> bool {
+        self.layers
+            .iter()
+            .find(|layer| layer.clip.name == name)
+            .map(|layer| !layer.looping && layer.time >= layer.clip.duration)
+            .unwrap_or(true)
+    }
+
+    pub fn time(&self, name: &str) -> Option<f32> {
+        self.layers
+            .iter()
+            .find(|layer| layer.clip.name == name)
+            .map(|layer| layer.time)
+    }
+
+    pub fn set_time(&mut self, name: &str, time: f32) {
+        if let Some(layer) = self.layers.iter_mut().find(|layer| layer.clip.name == name) {
+            layer.time = time.clamp(0f32, layer.clip.duration);
+        }
+    }
+
+    pub(crate) fn advance(&mut self, delta_time: u64, time_scale: f32) {
+        if self.paused {
+            return;
+        }
+        let seconds = delta_time as f32 / 1000f32 * self.speed * time_scale;
+        for layer in self.layers.iter_mut() {
+            layer.advance(seconds);
+        }
+        self.layers
+            .retain(|layer| layer.weight > 0f32 || layer.target_weight > 0f32);
+    }
+
+    pub(crate) fn pose(&self) -> HashMap<String, Transform> {
+        let mut blended: HashMap<&str, BlendedPose> = HashMap::new();
+        let poses = self
+            .layers
+            .iter()
+            .filter(|layer| layer.weight > 0f32)
+            .map(|layer| (layer.clip.sample(layer.time), layer.weight))
+            .collect::<Vec<_>>();
+        for (pose, weight) in poses.iter() {
+            for (target, pose) in pose.iter() {
+                blended
+                    .entry(target.as_str())
+                    .or_insert_with(BlendedPose::new)
+                    .add(pose, *weight);
+            }
+        }
+        blended
+            .into_iter()
+            .filter_map(|(target, pose)| {
+                let entity = self.targets.get(target)?;
+                let rest = self.rest.get(entity)?;
+                Some((target.to_string(), pose.resolve(rest)))
+            })
+            .collect()
+    }
+}
+
+impl Default for AnimationPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+
+    use super::AnimationPlayer;
+    use crate::animation::clip::{AnimationClip, Channel, Interpolation, Keyframes};
+    use crate::rendering::Transform;
+
+    fn clip(name: &str, x: f32) -> AnimationClip {
+        let channel = Channel {
+            interpolation: Interpolation::Step,
+            keyframes: Keyframes::Translation(vec![Vector3::new(x, 0f32, 0f32); 2]),
+            target: "bone".to_string(),
+            times: vec![0f32, 10f32],
+        };
+        AnimationClip::new(name, vec![channel])
+    }
+
+    fn player() -> AnimationPlayer {
+        let mut player = AnimationPlayer::new();
+        player.add_clip(clip("idle", 0f32));
+        player.add_clip(clip("walk", 4f32));
+        let entity = hecs::World::new().spawn(());
+        player.targets.insert("bone".to_string(), entity);
+        player.rest.insert(entity, Transform::identity());
+        player
+    }
+
+    fn translation(player: &AnimationPlayer) -> f32 {
+        player.pose()["bone"].position.x
+    }
+
+    #[test]
+    fn crossfade_blends_weights_over_the_duration() {
+        let mut player = player();
+        player.play("idle", true);
+        player.crossfade("walk", 1f32, true);
+        assert_eq!(translation(&player), 0f32);
+        player.advance(500, 1f32);
+        assert!((translation(&player) - 2f32).abs() < 1e-5);
+        assert!(player.is_playing("walk"));
+        assert!(!player.is_playing("idle"));
+        player.advance(500, 1f32);
+        assert!((translation(&player) - 4f32).abs() < 1e-5);
+        assert_eq!(player.time("idle"), None);
+    }
+
+    #[test]
+    fn partial_weights_blend_with_the_rest_pose() {
+        let mut player = player();
+        player.blend("walk", 0.25f32, true);
+        assert!((translation(&player) - 1f32).abs() < 1e-5);
+    }
+}
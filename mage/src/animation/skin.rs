@@ -0,0 +1,35 @@
+use hecs::{Entity, World};
+use nalgebra::Matrix4;
+
+use crate::rendering::Transform;
+
+pub const MAX_JOINTS: usize = 128;
+
+#[derive(Clone, Debug)]
+pub struct Skin {
+    pub inverse_bind_matrices: Vec<Matrix4<f32>>,
+    pub joints: Vec<Entity>,
+}
+
+impl Skin {
+    pub fn joint_matrices(&self, world: &World, model: &Matrix4<f32>) -> Vec<Matrix4<f32>> {
+        let inverse_model = model.try_inverse().unwrap_or_else(Matrix4::identity);
+        self.joints
+            .iter()
+            .take(MAX_JOINTS)
+            .enumerate()
+            .map(|(i, joint)| {
+                let joint = world
+                    .get::<Transform>(*joint)
+                    .map(|transform| transform.get_model_matrix())
+                    .unwrap_or_else(|_| Matrix4::identity());
+                let inverse_bind = self
+                    .inverse_bind_matrices
+                    .get(i)
+                    .copied()
+                    .unwrap_or_else(Matrix4::identity);
+                inverse_model * joint * inverse_bind
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,82 @@
+use hecs::{Entity, World};
+
+use crate::animation::AnimationPlayer;
+use crate::core::system::System;
+use crate::core::time::time_scale;
+use crate::rendering::hierarchy::{descendants, Name, Parent};
+use crate::rendering::Transform;
+use crate::MageError;
+
+fn resolve_targets(world: &World, root: Entity, player: &mut AnimationPlayer) {
+    for entity in descendants(world, root) {
+        let name = match world.get::<Name>(entity) {
+            Ok(name) => name.0.clone(),
+            Err(_) => continue,
+        };
+        if player.targets.contains_key(&name) {
+            continue;
+        }
+        let rest = match world.get::<Parent>(entity) {
+            Ok(parent) => parent.local.clone(),
+            Err(_) => match world.get::<Transform>(entity) {
+                Ok(transform) => (*transform).clone(),
+                Err(_) => continue,
+            },
+        };
+        player.targets.insert(name, entity);
+        player.rest.insert(entity, rest);
+    }
+}
+
+pub struct AnimationSystem;
+
+impl System for AnimationSystem {
+    fn name(&self) -> &str {
+        "Animation"
+    }
+
+    fn start(&self, _world: &mut World) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn early_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn update(&self, world: &mut World, delta_time: u64) -> Result<(), MageError> {
+        let time_scale = time_scale(world);
+        let players = world
+            .query::<&AnimationPlayer>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for entity in players {
+            let poses = {
+                let mut player = world.get_mut::<AnimationPlayer>(entity)?;
+                if player.targets.is_empty() {
+                    resolve_targets(world, entity, &mut player);
+                }
+                player.advance(delta_time, time_scale);
+                player
+                    .pose()
+                    .into_iter()
+                    .filter_map(|(name, pose)| Some((*player.targets.get(&name)?, pose)))
+                    .collect::<Vec<_>>()
+            };
+            for (target, pose) in poses {
+                if let Ok(mut parent) = world.get_mut::<Parent>(target) {
+                    parent.local = pose;
+                    continue;
+                }
+                if let Ok(mut transform) = world.get_mut::<Transform>(target) {
+                    *transform = pose;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn late_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+}
@@ -3,7 +3,10 @@ use crate::core::window::Window;
 use crate::core::world::World;
 use crate::gameplay::input::{Input, InputSystem, InputType};
 use crate::gameplay::quit::{QuitControl, QuitSystem};
-use crate::rendering::engine::Engine;
+use crate::rendering::engine::{sync_rendering_meshes, Engine};
+use crate::rendering::opengl::set_viewport;
+use crate::resources::texture::TextureLoader;
+use crate::ui::{paint, Ui, UiCanvas, UiRenderer, UiSystem};
 use crate::MageError;
 use hecs::{Component, DynamicBundle, Entity};
 use rapier3d::dynamics::RigidBody;
@@ -17,16 +20,29 @@ use std::sync::Arc;
 
 pub struct GameBuilder<E: EventHandler, P: PhysicsHooks> {
     game_ended: Arc<AtomicBool>,
+    samples: u32,
     window: Window,
     world: World<E, P>,
 }
 
 impl GameBuilder<(), ()> {
     pub fn new(name: &str, width: u32, height: u32) -> Result<GameBuilder<(), ()>, MageError> {
+        GameBuilder::new_with_samples(name, width, height, 0)
+    }
+
+    pub fn new_with_samples(
+        name: &str,
+        width: u32,
+        height: u32,
+        samples: u32,
+    ) -> Result<GameBuilder<(), ()>, MageError> {
         let world = World::new();
+        // The engine renders into its own multisampled target and resolves it,
+        // so the default framebuffer stays single-sampled.
         let window = Window::new(name, width, height)?;
         Ok(GameBuilder {
             game_ended: Arc::new(AtomicBool::new(false)),
+            samples,
             window,
             world,
         })
@@ -34,11 +50,16 @@ impl GameBuilder<(), ()> {
 }
 
 impl<E: EventHandler, P: PhysicsHooks> GameBuilder<E, P> {
-    pub fn build<N: Engine>(self, engine: N) -> Game<N, E, P> {
+    pub fn build<N: Engine>(self, mut engine: N) -> Game<N, E, P> {
+        if self.samples > 0 {
+            let (width, height) = self.window.size();
+            engine.set_samples(width, height, self.samples);
+        }
         Game {
             engine,
             frame_rate: 1000 / 60, // 60 frames per second
             game_ended: self.game_ended,
+            texture_loader: TextureLoader::new(),
             window: self.window,
             world: self.world,
         }
@@ -49,6 +70,7 @@ pub struct Game<N: Engine, E: EventHandler, P: PhysicsHooks> {
     engine: N,
     frame_rate: u64,
     game_ended: Arc<AtomicBool>,
+    texture_loader: TextureLoader,
     window: Window,
     world: World<E, P>,
 }
@@ -99,15 +121,25 @@ impl<N: Engine, E: EventHandler, P: PhysicsHooks> Game<N, E, P> {
         self.world.add_system(Box::new(QuitSystem {
             game_ended: self.game_ended.clone(),
         }));
+        self.world.add_system(Box::new(UiSystem));
         for system in systems {
             self.world.add_system(system);
         }
 
         self.window.start_timer();
-        self.engine.setup(&mut self.world.world)?;
+        sync_rendering_meshes(&mut self.world.world, &mut self.texture_loader)?;
+        self.engine
+            .setup(&mut self.world.world, &mut self.texture_loader)?;
         self.world.start();
+        let ui_renderer = UiRenderer::new()?;
         let mut lag = 0;
+        let mut size = self.window.size();
         while !self.game_ended.load(Ordering::Relaxed) {
+            if self.window.size() != size {
+                size = self.window.size();
+                set_viewport(0, 0, size.0, size.1);
+                self.engine.resize(size.0, size.1);
+            }
             let delta_time = self.window.delta_time();
             lag += delta_time;
 
@@ -119,10 +151,24 @@ impl<N: Engine, E: EventHandler, P: PhysicsHooks> Game<N, E, P> {
             }
 
             self.world.late_update(delta_time);
+            sync_rendering_meshes(&mut self.world.world, &mut self.texture_loader)?;
             self.engine.render(
                 &mut self.world.world,
                 delta_time as f32 / self.frame_rate as f32,
+                &mut self.texture_loader,
             )?;
+            for (_e, canvas) in self.world.world.query::<&UiCanvas>().iter() {
+                ui_renderer.render_with_size(
+                    canvas.font(),
+                    &paint(&self.world.world, canvas),
+                    canvas.size(),
+                    &mut self.texture_loader,
+                )?;
+            }
+            for (_e, ui) in self.world.world.query_mut::<&mut Ui>() {
+                ui_renderer.render(ui.font(), &ui.draw_list(), &mut self.texture_loader)?;
+                ui.end_frame();
+            }
 
             self.window.swap_buffers();
         }
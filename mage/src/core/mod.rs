@@ -1,4 +1,5 @@
 pub mod game;
 pub mod system;
+pub mod time;
 pub mod window;
 pub mod world;
@@ -0,0 +1,22 @@
+use hecs::World;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> TimeScale {
+        TimeScale(1f32)
+    }
+}
+
+// Animation, sprite animation, tween and particle systems read the time scale
+// from the first entity holding a `TimeScale`, falling back to real time when
+// there is none.
+pub fn time_scale(world: &World) -> f32 {
+    world
+        .query::<&TimeScale>()
+        .iter()
+        .next()
+        .map(|(_, scale)| scale.0.max(0f32))
+        .unwrap_or(1f32)
+}
@@ -1,9 +1,12 @@
+use sdl2::controller::GameController;
 use sdl2::video::{GLContext, GLProfile, Window as SdlWindow};
-use sdl2::{EventPump, Sdl, TimerSubsystem};
+use sdl2::{EventPump, GameControllerSubsystem, Sdl, TimerSubsystem};
 
 use crate::MageError;
 
 pub struct Window {
+    _controllers: Vec<GameController>,
+    _game_controller: GameControllerSubsystem,
     last: u64,
     now: u64,
     sdl_context: Sdl,
@@ -13,6 +16,8 @@ pub struct Window {
 }
 
 impl Window {
+    // The default framebuffer stays single sampled, engines resolve their own
+    // multisample target into it.
     pub fn new(name: &str, width: u32, height: u32) -> Result<Window, MageError> {
         let sdl_context = sdl2::init()?;
         let video_subsystem = sdl_context.video()?;
@@ -32,7 +37,14 @@ impl Window {
         let _opengl = window.gl_create_context()?;
         gl::load_with(|s| video_subsystem.gl_get_proc_address(s) as *const std::os::raw::c_void);
         let sdl_timer = sdl_context.timer()?;
+        let game_controller = sdl_context.game_controller()?;
+        let controllers = (0..game_controller.num_joysticks()?)
+            .filter(|i| game_controller.is_game_controller(*i))
+            .filter_map(|i| game_controller.open(i).ok())
+            .collect();
         Ok(Window {
+            _controllers: controllers,
+            _game_controller: game_controller,
             _opengl,
             sdl_context,
             last: 0,
@@ -42,6 +54,10 @@ impl Window {
         })
     }
 
+    pub fn size(&self) -> (u32, u32) {
+        self.sdl_window.drawable_size()
+    }
+
     pub fn swap_buffers(&self) {
         self.sdl_window.gl_swap_window();
     }
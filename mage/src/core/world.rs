@@ -1,6 +1,8 @@
 use crate::core::system::System;
 use crate::physics::engine::PhysicsEngine;
 use crate::physics::scalable_shape::scale_shape;
+use crate::physics::PendingColliders;
+use crate::rendering::hierarchy::propagate_transforms;
 use crate::rendering::Transform;
 use approx::RelativeEq;
 use hecs::World as HecsWorld;
@@ -103,6 +105,9 @@ impl<E: EventHandler, P: PhysicsHooks> World<E, P> {
             );
         }
 
+        self.register_pending_colliders();
+        propagate_transforms(&self.world);
+
         for (entity, r) in self.physics_engine.iter_mut_rigidbody() {
             if let Some(mut transform) =
                 handle_result(self.world.query_one::<&mut Transform>(entity))
@@ -158,6 +163,24 @@ impl<E: EventHandler, P: PhysicsHooks> World<E, P> {
         }
         self.physics_engine.set_scales(new_scales);
     }
+
+    fn register_pending_colliders(&mut self) {
+        let entities = self
+            .world
+            .query_mut::<&PendingColliders>()
+            .into_iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for entity in entities {
+            if let Some(pending) = handle_result(self.world.remove_one::<PendingColliders>(entity))
+            {
+                self.physics_engine.remove_colliders(entity);
+                for collider in pending.0 {
+                    self.physics_engine.add_collider(entity, collider);
+                }
+            }
+        }
+    }
 }
 
 impl Default for World<(), ()> {
@@ -1,4 +1,5 @@
 use crate::core::system::System;
+use crate::ui::{Ui, UiCanvas};
 use crate::MageError;
 use hecs::World;
 use sdl2::event::Event;
@@ -61,8 +62,34 @@ impl System for InputSystem {
     }
 
     fn early_update(&self, world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        let events = self
+            .event_pumper
+            .borrow_mut()
+            .poll_iter()
+            .collect::<Vec<_>>();
+        let mut ui_wants_mouse = false;
+        let mut ui_wants_keyboard = false;
+        for (_e, ui) in world.query_mut::<&mut Ui>() {
+            ui.handle_events(&events);
+            ui_wants_mouse |= ui.wants_mouse();
+            ui_wants_keyboard |= ui.wants_keyboard();
+        }
+        let mut canvas_keys = vec![false; events.len()];
+        for (_e, canvas) in world.query_mut::<&mut UiCanvas>() {
+            for (wanted, event) in canvas_keys.iter_mut().zip(events.iter()) {
+                *wanted |= canvas.wants_key(event);
+            }
+            canvas.handle_events(&events);
+            ui_wants_mouse |= canvas.wants_mouse();
+        }
         let mut events_by_type = HashMap::new();
-        for event in self.event_pumper.borrow_mut().poll_iter() {
+        for (event, canvas_key) in events.into_iter().zip(canvas_keys) {
+            match &event {
+                e if ui_wants_mouse && e.is_mouse() => continue,
+                Event::KeyDown { .. } | Event::TextInput { .. } if ui_wants_keyboard => continue,
+                _ if canvas_key => continue,
+                _ => {}
+            }
             let event_type = InputType::from(&event);
             if !events_by_type.contains_key(&event_type) {
                 events_by_type.insert(event_type.clone(), vec![]);
@@ -4,8 +4,14 @@ use std::error::Error;
 
 pub type MageError = Box<dyn Error>;
 
+pub mod animation;
 pub mod core;
 pub mod gameplay;
+pub mod particles;
 pub mod physics;
 pub mod rendering;
 pub mod resources;
+pub mod terrain;
+pub mod tilemap;
+pub mod tween;
+pub mod ui;
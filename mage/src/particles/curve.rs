@@ -0,0 +1,79 @@
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Curve<T> {
+    keys: Vec<(f32, T)>,
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>> Curve<T> {
+    pub fn constant(value: T) -> Curve<T> {
+        Curve {
+            keys: vec![(0f32, value)],
+        }
+    }
+
+    pub fn linear(from: T, to: T) -> Curve<T> {
+        Curve {
+            keys: vec![(0f32, from), (1f32, to)],
+        }
+    }
+
+    pub fn with_key(mut self, time: f32, value: T) -> Curve<T> {
+        let time = time.clamp(0f32, 1f32);
+        let index = self.keys.partition_point(|(key, _)| *key <= time);
+        self.keys.insert(index, (time, value));
+        self
+    }
+
+    pub fn keys(&self) -> &[(f32, T)] {
+        &self.keys
+    }
+
+    pub fn sample(&self, time: f32) -> T {
+        let time = time.clamp(0f32, 1f32);
+        let next = self.keys.partition_point(|(key, _)| *key <= time);
+        if next == 0 {
+            return self.keys[0].1;
+        }
+        if next == self.keys.len() {
+            return self.keys[next - 1].1;
+        }
+        let (from_time, from) = self.keys[next - 1];
+        let (to_time, to) = self.keys[next];
+        from + (to - from) * ((time - from_time) / (to_time - from_time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Curve;
+
+    #[test]
+    fn sample_interpolates_between_keys() {
+        let curve = Curve::linear(0f32, 2f32).with_key(0.5f32, 4f32);
+        assert_eq!(curve.sample(0.25f32), 2f32);
+        assert_eq!(curve.sample(0.5f32), 4f32);
+        assert_eq!(curve.sample(0.75f32), 3f32);
+    }
+
+    #[test]
+    fn sample_clamps_outside_the_keys() {
+        let curve = Curve::constant(1f32).with_key(0.5f32, 3f32);
+        assert_eq!(curve.sample(-1f32), 1f32);
+        assert_eq!(curve.sample(2f32), 3f32);
+        assert_eq!(Curve::constant(5f32).sample(0.5f32), 5f32);
+    }
+
+    #[test]
+    fn with_key_keeps_keys_sorted_and_clamped() {
+        let curve = Curve::linear(0f32, 1f32)
+            .with_key(2f32, 3f32)
+            .with_key(0.25f32, 4f32);
+        let times = curve
+            .keys()
+            .iter()
+            .map(|(time, _)| *time)
+            .collect::<Vec<_>>();
+        assert_eq!(times, vec![0f32, 0.25f32, 1f32, 1f32]);
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+
+use nalgebra::{UnitQuaternion, Vector3, Vector4};
+use russimp::texture::TextureType;
+
+use crate::rendering::model::mesh::{TextureInfo, TextureSource};
+use crate::rendering::opengl::BlendMode;
+use crate::rendering::Transform;
+
+pub(crate) const PARTICLE_SIZE: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Burst {
+    pub count: u32,
+    pub time: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextureSheet {
+    pub columns: u32,
+    pub cycles: f32,
+    pub frames: u32,
+    pub rows: u32,
+}
+
+impl TextureSheet {
+    pub fn new(columns: u32, rows: u32) -> TextureSheet {
+        TextureSheet {
+            columns: columns.max(1),
+            cycles: 1f32,
+            frames: columns.max(1) * rows.max(1),
+            rows: rows.max(1),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ParticleSimulation {
+    #[default]
+    Cpu,
+    TransformFeedback,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Particle {
+    pub(crate) age: f32,
+    pub(crate) lifetime: f32,
+    pub(crate) position: Vector3<f32>,
+    pub(crate) velocity: Vector3<f32>,
+}
+
+impl Particle {
+    fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+
+    // Must stay in sync with particle-update-vertex.glsl, so both simulation
+    // paths move particles the same way.
+    fn step(&mut self, delta: f32, gravity: &Vector3<f32>) {
+        self.velocity += gravity * delta;
+        self.position += self.velocity * delta;
+        self.age += delta;
+    }
+
+    pub(crate) fn data(&self) -> [f32; PARTICLE_SIZE] {
+        [
+            self.position.x,
+            self.position.y,
+            self.position.z,
+            self.velocity.x,
+            self.velocity.y,
+            self.velocity.z,
+            self.age,
+            self.lifetime,
+        ]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParticleEmitter {
+    pub blend_mode: BlendMode,
+    pub bursts: Vec<Burst>,
+    pub color_over_life: Curve<Vector4<f32>>,
+    pub cone_angle: f32,
+    pub direction: Vector3<f32>,
+    pub duration: f32,
+    elapsed: f32,
+    emitting: bool,
+    pub gravity: Vector3<f32>,
+    pub lifetime: (f32, f32),
+    pub looping: bool,
+    pub max_particles: usize,
+    // With transform feedback the GPU owns the live particles, so this only
+    // holds the ones spawned since they were last uploaded.
+    pub(crate) particles: Vec<Particle>,
+    pub paused: bool,
+    pub(crate) pending_time: f32,
+    rate_accumulator: f32,
+    requested: u32,
+    seed: u32,
+    pub simulation: ParticleSimulation,
+    pub size_over_life: Curve<f32>,
+    pub spawn_rate: f32,
+    pub speed: (f32, f32),
+    pub texture: TextureInfo,
+    pub texture_sheet: Option<TextureSheet>,
+    // Applied on top of the world `TimeScale`, for slowing a single emitter
+    pub time_scale: f32,
+}
+
+impl ParticleEmitter {
+    pub fn new(max_particles: usize) -> ParticleEmitter {
+        ParticleEmitter {
+            blend_mode: BlendMode::Alpha,
+            bursts: vec![],
+            color_over_life: Curve::constant(Vector4::new(1f32, 1f32, 1f32, 1f32)),
+            cone_angle: PI / 8f32,
+            direction: Vector3::y(),
+            duration: 1f32,
+            elapsed: 0f32,
+            emitting: true,
+            gravity: Vector3::zeros(),
+            lifetime: (1f32, 1f32),
+            looping: true,
+            max_particles,
+            particles: vec![],
+            paused: false,
+            pending_time: 0f32,
+            rate_accumulator: 0f32,
+            requested: 0,
+            seed: 0x2545_f491,
+            simulation: ParticleSimulation::Cpu,
+            size_over_life: Curve::constant(1f32),
+            spawn_rate: 10f32,
+            speed: (1f32, 1f32),
+            texture: TextureInfo {
+                id: 0,
+                texture_type: TextureType::Diffuse,
+                source: TextureSource::Color(Vector3::new(255, 255, 255)),
+                parameters: HashMap::new(),
+            },
+            texture_sheet: None,
+            time_scale: 1f32,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u32) -> ParticleEmitter {
+        self.seed = seed.max(1);
+        self
+    }
+
+    pub fn play(&mut self) {
+        self.emitting = true;
+        self.paused = false;
+        self.elapsed = 0f32;
+        self.rate_accumulator = 0f32;
+    }
+
+    pub fn stop(&mut self) {
+        self.emitting = false;
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.requested = 0;
+    }
+
+    pub fn emit(&mut self, count: u32) {
+        self.requested += count;
+    }
+
+    pub fn is_emitting(&self) -> bool {
+        self.emitting
+    }
+
+    // Only meaningful for the CPU simulation, the GPU keeps its own count.
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn advance(&mut self, delta: f32, transform: &Transform) {
+        if self.paused {
+            return;
+        }
+        let delta = delta * self.time_scale.max(0f32);
+        for particle in self.particles.iter_mut() {
+            particle.step(delta, &self.gravity);
+        }
+        self.particles.retain(Particle::is_alive);
+        if self.simulation == ParticleSimulation::TransformFeedback {
+            self.pending_time += delta;
+        }
+
+        let mut count = std::mem::take(&mut self.requested);
+        if self.emitting {
+            self.rate_accumulator += self.spawn_rate.max(0f32) * delta;
+            let spawned = self.rate_accumulator.floor();
+            self.rate_accumulator -= spawned;
+            count += spawned as u32 + self.advance_clock(delta);
+        }
+        self.spawn(count, transform);
+    }
+
+    fn advance_clock(&mut self, delta: f32) -> u32 {
+        let duration = self.duration.max(f32::EPSILON);
+        let to = self.elapsed + delta;
+        let mut count = self.bursts_between(self.elapsed, to.min(duration));
+        if to < duration {
+            self.elapsed = to;
+        } else if self.looping {
+            self.elapsed = (to - duration) % duration;
+            count += self.bursts_between(0f32, self.elapsed);
+        } else {
+            self.elapsed = duration;
+            self.emitting = false;
+        }
+        count
+    }
+
+    fn bursts_between(&self, from: f32, to: f32) -> u32 {
+        self.bursts
+            .iter()
+            .filter(|burst| from <= burst.time && burst.time < to)
+            .map(|burst| burst.count)
+            .sum()
+    }
+
+    fn spawn(&mut self, count: u32, transform: &Transform) {
+        let available = self.max_particles.saturating_sub(self.particles.len());
+        let axis = transform.rotation
+            * self
+                .direction
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(Vector3::y);
+        let to_axis = UnitQuaternion::rotation_between(&Vector3::y(), &axis)
+            .unwrap_or_else(|| UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI));
+        for _ in 0..(count as usize).min(available) {
+            let cos_theta = 1f32 - self.random() * (1f32 - self.cone_angle.clamp(0f32, PI).cos());
+            let sin_theta = (1f32 - cos_theta * cos_theta).max(0f32).sqrt();
+            let phi = self.random() * TAU;
+            let direction =
+                to_axis * Vector3::new(sin_theta * phi.cos(), cos_theta, sin_theta * phi.sin());
+            let speed = self.random_between(self.speed);
+            let lifetime = self.random_between(self.lifetime);
+            self.particles.push(Particle {
+                age: 0f32,
+                lifetime,
+                position: transform.position,
+                velocity: direction * speed,
+            });
+        }
+    }
+
+    fn random_between(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + (max - min) * self.random()
+    }
+
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as f32 / u32::MAX as f32
+    }
+}
+
+mod curve;
+pub use curve::Curve;
+
+mod renderer;
+pub use renderer::ParticleRenderer;
+
+mod system;
+pub use system::ParticleSystem;
+
+#[cfg(test)]
+mod tests {
+    use super::{Burst, ParticleEmitter};
+    use crate::rendering::Transform;
+
+    fn emitter(looping: bool) -> ParticleEmitter {
+        let mut emitter = ParticleEmitter::new(100);
+        emitter.bursts = vec![
+            Burst {
+                count: 3,
+                time: 0.25f32,
+            },
+            Burst {
+                count: 5,
+                time: 0.75f32,
+            },
+        ];
+        emitter.lifetime = (10f32, 10f32);
+        emitter.looping = looping;
+        emitter.spawn_rate = 0f32;
+        emitter
+    }
+
+    #[test]
+    fn bursts_fire_once_their_time_is_reached() {
+        let mut emitter = emitter(true);
+        let transform = Transform::identity();
+        emitter.advance(0.2f32, &transform);
+        assert_eq!(emitter.particle_count(), 0);
+        emitter.advance(0.1f32, &transform);
+        assert_eq!(emitter.particle_count(), 3);
+        emitter.advance(0.5f32, &transform);
+        assert_eq!(emitter.particle_count(), 8);
+        // Wrapping past the duration replays the bursts from the start
+        emitter.advance(0.5f32, &transform);
+        assert_eq!(emitter.particle_count(), 11);
+    }
+
+    #[test]
+    fn bursts_stop_after_a_single_cycle() {
+        let mut emitter = emitter(false);
+        let transform = Transform::identity();
+        emitter.advance(1.5f32, &transform);
+        assert_eq!(emitter.particle_count(), 8);
+        assert!(!emitter.is_emitting());
+        emitter.advance(1f32, &transform);
+        assert_eq!(emitter.particle_count(), 8);
+    }
+
+    #[test]
+    fn emitter_time_scale_slows_the_clock() {
+        let mut emitter = emitter(true);
+        emitter.time_scale = 0.5f32;
+        emitter.advance(0.4f32, &Transform::identity());
+        assert_eq!(emitter.particle_count(), 0);
+        emitter.advance(0.2f32, &Transform::identity());
+        assert_eq!(emitter.particle_count(), 3);
+    }
+}
@@ -0,0 +1,315 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use hecs::{Entity, World};
+use nalgebra::Vector3;
+
+use crate::particles::{ParticleEmitter, ParticleSimulation, TextureSheet, PARTICLE_SIZE};
+use crate::rendering::engine::SHADER_LIBRARY;
+use crate::rendering::opengl::buffer::{Buffer, BufferType, BufferUsage};
+use crate::rendering::opengl::program::Program;
+use crate::rendering::opengl::shader::ShaderType;
+use crate::rendering::opengl::vertex_array::{DataType, VertexArray};
+use crate::rendering::opengl::{
+    begin_transform_feedback, disable, draw_arrays_instanced, draw_points, enable,
+    end_transform_feedback, set_blend_mode, BlendMode, DrawingMode, Feature,
+};
+use crate::rendering::transparent::{begin_transparent, end_transparent};
+use crate::rendering::Transform;
+use crate::resources::shader::ShaderLoader;
+use crate::resources::texture::TextureLoader;
+use crate::MageError;
+
+const VERTEX_SHADER: &str = "particle-vertex.glsl";
+const FRAGMENT_SHADER: &str = "particle-fragment.glsl";
+const UPDATE_VERTEX_SHADER: &str = "particle-update-vertex.glsl";
+const VARYINGS: [&str; 4] = ["outPosition", "outVelocity", "outAge", "outLifetime"];
+const CURVE_SAMPLES: usize = 16;
+const PARTICLE_UNIT: u32 = 0;
+const QUAD: [f32; 8] = [
+    -0.5f32, -0.5f32, 0.5f32, -0.5f32, -0.5f32, 0.5f32, 0.5f32, 0.5f32,
+];
+
+// Position, velocity, age and lifetime, in the same order as `Particle::data`.
+const PARTICLE_ATTRIBUTES: [(u32, u32); 4] = [(3, 0), (3, 3), (1, 6), (1, 7)];
+
+fn set_particle_attributes(first_location: u32, divisor: u32) {
+    for (i, (components, offset)) in PARTICLE_ATTRIBUTES.iter().enumerate() {
+        let location = first_location + i as u32;
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            location,
+            PARTICLE_SIZE as u32,
+            *components,
+            *offset,
+            false,
+        );
+        VertexArray::set_vertex_attrib_divisor(location, divisor);
+    }
+}
+
+struct ParticleBuffers {
+    buffers: Vec<Buffer>,
+    capacity: usize,
+    count: usize,
+    current: usize,
+    cursor: usize,
+    render_arrays: Vec<VertexArray>,
+    simulation: ParticleSimulation,
+    update_arrays: Vec<VertexArray>,
+}
+
+impl ParticleBuffers {
+    // The transform feedback path ping-pongs between two buffers holding a
+    // fixed ring of particles, the CPU path streams into a single one.
+    fn new(quad: &Buffer, simulation: ParticleSimulation, capacity: usize) -> ParticleBuffers {
+        let capacity = capacity.max(1);
+        let (count, usage) = match simulation {
+            ParticleSimulation::Cpu => (1, BufferUsage::StreamDraw),
+            ParticleSimulation::TransformFeedback => (2, BufferUsage::DynamicCopy),
+        };
+        let buffers = Buffer::multiple(vec![BufferType::Array; count]);
+        let mut render_arrays = vec![];
+        let mut update_arrays = vec![];
+        for buffer in buffers.iter() {
+            buffer.bind();
+            buffer.set_data(&vec![0f32; capacity * PARTICLE_SIZE], usage);
+
+            let render_array = VertexArray::new();
+            render_array.bind();
+            quad.bind();
+            VertexArray::set_vertex_attrib::<f32>(DataType::Float, 0, 2, false);
+            buffer.bind();
+            set_particle_attributes(1, 1);
+            VertexArray::unbind();
+            render_arrays.push(render_array);
+
+            if simulation == ParticleSimulation::TransformFeedback {
+                let update_array = VertexArray::new();
+                update_array.bind();
+                buffer.bind();
+                set_particle_attributes(0, 0);
+                VertexArray::unbind();
+                update_arrays.push(update_array);
+            }
+            buffer.unbind();
+        }
+        ParticleBuffers {
+            buffers,
+            capacity,
+            count: 0,
+            current: 0,
+            cursor: 0,
+            render_arrays,
+            simulation,
+            update_arrays,
+        }
+    }
+
+    fn matches(&self, emitter: &ParticleEmitter) -> bool {
+        self.simulation == emitter.simulation
+            && (self.simulation == ParticleSimulation::Cpu
+                || self.capacity == emitter.max_particles.max(1))
+    }
+
+    fn reserve(&mut self, particles: usize) {
+        if particles <= self.capacity {
+            return;
+        }
+        self.capacity = particles.next_power_of_two();
+        self.buffers[0].bind();
+        self.buffers[0].allocate_data_with_usage::<f32>(
+            self.capacity * PARTICLE_SIZE,
+            BufferUsage::StreamDraw,
+        );
+        self.buffers[0].unbind();
+    }
+}
+
+pub struct ParticleRenderer {
+    buffers: RefCell<HashMap<Entity, ParticleBuffers>>,
+    program: Program,
+    quad: Buffer,
+    update_program: Program,
+}
+
+impl ParticleRenderer {
+    pub fn new() -> Result<ParticleRenderer, MageError> {
+        let shader_loader = ShaderLoader::new(&SHADER_LIBRARY)?;
+        let program = Program::new(
+            shader_loader.load(ShaderType::Vertex, VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, FRAGMENT_SHADER)?,
+        )?;
+        let update_program = Program::with_transform_feedback(
+            shader_loader.load(ShaderType::Vertex, UPDATE_VERTEX_SHADER)?,
+            &VARYINGS,
+        )?;
+        let quad = Buffer::new(BufferType::Array);
+        quad.bind();
+        quad.set_data(&QUAD, BufferUsage::StaticDraw);
+        quad.unbind();
+        Ok(ParticleRenderer {
+            buffers: RefCell::new(HashMap::new()),
+            program,
+            quad,
+            update_program,
+        })
+    }
+
+    pub fn render(
+        &self,
+        world: &mut World,
+        camera_position: Vector3<f32>,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
+        let mut buffers = self.buffers.borrow_mut();
+        let mut emitters = world
+            .query::<(&ParticleEmitter, Option<&Transform>)>()
+            .iter()
+            .map(|(e, (_, transform))| {
+                let distance = transform
+                    .map(|transform| (transform.position - camera_position).norm_squared())
+                    .unwrap_or(0f32);
+                (e, distance)
+            })
+            .collect::<Vec<_>>();
+        let alive = emitters.iter().map(|(e, _)| *e).collect::<HashSet<_>>();
+        buffers.retain(|e, _| alive.contains(e));
+        if emitters.is_empty() {
+            return Ok(());
+        }
+        emitters.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+        begin_transparent();
+        for (e, _) in emitters {
+            let mut emitter = world.get_mut::<ParticleEmitter>(e)?;
+            if !buffers
+                .get(&e)
+                .map(|state| state.matches(&emitter))
+                .unwrap_or(false)
+            {
+                let state =
+                    ParticleBuffers::new(&self.quad, emitter.simulation, emitter.max_particles);
+                buffers.insert(e, state);
+            }
+            let state = buffers.get_mut(&e).unwrap();
+            match emitter.simulation {
+                ParticleSimulation::Cpu => self.upload(state, &emitter, camera_position),
+                ParticleSimulation::TransformFeedback => self.simulate(state, &mut emitter),
+            }
+            if state.count == 0 {
+                continue;
+            }
+            let texture = texture_loader.load_texture_2d(&emitter.texture)?;
+            self.program.use_program();
+            self.attach_emitter(&emitter);
+            set_blend_mode(emitter.blend_mode);
+            texture.bind(PARTICLE_UNIT);
+            state.render_arrays[state.current].bind();
+            draw_arrays_instanced(DrawingMode::TriangleStrip, 4, state.count as u32);
+            VertexArray::unbind();
+        }
+        end_transparent();
+        Ok(())
+    }
+
+    fn attach_emitter(&self, emitter: &ParticleEmitter) {
+        for i in 0..CURVE_SAMPLES {
+            let time = i as f32 / (CURVE_SAMPLES - 1) as f32;
+            self.program.set_uniform_v4(
+                &format!("colorOverLife[{}]", i),
+                emitter.color_over_life.sample(time),
+            );
+            self.program.set_uniform_f1(
+                &format!("sizeOverLife[{}]", i),
+                emitter.size_over_life.sample(time),
+            );
+        }
+        let sheet = emitter
+            .texture_sheet
+            .unwrap_or_else(|| TextureSheet::new(1, 1));
+        let columns = sheet.columns.max(1);
+        let rows = sheet.rows.max(1);
+        self.program.set_uniform_i1("sheetColumns", columns as i32);
+        self.program.set_uniform_i1("sheetRows", rows as i32);
+        self.program
+            .set_uniform_i1("sheetFrames", sheet.frames.clamp(1, columns * rows) as i32);
+        self.program.set_uniform_f1("sheetCycles", sheet.cycles);
+        self.program
+            .set_uniform_i1("particle", PARTICLE_UNIT as i32);
+    }
+
+    fn upload(
+        &self,
+        state: &mut ParticleBuffers,
+        emitter: &ParticleEmitter,
+        camera_position: Vector3<f32>,
+    ) {
+        let mut particles = emitter.particles.iter().collect::<Vec<_>>();
+        state.count = particles.len();
+        if particles.is_empty() {
+            return;
+        }
+        // Alpha blending needs back to front order, additive blending does not.
+        if emitter.blend_mode == BlendMode::Alpha {
+            let distance = |position: &Vector3<f32>| (position - camera_position).norm_squared();
+            particles.sort_by(|a, b| {
+                distance(&b.position)
+                    .partial_cmp(&distance(&a.position))
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+        let data = particles
+            .iter()
+            .flat_map(|particle| particle.data())
+            .collect::<Vec<f32>>();
+        state.reserve(particles.len());
+        state.buffers[0].bind();
+        state.buffers[0].set_sub_data(0, data.len(), &data);
+        state.buffers[0].unbind();
+    }
+
+    fn simulate(&self, state: &mut ParticleBuffers, emitter: &mut ParticleEmitter) {
+        let delta = std::mem::take(&mut emitter.pending_time);
+        if delta > 0f32 {
+            let target = 1 - state.current;
+            self.update_program.use_program();
+            self.update_program.set_uniform_f1("deltaTime", delta);
+            self.update_program
+                .set_uniform_v3("gravity", emitter.gravity);
+            state.update_arrays[state.current].bind();
+            state.buffers[target].bind_transform_feedback(0);
+            enable(Feature::RasterizerDiscard);
+            begin_transform_feedback();
+            draw_points(state.capacity as u32);
+            end_transform_feedback();
+            disable(Feature::RasterizerDiscard);
+            VertexArray::unbind();
+            state.current = target;
+        }
+
+        // New particles overwrite the oldest slots of the ring.
+        let spawned = std::mem::take(&mut emitter.particles)
+            .iter()
+            .flat_map(|particle| particle.data())
+            .collect::<Vec<f32>>();
+        let buffer = &state.buffers[state.current];
+        buffer.bind();
+        let mut written = 0;
+        while written < spawned.len() {
+            let slots =
+                (state.capacity - state.cursor).min((spawned.len() - written) / PARTICLE_SIZE);
+            let length = slots * PARTICLE_SIZE;
+            buffer.set_sub_data(
+                state.cursor * PARTICLE_SIZE,
+                length,
+                &spawned[written..written + length],
+            );
+            written += length;
+            state.cursor = (state.cursor + slots) % state.capacity;
+        }
+        buffer.unbind();
+        state.count = state.capacity;
+    }
+}
@@ -0,0 +1,35 @@
+use hecs::World;
+
+use crate::core::system::System;
+use crate::core::time::time_scale;
+use crate::particles::ParticleEmitter;
+use crate::rendering::Transform;
+use crate::MageError;
+
+pub struct ParticleSystem;
+
+impl System for ParticleSystem {
+    fn name(&self) -> &str {
+        "Particles"
+    }
+
+    fn start(&self, _world: &mut World) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn early_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn update(&self, world: &mut World, delta_time: u64) -> Result<(), MageError> {
+        let delta = delta_time as f32 / 1000f32 * time_scale(world);
+        for (_e, (emitter, transform)) in world.query_mut::<(&mut ParticleEmitter, &Transform)>() {
+            emitter.advance(delta, transform);
+        }
+        Ok(())
+    }
+
+    fn late_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+}
@@ -109,6 +109,25 @@ impl<E: EventHandler, P: PhysicsHooks> PhysicsEngine<E, P> {
             .insert(collider_handle, Vector3::new(1.0, 1.0, 1.0));
     }
 
+    pub fn remove_colliders(&mut self, entity: Entity) {
+        let handles = self
+            .colliders
+            .iter()
+            .filter(|(_, e)| **e == entity)
+            .map(|(h, _)| *h)
+            .collect::<Vec<_>>();
+        for handle in handles {
+            self.collider_set.remove(
+                handle,
+                &mut self.island_manager,
+                &mut self.rigidbody_set,
+                true,
+            );
+            self.colliders.remove(&handle);
+            self.collider_scale.remove(&handle);
+        }
+    }
+
     pub fn set_scales(&mut self, scales: Vec<(ColliderHandle, Vector3<f32>)>) {
         for (handle, scale) in scales {
             self.collider_scale.insert(handle, scale);
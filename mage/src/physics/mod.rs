@@ -1,2 +1,7 @@
+use rapier3d::geometry::Collider;
+
 pub mod engine;
 pub mod scalable_shape;
+
+#[derive(Clone)]
+pub struct PendingColliders(pub Vec<Collider>);
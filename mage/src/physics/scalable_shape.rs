@@ -3,7 +3,8 @@ use nalgebra::Vector3;
 use rapier3d::geometry::{Collider, SharedShape};
 use rapier3d::parry::either::Either;
 use rapier3d::parry::shape::{
-    Ball, Capsule, Cone, ConvexPolyhedron, Cylinder, Polyline, TriMesh, Triangle, TypedShape,
+    Ball, Capsule, Cone, ConvexPolyhedron, Cylinder, HeightField, Polyline, TriMesh, Triangle,
+    TypedShape,
 };
 use thiserror::Error;
 
@@ -23,6 +24,7 @@ pub enum ScalableTypedShape {
     Cone(Cone),
     ConvexPolyhedron(ConvexPolyhedron),
     Cylinder(Cylinder),
+    HeightField(HeightField),
     Polyline(Polyline),
     TriMesh(TriMesh),
     Triangle(Triangle),
@@ -36,6 +38,7 @@ impl ScalableTypedShape {
             ScalableTypedShape::Cone(s) => collider.set_shape(SharedShape::new(s)),
             ScalableTypedShape::ConvexPolyhedron(s) => collider.set_shape(SharedShape::new(s)),
             ScalableTypedShape::Cylinder(s) => collider.set_shape(SharedShape::new(s)),
+            ScalableTypedShape::HeightField(s) => collider.set_shape(SharedShape::new(s)),
             ScalableTypedShape::Polyline(s) => collider.set_shape(SharedShape::new(s)),
             ScalableTypedShape::TriMesh(s) => collider.set_shape(SharedShape::new(s)),
             ScalableTypedShape::Triangle(s) => collider.set_shape(SharedShape::new(s)),
@@ -76,6 +79,9 @@ pub fn scale_shape<'a>(
                 Either::Right(o) => ScalableTypedShape::ConvexPolyhedron(o),
             })
             .ok_or_else(|| ScalableShapeError::DegeneratedNormalsWhileScaling.into()),
+        TypedShape::HeightField(h) => {
+            Ok(ScalableTypedShape::HeightField((*h).clone().scaled(scale)))
+        }
         TypedShape::Polyline(p) => Ok(ScalableTypedShape::Polyline((*p).clone().scaled(scale))),
         TypedShape::Triangle(t) => Ok(ScalableTypedShape::Triangle(t.scaled(scale))),
         TypedShape::TriMesh(t) => Ok(ScalableTypedShape::TriMesh((*t).clone().scaled(scale))),
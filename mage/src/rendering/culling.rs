@@ -0,0 +1,95 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use nalgebra::{Matrix4, Vector3, Vector4};
+
+use crate::gameplay::camera::Camera;
+use crate::rendering::model::bounds::{Aabb, BoundingSphere, Bounds};
+
+#[derive(Clone, Debug)]
+pub struct Frustum {
+    planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    pub fn from_matrix(matrix: &Matrix4<f32>) -> Frustum {
+        let row = |i: usize| matrix.row(i).transpose();
+        let planes = [
+            row(3) + row(0),
+            row(3) - row(0),
+            row(3) + row(1),
+            row(3) - row(1),
+            row(3) + row(2),
+            row(3) - row(2),
+        ]
+        .map(|plane| plane / plane.xyz().norm());
+        Frustum { planes }
+    }
+
+    pub fn from_camera<C: Camera>(camera: &C) -> Frustum {
+        Frustum::from_matrix(&(camera.projection() * camera.look_at_matrix()))
+    }
+
+    pub fn contains_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.xyz().dot(&sphere.center) + plane.w >= -sphere.radius)
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            let normal = plane.xyz();
+            let positive = Vector3::new(
+                if normal.x >= 0f32 {
+                    aabb.max.x
+                } else {
+                    aabb.min.x
+                },
+                if normal.y >= 0f32 {
+                    aabb.max.y
+                } else {
+                    aabb.min.y
+                },
+                if normal.z >= 0f32 {
+                    aabb.max.z
+                } else {
+                    aabb.min.z
+                },
+            );
+            normal.dot(&positive) + plane.w >= 0f32
+        })
+    }
+
+    pub fn is_visible(&self, bounds: &Bounds, model: &Matrix4<f32>) -> bool {
+        self.contains_sphere(&bounds.sphere.transform(model))
+            && self.intersects_aabb(&bounds.aabb.transform(model))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CullingStatistics {
+    culled: AtomicUsize,
+    drawn: AtomicUsize,
+}
+
+impl CullingStatistics {
+    pub fn culled(&self) -> usize {
+        self.culled.load(Ordering::Relaxed)
+    }
+
+    pub fn drawn(&self) -> usize {
+        self.drawn.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record(&self, visible: bool) {
+        if visible {
+            self.drawn.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.culled.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        self.culled.store(0, Ordering::Relaxed);
+        self.drawn.store(0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use nalgebra::{Matrix4, Perspective3, Point3, Vector3};
+
+use crate::rendering::model::cube::unit_cube;
+use crate::rendering::model::mesh::TextureInfo;
+use crate::rendering::model::plane::vertical_plane;
+use crate::rendering::opengl::frame_buffer::FrameBuffer;
+use crate::rendering::opengl::program::Program;
+use crate::rendering::opengl::shader::ShaderType;
+use crate::rendering::opengl::texture::{
+    Texture, TextureDimension, TextureFormat, TextureParameter, TextureParameterValue,
+};
+use crate::rendering::opengl::{clear, set_viewport, DrawingBuffer};
+use crate::resources::shader::ShaderLoader;
+use crate::resources::texture::TextureLoader;
+use crate::MageError;
+
+const CAPTURE_VERTEX_SHADER: &str = "cubemap-capture-vertex.glsl";
+const IRRADIANCE_FRAGMENT_SHADER: &str = "irradiance-fragment.glsl";
+const PREFILTER_FRAGMENT_SHADER: &str = "prefilter-fragment.glsl";
+const BRDF_VERTEX_SHADER: &str = "brdf-vertex.glsl";
+const BRDF_FRAGMENT_SHADER: &str = "brdf-fragment.glsl";
+
+const IRRADIANCE_SIZE: u32 = 32;
+const PREFILTER_SIZE: u32 = 128;
+const PREFILTER_MIP_LEVELS: u32 = 5;
+const BRDF_LUT_SIZE: u32 = 512;
+
+const IRRADIANCE_UNIT: u32 = 13;
+const PREFILTER_UNIT: u32 = 14;
+const BRDF_LUT_UNIT: u32 = 15;
+
+fn capture_views() -> [Matrix4<f32>; 6] {
+    let eye = Point3::origin();
+    [
+        Matrix4::look_at_rh(&eye, &Point3::new(1.0, 0.0, 0.0), &-Vector3::y()),
+        Matrix4::look_at_rh(&eye, &Point3::new(-1.0, 0.0, 0.0), &-Vector3::y()),
+        Matrix4::look_at_rh(&eye, &Point3::new(0.0, 1.0, 0.0), &Vector3::z()),
+        Matrix4::look_at_rh(&eye, &Point3::new(0.0, -1.0, 0.0), &-Vector3::z()),
+        Matrix4::look_at_rh(&eye, &Point3::new(0.0, 0.0, 1.0), &-Vector3::y()),
+        Matrix4::look_at_rh(&eye, &Point3::new(0.0, 0.0, -1.0), &-Vector3::y()),
+    ]
+}
+
+fn set_linear_clamped(texture: &Texture) {
+    for (parameter, value) in [
+        (
+            TextureParameter::TextureWrapS,
+            TextureParameterValue::ClampToEdge,
+        ),
+        (
+            TextureParameter::TextureWrapT,
+            TextureParameterValue::ClampToEdge,
+        ),
+        (
+            TextureParameter::TextureMinFilter,
+            TextureParameterValue::Linear,
+        ),
+        (
+            TextureParameter::TextureMagFilter,
+            TextureParameterValue::Linear,
+        ),
+    ] {
+        texture.set_parameter(parameter, value);
+    }
+}
+
+// Bound in place of the environment maps when there is no environment, so the
+// cube map samplers never share a unit with the 2D material textures.
+pub(crate) struct EmptyEnvironment {
+    brdf_lut: Texture,
+    cube_map: Texture,
+}
+
+impl EmptyEnvironment {
+    pub(crate) fn new() -> EmptyEnvironment {
+        let black = [0u8; 4];
+        let cube_map = Texture::new(TextureDimension::CubeMap);
+        cube_map.just_bind();
+        for face in 0..6 {
+            cube_map.set_cube_map_face(face, 1, 1, &black);
+        }
+        set_linear_clamped(&cube_map);
+        cube_map.unbind();
+        let brdf_lut = Texture::new(TextureDimension::Texture2D);
+        brdf_lut.just_bind();
+        brdf_lut.set_image_2d(1, 1, &black, TextureFormat::UnsignedByteWithAlpha);
+        set_linear_clamped(&brdf_lut);
+        brdf_lut.unbind();
+        EmptyEnvironment { brdf_lut, cube_map }
+    }
+
+    pub(crate) fn attach_to_program(&self, program: &Program) {
+        self.cube_map.bind(IRRADIANCE_UNIT);
+        program.set_uniform_i1("irradianceMap", IRRADIANCE_UNIT as i32);
+        self.cube_map.bind(PREFILTER_UNIT);
+        program.set_uniform_i1("prefilterMap", PREFILTER_UNIT as i32);
+        self.brdf_lut.bind(BRDF_LUT_UNIT);
+        program.set_uniform_i1("brdfLUT", BRDF_LUT_UNIT as i32);
+    }
+}
+
+pub(crate) struct ImageBasedLighting {
+    brdf_lut: FrameBuffer,
+    _environment: Arc<Texture>,
+    irradiance: FrameBuffer,
+    prefilter: FrameBuffer,
+}
+
+impl ImageBasedLighting {
+    pub(crate) fn new(
+        environment: &TextureInfo,
+        shader_loader: &ShaderLoader,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<ImageBasedLighting, MageError> {
+        let environment_map = texture_loader.load_texture_cubemap(environment)?;
+        let cube = unit_cube().to_rendering_mesh(texture_loader)?;
+        let projection = Perspective3::new(1f32, 90f32.to_radians(), 0.1, 10f32).to_homogeneous();
+        let views = capture_views();
+
+        let irradiance_program = Program::new(
+            shader_loader.load(ShaderType::Vertex, CAPTURE_VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, IRRADIANCE_FRAGMENT_SHADER)?,
+        )?;
+        let irradiance = FrameBuffer::cube_map(IRRADIANCE_SIZE, IRRADIANCE_SIZE, false);
+        irradiance_program.use_program();
+        irradiance_program.set_uniform_matrix4("projection", projection);
+        irradiance_program.set_uniform_i1("environmentMap", 0);
+        environment_map.bind(0);
+        set_viewport(0, 0, IRRADIANCE_SIZE, IRRADIANCE_SIZE);
+        irradiance.bind();
+        for (face, view) in views.iter().enumerate() {
+            irradiance_program.set_uniform_matrix4("view", *view);
+            irradiance.attach_cube_map_face(face as u32, 0);
+            clear(&[DrawingBuffer::Color, DrawingBuffer::Depth]);
+            cube.draw();
+        }
+        FrameBuffer::unbind();
+
+        let prefilter_program = Program::new(
+            shader_loader.load(ShaderType::Vertex, CAPTURE_VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, PREFILTER_FRAGMENT_SHADER)?,
+        )?;
+        let prefilter = FrameBuffer::cube_map(PREFILTER_SIZE, PREFILTER_SIZE, true);
+        prefilter_program.use_program();
+        prefilter_program.set_uniform_matrix4("projection", projection);
+        prefilter_program.set_uniform_i1("environmentMap", 0);
+        prefilter_program.set_uniform_f1("resolution", PREFILTER_SIZE as f32);
+        environment_map.bind(0);
+        prefilter.bind();
+        for level in 0..PREFILTER_MIP_LEVELS {
+            let size = PREFILTER_SIZE >> level;
+            prefilter.resize_depth(size, size);
+            set_viewport(0, 0, size, size);
+            prefilter_program.set_uniform_f1(
+                "roughness",
+                level as f32 / (PREFILTER_MIP_LEVELS - 1) as f32,
+            );
+            for (face, view) in views.iter().enumerate() {
+                prefilter_program.set_uniform_matrix4("view", *view);
+                prefilter.attach_cube_map_face(face as u32, level);
+                clear(&[DrawingBuffer::Color, DrawingBuffer::Depth]);
+                cube.draw();
+            }
+        }
+        FrameBuffer::unbind();
+
+        let brdf_program = Program::new(
+            shader_loader.load(ShaderType::Vertex, BRDF_VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, BRDF_FRAGMENT_SHADER)?,
+        )?;
+        let quad = vertical_plane(vec![]).to_rendering_mesh(texture_loader)?;
+        let brdf_lut = FrameBuffer::intermediate_with_format(
+            BRDF_LUT_SIZE,
+            BRDF_LUT_SIZE,
+            TextureFormat::FloatingPoint,
+        );
+        brdf_lut.texture.just_bind();
+        set_linear_clamped(&brdf_lut.texture);
+        brdf_lut.texture.unbind();
+        brdf_lut.bind();
+        set_viewport(0, 0, BRDF_LUT_SIZE, BRDF_LUT_SIZE);
+        brdf_program.use_program();
+        clear(&[DrawingBuffer::Color]);
+        quad.draw();
+        FrameBuffer::unbind();
+
+        Ok(ImageBasedLighting {
+            brdf_lut,
+            _environment: environment_map,
+            irradiance,
+            prefilter,
+        })
+    }
+
+    pub(crate) fn attach_to_program(&self, program: &Program) {
+        self.irradiance.texture.bind(IRRADIANCE_UNIT);
+        program.set_uniform_i1("irradianceMap", IRRADIANCE_UNIT as i32);
+        self.prefilter.texture.bind(PREFILTER_UNIT);
+        program.set_uniform_i1("prefilterMap", PREFILTER_UNIT as i32);
+        self.brdf_lut.texture.bind(BRDF_LUT_UNIT);
+        program.set_uniform_i1("brdfLUT", BRDF_LUT_UNIT as i32);
+    }
+}
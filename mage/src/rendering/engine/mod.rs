@@ -1,15 +1,253 @@
+use crate::animation::{Skin, MAX_JOINTS};
+use crate::gameplay::camera::Camera;
+use crate::rendering::model::dynamic::DynamicMesh;
+use crate::rendering::model::mesh::{Mesh, RenderingMesh};
+use crate::rendering::opengl::buffer::{Buffer, BufferType, BufferUsage};
+use crate::rendering::opengl::frame_buffer::FrameBuffer;
+use crate::rendering::opengl::program::Program;
+use crate::rendering::skybox::{RenderingSkybox, Skybox};
+use crate::resources::texture::TextureLoader;
+use crate::terrain::{RenderingSplatMaterial, SplatMaterial};
 use crate::MageError;
-use hecs::World;
+use hecs::{Entity, World};
 use include_dir::{include_dir, Dir};
+use nalgebra::Matrix4;
+use std::mem::size_of;
 
+mod ibl;
+mod pbr;
 mod simple;
 
+pub(crate) const JOINTS_BINDING: usize = 1;
+
 pub(crate) const SHADER_LIBRARY: Dir<'static> = include_dir!("$CARGO_MANIFEST_DIR/shaders");
 
+// Engines share the game's texture loader, so an image used by several
+// renderers is only decoded and uploaded once.
 pub trait Engine {
-    fn setup(&self, world: &mut World) -> Result<(), MageError>;
+    fn setup(
+        &mut self,
+        world: &mut World,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError>;
+
+    fn render(
+        &self,
+        world: &mut World,
+        delta_time: f32,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError>;
+
+    fn set_samples(&mut self, _width: u32, _height: u32, _samples: u32) {}
+
+    fn resize(&mut self, _width: u32, _height: u32) {}
+}
+
+pub(crate) struct MultisampleTarget {
+    frame_buffer: FrameBuffer,
+    height: u32,
+    samples: u32,
+    width: u32,
+}
+
+impl MultisampleTarget {
+    pub(crate) fn new(width: u32, height: u32, samples: u32) -> MultisampleTarget {
+        MultisampleTarget {
+            frame_buffer: FrameBuffer::multisample_with_samples(width, height, samples),
+            height,
+            samples,
+            width,
+        }
+    }
+
+    pub(crate) fn from_samples(width: u32, height: u32, samples: u32) -> Option<MultisampleTarget> {
+        (samples > 0).then(|| MultisampleTarget::new(width, height, samples))
+    }
+
+    pub(crate) fn resized(&self, width: u32, height: u32) -> MultisampleTarget {
+        MultisampleTarget::new(width, height, self.samples)
+    }
+
+    pub(crate) fn bind(&self) {
+        self.frame_buffer.bind();
+    }
+
+    pub(crate) fn resolve(&self) {
+        self.frame_buffer.blit(self.width, self.height);
+    }
+}
+
+pub(crate) fn matrices_buffer() -> Buffer {
+    let uniform_buffer = Buffer::new(BufferType::Uniform);
+    let buffer_size = Matrix4::<f32>::identity().len() * 2;
+    uniform_buffer.bind();
+    uniform_buffer.allocate_data::<f32>(buffer_size);
+    uniform_buffer.unbind();
+    uniform_buffer.link_to_binding_point(0, 0, buffer_size);
+    uniform_buffer
+}
+
+pub(crate) fn update_matrices<C: Camera>(uniform_buffer: &Buffer, camera: &C) {
+    let projection = camera.projection();
+    let view = camera.look_at_matrix();
+    uniform_buffer.bind();
+    uniform_buffer.set_sub_data(0, view.len(), view.as_slice());
+    uniform_buffer.set_sub_data(view.len(), projection.len(), projection.as_slice());
+    uniform_buffer.unbind();
+}
+
+pub(crate) fn joints_buffer() -> Buffer {
+    let uniform_buffer = Buffer::new(BufferType::Uniform);
+    let buffer_size = Matrix4::<f32>::identity().len() * MAX_JOINTS;
+    uniform_buffer.bind();
+    uniform_buffer.allocate_data::<f32>(buffer_size);
+    uniform_buffer.unbind();
+    uniform_buffer.link_to_binding_point(JOINTS_BINDING, 0, buffer_size * size_of::<f32>());
+    uniform_buffer
+}
+
+pub(crate) fn attach_skin(
+    program: &Program,
+    uniform_buffer: &Buffer,
+    world: &World,
+    entity: Entity,
+    model: &Matrix4<f32>,
+) {
+    let skin = match world.get::<Skin>(entity) {
+        Ok(skin) => skin,
+        Err(_) => {
+            program.set_uniform_i1("skinned", 0);
+            return;
+        }
+    };
+    let matrices = skin
+        .joint_matrices(world, model)
+        .iter()
+        .flat_map(|matrix| matrix.as_slice().to_vec())
+        .collect::<Vec<f32>>();
+    if !matrices.is_empty() {
+        uniform_buffer.bind();
+        uniform_buffer.set_sub_data(0, matrices.len(), &matrices);
+        uniform_buffer.unbind();
+    }
+    program.set_uniform_i1("skinned", 1);
+}
+
+// Runs every frame with a loader that outlives it, so rebuilt meshes share the
+// textures already uploaded instead of decoding them again.
+pub fn sync_rendering_meshes(
+    world: &mut World,
+    texture_loader: &mut TextureLoader,
+) -> Result<(), MageError> {
+    let mut rendering_meshes = vec![];
+    for (e, (mesh, rendering_mesh, dynamic)) in
+        world.query_mut::<(&Mesh, Option<&mut RenderingMesh>, Option<&mut DynamicMesh>)>()
+    {
+        match (rendering_mesh, dynamic) {
+            (Some(rendering_mesh), Some(dynamic)) if rendering_mesh.is_dynamic() => {
+                let dirty = dynamic.take_dirty();
+                if dirty.is_some() || rendering_mesh.revision() != mesh.revision() {
+                    rendering_mesh.update(mesh, dirty, texture_loader)?;
+                }
+            }
+            (rendering_mesh, dynamic) => {
+                let up_to_date = rendering_mesh
+                    .map(|rendering_mesh| rendering_mesh.revision() == mesh.revision())
+                    .unwrap_or(false);
+                if up_to_date {
+                    continue;
+                }
+                let rendering_mesh = match dynamic {
+                    Some(dynamic) => {
+                        dynamic.take_dirty();
+                        mesh.to_dynamic_rendering_mesh(dynamic, texture_loader)?
+                    }
+                    None => {
+                        mesh.to_rendering_mesh_with(BufferUsage::StaticDraw, false, texture_loader)?
+                    }
+                };
+                rendering_meshes.push((e, rendering_mesh));
+            }
+        }
+    }
+    let removed = world
+        .query_mut::<&RenderingMesh>()
+        .without::<Mesh>()
+        .into_iter()
+        .map(|(e, _)| e)
+        .collect::<Vec<_>>();
+    for e in removed {
+        world.remove_one::<RenderingMesh>(e)?;
+    }
+    for (e, rendering_mesh) in rendering_meshes {
+        world.insert_one(e, rendering_mesh)?;
+    }
+    sync_splat_materials(world, texture_loader)?;
+    sync_skyboxes(world, texture_loader)
+}
+
+fn sync_splat_materials(
+    world: &mut World,
+    texture_loader: &mut TextureLoader,
+) -> Result<(), MageError> {
+    let mut materials = vec![];
+    for (e, (material, rendering_material)) in
+        world.query_mut::<(&SplatMaterial, Option<&RenderingSplatMaterial>)>()
+    {
+        let up_to_date = rendering_material
+            .map(|rendering_material| rendering_material.fingerprint() == material.fingerprint())
+            .unwrap_or(false);
+        if !up_to_date {
+            materials.push((e, RenderingSplatMaterial::new(material, texture_loader)?));
+        }
+    }
+    let removed = world
+        .query_mut::<&RenderingSplatMaterial>()
+        .without::<SplatMaterial>()
+        .into_iter()
+        .map(|(e, _)| e)
+        .collect::<Vec<_>>();
+    for e in removed {
+        world.remove_one::<RenderingSplatMaterial>(e)?;
+    }
+    for (e, material) in materials {
+        world.insert_one(e, material)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn attach_splat(program: &Program, world: &World, entity: Entity) {
+    match world.get::<RenderingSplatMaterial>(entity) {
+        Ok(material) => material.attach_to_program(program),
+        Err(_) => program.set_uniform_i1("splatting", 0),
+    }
+}
 
-    fn render(&self, world: &mut World, delta_time: f32) -> Result<(), MageError>;
+fn sync_skyboxes(world: &mut World, texture_loader: &mut TextureLoader) -> Result<(), MageError> {
+    let mut rendering_skyboxes = vec![];
+    for (e, (skybox, rendering_skybox)) in world.query_mut::<(&Skybox, Option<&RenderingSkybox>)>()
+    {
+        let up_to_date = rendering_skybox
+            .map(|rendering_skybox| rendering_skybox.fingerprint() == skybox.fingerprint())
+            .unwrap_or(false);
+        if !up_to_date {
+            rendering_skyboxes.push((e, skybox.to_rendering_skybox(texture_loader)?));
+        }
+    }
+    let removed = world
+        .query_mut::<&RenderingSkybox>()
+        .without::<Skybox>()
+        .into_iter()
+        .map(|(e, _)| e)
+        .collect::<Vec<_>>();
+    for e in removed {
+        world.remove_one::<RenderingSkybox>(e)?;
+    }
+    for (e, rendering_skybox) in rendering_skyboxes {
+        world.insert_one(e, rendering_skybox)?;
+    }
+    Ok(())
 }
 
+pub use pbr::PbrEngine;
 pub use simple::SimpleEngine;
@@ -0,0 +1,346 @@
+use crate::animation::Skin;
+use crate::gameplay::camera::Camera;
+use crate::particles::ParticleRenderer;
+use crate::rendering::culling::{CullingStatistics, Frustum};
+use crate::rendering::engine::ibl::{EmptyEnvironment, ImageBasedLighting};
+use crate::rendering::engine::{
+    attach_skin, attach_splat, joints_buffer, matrices_buffer, update_matrices, Engine,
+    MultisampleTarget, JOINTS_BINDING, SHADER_LIBRARY,
+};
+use crate::rendering::instanced::InstancedRenderer;
+use crate::rendering::lights::{
+    DirectionalLight, PointLight, MAX_DIRECTIONAL_LIGHTS, MAX_POINT_LIGHTS,
+};
+use crate::rendering::model::material::PbrMaterial;
+use crate::rendering::model::mesh::{RenderingMesh, TextureInfo};
+use crate::rendering::opengl::buffer::Buffer;
+use crate::rendering::opengl::program::Program;
+use crate::rendering::opengl::shader::ShaderType;
+use crate::rendering::opengl::{
+    clear, enable, get_viewport, set_blend_mode, set_clear_color, set_viewport, DrawingBuffer,
+    Feature,
+};
+use crate::rendering::skybox::RenderingSkybox;
+use crate::rendering::sprite::SpriteRenderer;
+use crate::rendering::text::TextRenderer;
+use crate::rendering::transparent::{
+    begin_transparent, end_transparent, render_order, RenderQueue,
+};
+use crate::rendering::Transform;
+use crate::resources::shader::ShaderLoader;
+use crate::resources::texture::TextureLoader;
+use crate::MageError;
+use hecs::{Entity, World};
+use nalgebra::{Vector3, Vector4};
+use russimp::texture::TextureType;
+
+const VERTEX_SHADER: &str = "pbr-vertex.glsl";
+const FRAGMENT_SHADER: &str = "pbr-fragment.glsl";
+const SKYBOX_VERTEX_SHADER: &str = "skybox-vertex.glsl";
+const SKYBOX_FRAGMENT_SHADER: &str = "skybox-fragment.glsl";
+
+pub struct PbrEngine<C: Camera> {
+    camera: C,
+    clear_color: Vector3<f32>,
+    empty_environment: EmptyEnvironment,
+    environment: Option<TextureInfo>,
+    image_based_lighting: Option<ImageBasedLighting>,
+    instanced_renderer: InstancedRenderer,
+    joints_buffer: Buffer,
+    multisample: Option<MultisampleTarget>,
+    particle_renderer: ParticleRenderer,
+    program: Program,
+    skybox_program: Program,
+    sprite_renderer: SpriteRenderer,
+    statistics: CullingStatistics,
+    text_renderer: TextRenderer,
+    uniform_buffer: Buffer,
+}
+
+impl<C: Camera> PbrEngine<C> {
+    pub fn new(camera: C, clear_color: Vector3<f32>) -> Result<PbrEngine<C>, MageError> {
+        let shader_loader = ShaderLoader::new(&SHADER_LIBRARY)?;
+        let program = Program::new(
+            shader_loader.load(ShaderType::Vertex, VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, FRAGMENT_SHADER)?,
+        )?;
+        let skybox_program = Program::new(
+            shader_loader.load(ShaderType::Vertex, SKYBOX_VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, SKYBOX_FRAGMENT_SHADER)?,
+        )?;
+        program.bind_uniform_block("Matrices", 0);
+        program.bind_uniform_block("Joints", JOINTS_BINDING);
+        let uniform_buffer = matrices_buffer();
+        Ok(PbrEngine {
+            camera,
+            clear_color,
+            empty_environment: EmptyEnvironment::new(),
+            environment: None,
+            image_based_lighting: None,
+            instanced_renderer: InstancedRenderer::new(),
+            joints_buffer: joints_buffer(),
+            multisample: None,
+            particle_renderer: ParticleRenderer::new()?,
+            program,
+            skybox_program,
+            sprite_renderer: SpriteRenderer::new()?,
+            statistics: CullingStatistics::default(),
+            text_renderer: TextRenderer::new()?,
+            uniform_buffer,
+        })
+    }
+
+    // The environment maps are baked in `setup` with the game's texture loader.
+    pub fn new_with_environment(
+        camera: C,
+        clear_color: Vector3<f32>,
+        environment: TextureInfo,
+    ) -> Result<PbrEngine<C>, MageError> {
+        let mut engine = PbrEngine::new(camera, clear_color)?;
+        engine.environment = Some(environment);
+        Ok(engine)
+    }
+
+    pub fn new_with_samples(
+        camera: C,
+        clear_color: Vector3<f32>,
+        width: u32,
+        height: u32,
+        samples: u32,
+    ) -> Result<PbrEngine<C>, MageError> {
+        let mut engine = PbrEngine::new(camera, clear_color)?;
+        engine.set_samples(width, height, samples);
+        Ok(engine)
+    }
+
+    pub fn statistics(&self) -> &CullingStatistics {
+        &self.statistics
+    }
+
+    fn setup_globals(&self, world: &World) {
+        update_matrices(&self.uniform_buffer, &self.camera);
+        self.program
+            .set_uniform_v3("viewPos", self.camera.position());
+
+        let mut point_lights = 0;
+        for (_e, (light, transform)) in world.query::<(&PointLight, &Transform)>().iter() {
+            if point_lights >= MAX_POINT_LIGHTS {
+                break;
+            }
+            self.program.set_uniform_v3(
+                &format!("pointLights[{}].position", point_lights),
+                transform.position,
+            );
+            self.program.set_uniform_v3(
+                &format!("pointLights[{}].color", point_lights),
+                light.color * light.intensity,
+            );
+            point_lights += 1;
+        }
+        self.program
+            .set_uniform_i1("pointLightsCount", point_lights as i32);
+
+        let mut directional_lights = 0;
+        for (_e, light) in world.query::<&DirectionalLight>().iter() {
+            if directional_lights >= MAX_DIRECTIONAL_LIGHTS {
+                break;
+            }
+            self.program.set_uniform_v3(
+                &format!("directionalLights[{}].direction", directional_lights),
+                light.direction,
+            );
+            self.program.set_uniform_v3(
+                &format!("directionalLights[{}].color", directional_lights),
+                light.color * light.intensity,
+            );
+            directional_lights += 1;
+        }
+        self.program
+            .set_uniform_i1("directionalLightsCount", directional_lights as i32);
+
+        match &self.image_based_lighting {
+            Some(image_based_lighting) => image_based_lighting.attach_to_program(&self.program),
+            None => self.empty_environment.attach_to_program(&self.program),
+        }
+        self.program
+            .set_uniform_i1("hasEnvironment", self.image_based_lighting.is_some() as i32);
+    }
+
+    fn draw_entity(
+        &self,
+        world: &World,
+        e: Entity,
+        frustum: &Frustum,
+        default_material: &PbrMaterial,
+    ) -> Result<(), MageError> {
+        let mut query = world.query_one::<(
+            &RenderingMesh,
+            &Transform,
+            Option<&PbrMaterial>,
+            Option<&RenderQueue>,
+        )>(e)?;
+        if let Some((mesh, transform, material, queue)) = query.get() {
+            let model = transform.get_model_matrix();
+            let skinned = world.get::<Skin>(e).is_ok();
+            let visible = skinned || frustum.is_visible(&mesh.bounds, &model);
+            self.statistics.record(visible);
+            if !visible {
+                return Ok(());
+            }
+            self.attach_material(mesh, material.unwrap_or(default_material));
+            self.program.set_uniform_f1(
+                "alphaCutoff",
+                queue.map(RenderQueue::alpha_cutoff).unwrap_or(0f32),
+            );
+            attach_skin(&self.program, &self.joints_buffer, world, e, &model);
+            attach_splat(&self.program, world, e);
+            self.program.set_uniform_matrix4("model", model);
+            mesh.draw();
+        }
+        Ok(())
+    }
+
+    fn attach_material(&self, mesh: &RenderingMesh, material: &PbrMaterial) {
+        mesh.attach_to_program(&self.program);
+        self.program.set_uniform_v3("albedo", material.albedo);
+        self.program.set_uniform_f1("metalness", material.metalness);
+        self.program.set_uniform_f1("roughness", material.roughness);
+        self.program.set_uniform_f1("ao", material.ao);
+        self.program
+            .set_uniform_f1("heightScale", material.height_scale);
+        self.program.set_uniform_f1("opacity", material.opacity);
+        self.program.set_uniform_i1(
+            "hasDiffuse",
+            (mesh.has_texture(TextureType::Diffuse) || mesh.has_texture(TextureType::BaseColor))
+                as i32,
+        );
+        self.program.set_uniform_i1(
+            "hasMetalness",
+            mesh.has_texture(TextureType::Metalness) as i32,
+        );
+        self.program.set_uniform_i1(
+            "hasRoughness",
+            mesh.has_texture(TextureType::Roughness) as i32,
+        );
+        self.program.set_uniform_i1(
+            "hasAo",
+            mesh.has_texture(TextureType::AmbientOcclusion) as i32,
+        );
+        self.program
+            .set_uniform_i1("hasNormal", mesh.has_texture(TextureType::Normals) as i32);
+        self.program
+            .set_uniform_i1("hasHeight", mesh.has_texture(TextureType::Height) as i32);
+    }
+}
+
+impl<C: Camera> Engine for PbrEngine<C> {
+    fn setup(
+        &mut self,
+        _world: &mut World,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
+        if let Some(environment) = &self.environment {
+            enable(Feature::TextureCubeMapSeamless);
+            let (x, y, width, height) = get_viewport();
+            let shader_loader = ShaderLoader::new(&SHADER_LIBRARY)?;
+            self.image_based_lighting = Some(ImageBasedLighting::new(
+                environment,
+                &shader_loader,
+                texture_loader,
+            )?);
+            set_viewport(x, y, width, height);
+        }
+        enable(Feature::Depth);
+        set_clear_color(Vector4::new(
+            self.clear_color.x,
+            self.clear_color.y,
+            self.clear_color.z,
+            1.0,
+        ));
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        world: &mut World,
+        _delta_time: f32,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
+        if let Some(multisample) = &self.multisample {
+            multisample.bind();
+        }
+        clear(&[DrawingBuffer::Color, DrawingBuffer::Depth]);
+        self.program.use_program();
+        self.setup_globals(world);
+        let default_material = PbrMaterial::default();
+        let frustum = Frustum::from_camera(&self.camera);
+        self.statistics.reset();
+        let order = render_order(world, self.camera.position());
+        for e in order.opaque {
+            self.draw_entity(world, e, &frustum, &default_material)?;
+        }
+        let attach_instanced = |mesh: &RenderingMesh, e| {
+            self.program.set_uniform_i1("skinned", 0);
+            self.program.set_uniform_i1("splatting", 0);
+            match world.get::<PbrMaterial>(e) {
+                Ok(material) => self.attach_material(mesh, &material),
+                Err(_) => self.attach_material(mesh, &default_material),
+            }
+            self.program.set_uniform_f1(
+                "alphaCutoff",
+                world
+                    .get::<RenderQueue>(e)
+                    .map(|queue| queue.alpha_cutoff())
+                    .unwrap_or(0f32),
+            );
+        };
+        let batches = self
+            .instanced_renderer
+            .batches(world, self.camera.position());
+        self.instanced_renderer.render(
+            &batches,
+            &self.program,
+            &frustum,
+            &self.statistics,
+            texture_loader,
+            attach_instanced,
+        )?;
+        self.skybox_program.use_program();
+        for (_e, skybox) in world.query::<&RenderingSkybox>().iter() {
+            skybox.draw(&self.skybox_program);
+        }
+        self.program.use_program();
+        begin_transparent();
+        for (e, mode) in order.transparent {
+            set_blend_mode(mode);
+            self.draw_entity(world, e, &frustum, &default_material)?;
+        }
+        self.instanced_renderer.render_transparent(
+            &batches,
+            &self.program,
+            &frustum,
+            &self.statistics,
+            texture_loader,
+            attach_instanced,
+        )?;
+        end_transparent();
+        self.particle_renderer
+            .render(world, self.camera.position(), texture_loader)?;
+        self.sprite_renderer.render(world, texture_loader)?;
+        self.text_renderer.render(world)?;
+        if let Some(multisample) = &self.multisample {
+            multisample.resolve();
+        }
+        Ok(())
+    }
+
+    fn set_samples(&mut self, width: u32, height: u32, samples: u32) {
+        self.multisample = MultisampleTarget::from_samples(width, height, samples);
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        if let Some(multisample) = &self.multisample {
+            self.multisample = Some(multisample.resized(width, height));
+        }
+    }
+}
@@ -1,27 +1,53 @@
+use crate::animation::Skin;
 use crate::gameplay::camera::Camera;
-use crate::rendering::engine::{Engine, SHADER_LIBRARY};
-use crate::rendering::model::mesh::{Mesh, RenderingMesh};
-use crate::rendering::opengl::buffer::{Buffer, BufferType};
+use crate::particles::ParticleRenderer;
+use crate::rendering::culling::{CullingStatistics, Frustum};
+use crate::rendering::engine::{
+    attach_skin, attach_splat, joints_buffer, matrices_buffer, update_matrices, Engine,
+    MultisampleTarget, JOINTS_BINDING, SHADER_LIBRARY,
+};
+use crate::rendering::instanced::InstancedRenderer;
+use crate::rendering::model::mesh::RenderingMesh;
+use crate::rendering::opengl::buffer::Buffer;
 use crate::rendering::opengl::program::Program;
 use crate::rendering::opengl::shader::ShaderType;
-use crate::rendering::opengl::{clear, enable, set_clear_color, DrawingBuffer, Feature};
+use crate::rendering::opengl::{
+    clear, enable, set_blend_mode, set_clear_color, DrawingBuffer, Feature,
+};
+use crate::rendering::skybox::RenderingSkybox;
+use crate::rendering::sprite::SpriteRenderer;
+use crate::rendering::text::TextRenderer;
+use crate::rendering::transparent::{
+    begin_transparent, end_transparent, render_order, RenderQueue,
+};
 use crate::rendering::Transform;
 use crate::resources::shader::ShaderLoader;
+use crate::resources::texture::TextureLoader;
 use crate::MageError;
-use hecs::World;
+use hecs::{Entity, World};
 use log::debug;
-use nalgebra::{Matrix4, Vector3, Vector4};
+use nalgebra::{Vector3, Vector4};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 const VERTEX_SHADER: &str = "simple-rendering-vertex.glsl";
 const FRAGMENT_SHADER: &str = "simple-rendering-fragment.glsl";
+const SKYBOX_VERTEX_SHADER: &str = "skybox-vertex.glsl";
+const SKYBOX_FRAGMENT_SHADER: &str = "skybox-fragment.glsl";
 const DEBUG_ITERATION: usize = 100;
 
 pub struct SimpleEngine<C: Camera> {
     camera: C,
     clear_color: Vector3<f32>,
+    instanced_renderer: InstancedRenderer,
     iteration: AtomicUsize,
+    joints_buffer: Buffer,
+    multisample: Option<MultisampleTarget>,
+    particle_renderer: ParticleRenderer,
     program: Program,
+    skybox_program: Program,
+    sprite_renderer: SpriteRenderer,
+    statistics: CullingStatistics,
+    text_renderer: TextRenderer,
     uniform_buffer: Buffer,
 }
 
@@ -32,45 +58,86 @@ impl<C: Camera> SimpleEngine<C> {
             shader_loader.load(ShaderType::Vertex, VERTEX_SHADER)?,
             shader_loader.load(ShaderType::Fragment, FRAGMENT_SHADER)?,
         )?;
-        let uniform_buffer = Buffer::new(BufferType::Uniform);
-        let buffer_size = Matrix4::<f32>::identity().len() * 2;
-        uniform_buffer.bind();
-        uniform_buffer.allocate_data::<f32>(buffer_size);
-        uniform_buffer.unbind();
-        uniform_buffer.link_to_binding_point(0, 0, buffer_size);
+        let skybox_program = Program::new(
+            shader_loader.load(ShaderType::Vertex, SKYBOX_VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, SKYBOX_FRAGMENT_SHADER)?,
+        )?;
+        program.bind_uniform_block("Matrices", 0);
+        program.bind_uniform_block("Joints", JOINTS_BINDING);
+        let uniform_buffer = matrices_buffer();
         Ok(SimpleEngine {
             camera,
             clear_color,
+            instanced_renderer: InstancedRenderer::new(),
+            joints_buffer: joints_buffer(),
             iteration: AtomicUsize::new(0),
+            multisample: None,
+            particle_renderer: ParticleRenderer::new()?,
             program,
+            skybox_program,
+            sprite_renderer: SpriteRenderer::new()?,
+            statistics: CullingStatistics::default(),
+            text_renderer: TextRenderer::new()?,
             uniform_buffer,
         })
     }
 
+    pub fn new_with_samples(
+        camera: C,
+        clear_color: Vector3<f32>,
+        width: u32,
+        height: u32,
+        samples: u32,
+    ) -> Result<SimpleEngine<C>, MageError> {
+        let mut engine = SimpleEngine::new(camera, clear_color)?;
+        engine.set_samples(width, height, samples);
+        Ok(engine)
+    }
+
+    pub fn statistics(&self) -> &CullingStatistics {
+        &self.statistics
+    }
+
     fn setup_globals(&self) {
-        let projection = self.camera.projection();
-        let view = self.camera.look_at_matrix();
-        self.uniform_buffer.bind();
-        self.uniform_buffer
-            .set_sub_data(0, view.len(), view.as_slice());
-        self.uniform_buffer
-            .set_sub_data(view.len(), projection.len(), projection.as_slice());
-        self.uniform_buffer.unbind();
+        update_matrices(&self.uniform_buffer, &self.camera);
         self.program
             .set_uniform_v3("viewPos", self.camera.position());
     }
+
+    fn draw_entity(&self, world: &World, e: Entity, frustum: &Frustum) -> Result<(), MageError> {
+        let mut query = world.query_one::<(&RenderingMesh, &Transform, Option<&RenderQueue>)>(e)?;
+        if let Some((mesh, transform, queue)) = query.get() {
+            let model = transform.get_model_matrix();
+            let skinned = world.get::<Skin>(e).is_ok();
+            let visible = skinned || frustum.is_visible(&mesh.bounds, &model);
+            self.statistics.record(visible);
+            if !visible {
+                return Ok(());
+            }
+            if self.iteration.load(Ordering::Relaxed) % DEBUG_ITERATION == 0 {
+                debug!("MODEL {:?} {:?}", e, transform);
+            }
+            mesh.attach_to_program(&self.program);
+            self.program.set_uniform_f1(
+                "alphaCutoff",
+                queue.map(RenderQueue::alpha_cutoff).unwrap_or(0f32),
+            );
+            attach_skin(&self.program, &self.joints_buffer, world, e, &model);
+            attach_splat(&self.program, world, e);
+            self.program.set_uniform_matrix4("model", model);
+            mesh.draw();
+        }
+        Ok(())
+    }
 }
 
 impl<C: Camera> Engine for SimpleEngine<C> {
-    fn setup(&self, world: &mut World) -> Result<(), MageError> {
+    fn setup(
+        &mut self,
+        _world: &mut World,
+        _texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
         enable(Feature::Depth);
-        let mut rendering_mesh = vec![];
-        for (e, mesh) in world.query_mut::<&Mesh>() {
-            rendering_mesh.push((e, mesh.to_rendering_mesh()?));
-        }
-        for (e, rendering_mesh) in rendering_mesh {
-            world.insert_one(e, rendering_mesh)?;
-        }
         set_clear_color(Vector4::new(
             self.clear_color.x,
             self.clear_color.y,
@@ -80,25 +147,84 @@ impl<C: Camera> Engine for SimpleEngine<C> {
         Ok(())
     }
 
-    fn render(&self, world: &mut World, _delta_time: f32) -> Result<(), MageError> {
+    fn render(
+        &self,
+        world: &mut World,
+        _delta_time: f32,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
+        if let Some(multisample) = &self.multisample {
+            multisample.bind();
+        }
         clear(&[DrawingBuffer::Color, DrawingBuffer::Depth]);
         self.program.use_program();
         self.setup_globals();
-        for (_e, (mesh, transform)) in world.query::<(&RenderingMesh, &Transform)>().iter() {
-            if self.iteration.load(Ordering::Relaxed) % DEBUG_ITERATION == 0 {
-                debug!(
-                    "MODEL {:?} {:?} {:?}",
-                    _e,
-                    transform,
-                    world.query_one::<&Transform>(_e)?.get()
-                );
-            }
+        let frustum = Frustum::from_camera(&self.camera);
+        self.statistics.reset();
+        let order = render_order(world, self.camera.position());
+        for e in order.opaque {
+            self.draw_entity(world, e, &frustum)?;
+        }
+        let attach_instanced = |mesh: &RenderingMesh, e| {
+            self.program.set_uniform_i1("skinned", 0);
+            self.program.set_uniform_i1("splatting", 0);
             mesh.attach_to_program(&self.program);
-            self.program
-                .set_uniform_matrix4("model", transform.get_model_matrix());
-            mesh.draw();
+            self.program.set_uniform_f1(
+                "alphaCutoff",
+                world
+                    .get::<RenderQueue>(e)
+                    .map(|queue| queue.alpha_cutoff())
+                    .unwrap_or(0f32),
+            );
+        };
+        let batches = self
+            .instanced_renderer
+            .batches(world, self.camera.position());
+        self.instanced_renderer.render(
+            &batches,
+            &self.program,
+            &frustum,
+            &self.statistics,
+            texture_loader,
+            attach_instanced,
+        )?;
+        self.skybox_program.use_program();
+        for (_e, skybox) in world.query::<&RenderingSkybox>().iter() {
+            skybox.draw(&self.skybox_program);
+        }
+        self.program.use_program();
+        begin_transparent();
+        for (e, mode) in order.transparent {
+            set_blend_mode(mode);
+            self.draw_entity(world, e, &frustum)?;
+        }
+        self.instanced_renderer.render_transparent(
+            &batches,
+            &self.program,
+            &frustum,
+            &self.statistics,
+            texture_loader,
+            attach_instanced,
+        )?;
+        end_transparent();
+        self.particle_renderer
+            .render(world, self.camera.position(), texture_loader)?;
+        self.sprite_renderer.render(world, texture_loader)?;
+        self.text_renderer.render(world)?;
+        if let Some(multisample) = &self.multisample {
+            multisample.resolve();
         }
         self.iteration.fetch_add(1, Ordering::Relaxed);
         Ok(())
     }
+
+    fn set_samples(&mut self, width: u32, height: u32, samples: u32) {
+        self.multisample = MultisampleTarget::from_samples(width, height, samples);
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        if let Some(multisample) = &self.multisample {
+            self.multisample = Some(multisample.resized(width, height));
+        }
+    }
 }
@@ -0,0 +1,98 @@
+use hecs::{DynamicBundle, Entity, World};
+
+use crate::rendering::Transform;
+use crate::MageError;
+
+#[derive(Clone, Debug)]
+pub struct Name(pub String);
+
+#[derive(Clone, Debug)]
+pub struct Parent {
+    pub entity: Entity,
+    pub local: Transform,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Children(pub Vec<Entity>);
+
+// Spawns `components` as a child of `parent` that follows it without an offset.
+pub(crate) fn spawn_child(
+    world: &mut World,
+    parent: Entity,
+    components: impl DynamicBundle,
+) -> Result<Entity, MageError> {
+    let transform = (*world.get::<Transform>(parent)?).clone();
+    let entity = world.spawn(components);
+    world.insert(
+        entity,
+        (
+            transform,
+            Parent {
+                entity: parent,
+                local: Transform::identity(),
+            },
+        ),
+    )?;
+    if world.get::<Children>(parent).is_ok() {
+        world.get_mut::<Children>(parent)?.0.push(entity);
+    } else {
+        world.insert_one(parent, Children(vec![entity]))?;
+    }
+    Ok(entity)
+}
+
+fn propagate(world: &World, entity: Entity, transform: &Transform) {
+    let children = match world.get::<Children>(entity) {
+        Ok(children) => children.0.clone(),
+        Err(_) => return,
+    };
+    for child in children {
+        let local = match world.get::<Parent>(child) {
+            Ok(parent) if parent.entity == entity => parent.local.clone(),
+            _ => continue,
+        };
+        let global = transform.combine(&local);
+        if let Ok(mut child_transform) = world.get_mut::<Transform>(child) {
+            *child_transform = global.clone();
+        }
+        propagate(world, child, &global);
+    }
+}
+
+pub fn propagate_transforms(world: &World) {
+    let roots = world
+        .query::<(&Transform, &Children)>()
+        .without::<Parent>()
+        .iter()
+        .map(|(e, (transform, _children))| (e, transform.clone()))
+        .collect::<Vec<_>>();
+    for (root, transform) in roots {
+        propagate(world, root, &transform);
+    }
+}
+
+pub fn descendants(world: &World, root: Entity) -> Vec<Entity> {
+    let mut entities = vec![root];
+    let mut i = 0;
+    while i < entities.len() {
+        if let Ok(children) = world.get::<Children>(entities[i]) {
+            entities.extend(children.0.iter().copied());
+        }
+        i += 1;
+    }
+    entities
+}
+
+pub fn find_by_name(world: &World, root: Entity, name: &str) -> Option<Entity> {
+    if world
+        .get::<Name>(root)
+        .map(|n| n.0 == name)
+        .unwrap_or(false)
+    {
+        return Some(root);
+    }
+    let children = world.get::<Children>(root).ok()?.0.clone();
+    children
+        .into_iter()
+        .find_map(|child| find_by_name(world, child, name))
+}
@@ -0,0 +1,256 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Weak};
+
+use hecs::{Entity, World};
+use nalgebra::{Matrix4, Vector3};
+
+use crate::rendering::culling::{CullingStatistics, Frustum};
+use crate::rendering::model::material::PbrMaterial;
+use crate::rendering::model::mesh::{Mesh, RenderingMesh};
+use crate::rendering::opengl::buffer::{Buffer, BufferType, BufferUsage};
+use crate::rendering::opengl::program::Program;
+use crate::rendering::opengl::vertex_array::{DataType, VertexArray};
+use crate::rendering::opengl::{set_blend_mode, BlendMode};
+use crate::rendering::transparent::RenderQueue;
+use crate::rendering::Transform;
+use crate::resources::texture::TextureLoader;
+use crate::MageError;
+
+const MODEL_ATTRIBUTE: u32 = 5;
+const MODEL_SIZE: usize = 16;
+
+// Instances only share a draw call when both the mesh and the material match.
+type BatchKey = (usize, u64);
+type Batch = (Arc<Mesh>, Entity, RenderQueue, Vec<Matrix4<f32>>);
+
+fn material_fingerprint(world: &World, entity: Entity, queue: RenderQueue) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    if let Ok(material) = world.get::<PbrMaterial>(entity) {
+        material.fingerprint().hash(&mut hasher);
+    }
+    match queue {
+        RenderQueue::Opaque => 0u8.hash(&mut hasher),
+        RenderQueue::AlphaTest(cutoff) => {
+            1u8.hash(&mut hasher);
+            cutoff.to_bits().hash(&mut hasher);
+        }
+        RenderQueue::Transparent(mode) => {
+            2u8.hash(&mut hasher);
+            (mode as u32).hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+fn distance(model: &Matrix4<f32>, camera_position: Vector3<f32>) -> f32 {
+    (model.fixed_slice::<3, 1>(0, 3) - camera_position).norm_squared()
+}
+
+#[derive(Clone, Debug)]
+pub struct SharedMesh(Arc<Mesh>);
+
+impl SharedMesh {
+    pub fn new(mesh: Mesh) -> SharedMesh {
+        SharedMesh(Arc::new(mesh))
+    }
+
+    pub fn mesh(&self) -> &Mesh {
+        &self.0
+    }
+
+    fn key(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+}
+
+struct InstancedMesh {
+    capacity: usize,
+    instance_buffer: Buffer,
+    mesh: Weak<Mesh>,
+    rendering_mesh: RenderingMesh,
+}
+
+impl InstancedMesh {
+    fn new(mesh: &Arc<Mesh>, loader: &mut TextureLoader) -> Result<InstancedMesh, MageError> {
+        let rendering_mesh = mesh.to_rendering_mesh_with(BufferUsage::StaticDraw, false, loader)?;
+        let instance_buffer = Buffer::new(BufferType::Array);
+        rendering_mesh.vertex_array.bind();
+        instance_buffer.bind();
+        for column in 0..4 {
+            VertexArray::set_vertex_attrib_with_padding::<f32>(
+                DataType::Float,
+                MODEL_ATTRIBUTE + column,
+                MODEL_SIZE as u32,
+                4,
+                column * 4,
+                false,
+            );
+            VertexArray::set_vertex_attrib_divisor(MODEL_ATTRIBUTE + column, 1);
+        }
+        VertexArray::unbind();
+        instance_buffer.unbind();
+        Ok(InstancedMesh {
+            capacity: 0,
+            instance_buffer,
+            mesh: Arc::downgrade(mesh),
+            rendering_mesh,
+        })
+    }
+
+    fn set_models(&mut self, models: &[f32]) {
+        self.instance_buffer.bind();
+        if models.len() > self.capacity {
+            self.instance_buffer
+                .set_data(models, BufferUsage::DynamicDraw);
+            self.capacity = models.len();
+        } else {
+            self.instance_buffer.set_sub_data(0, models.len(), models);
+        }
+        self.instance_buffer.unbind();
+    }
+}
+
+pub(crate) struct InstancedBatches {
+    opaque: Vec<(BatchKey, Batch)>,
+    transparent: Vec<(BatchKey, Batch, BlendMode)>,
+}
+
+pub(crate) struct InstancedRenderer {
+    meshes: RefCell<HashMap<BatchKey, InstancedMesh>>,
+}
+
+impl InstancedRenderer {
+    pub(crate) fn new() -> InstancedRenderer {
+        InstancedRenderer {
+            meshes: RefCell::new(HashMap::new()),
+        }
+    }
+
+    // Groups the instances once per frame, transparent batches and their
+    // instances sorted back to front by their farthest instance.
+    pub(crate) fn batches(&self, world: &World, camera_position: Vector3<f32>) -> InstancedBatches {
+        let mut batches: HashMap<BatchKey, Batch> = HashMap::new();
+        for (e, (shared_mesh, transform, queue)) in world
+            .query::<(&SharedMesh, &Transform, Option<&RenderQueue>)>()
+            .iter()
+        {
+            let queue = queue.copied().unwrap_or_default();
+            batches
+                .entry((shared_mesh.key(), material_fingerprint(world, e, queue)))
+                .or_insert_with(|| (shared_mesh.0.clone(), e, queue, vec![]))
+                .3
+                .push(transform.get_model_matrix());
+        }
+        self.meshes
+            .borrow_mut()
+            .retain(|key, _| batches.contains_key(key));
+        let mut opaque = vec![];
+        let mut transparent = vec![];
+        for (key, (mesh, entity, queue, mut models)) in batches {
+            match queue {
+                RenderQueue::Transparent(mode) => {
+                    models.sort_by(|a, b| {
+                        distance(b, camera_position)
+                            .partial_cmp(&distance(a, camera_position))
+                            .unwrap_or(Ordering::Equal)
+                    });
+                    let farthest = distance(&models[0], camera_position);
+                    transparent.push((key, (mesh, entity, queue, models), mode, farthest));
+                }
+                _ => opaque.push((key, (mesh, entity, queue, models))),
+            }
+        }
+        transparent.sort_by(|a, b| b.3.partial_cmp(&a.3).unwrap_or(Ordering::Equal));
+        InstancedBatches {
+            opaque,
+            transparent: transparent
+                .into_iter()
+                .map(|(key, batch, mode, _)| (key, batch, mode))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn render<F: Fn(&RenderingMesh, Entity)>(
+        &self,
+        batches: &InstancedBatches,
+        program: &Program,
+        frustum: &Frustum,
+        statistics: &CullingStatistics,
+        texture_loader: &mut TextureLoader,
+        attach: F,
+    ) -> Result<(), MageError> {
+        if batches.opaque.is_empty() {
+            return Ok(());
+        }
+        program.set_uniform_i1("instanced", 1);
+        for (key, batch) in batches.opaque.iter() {
+            self.draw_batch(*key, batch, frustum, statistics, texture_loader, &attach)?;
+        }
+        program.set_uniform_i1("instanced", 0);
+        Ok(())
+    }
+
+    // Expects blending to be enabled already.
+    pub(crate) fn render_transparent<F: Fn(&RenderingMesh, Entity)>(
+        &self,
+        batches: &InstancedBatches,
+        program: &Program,
+        frustum: &Frustum,
+        statistics: &CullingStatistics,
+        texture_loader: &mut TextureLoader,
+        attach: F,
+    ) -> Result<(), MageError> {
+        if batches.transparent.is_empty() {
+            return Ok(());
+        }
+        program.set_uniform_i1("instanced", 1);
+        for (key, batch, mode) in batches.transparent.iter() {
+            set_blend_mode(*mode);
+            self.draw_batch(*key, batch, frustum, statistics, texture_loader, &attach)?;
+        }
+        program.set_uniform_i1("instanced", 0);
+        Ok(())
+    }
+
+    fn draw_batch<F: Fn(&RenderingMesh, Entity)>(
+        &self,
+        key: BatchKey,
+        (mesh, entity, _, models): &Batch,
+        frustum: &Frustum,
+        statistics: &CullingStatistics,
+        texture_loader: &mut TextureLoader,
+        attach: &F,
+    ) -> Result<(), MageError> {
+        let mut meshes = self.meshes.borrow_mut();
+        let up_to_date = meshes
+            .get(&key)
+            .map(|instanced| Weak::ptr_eq(&instanced.mesh, &Arc::downgrade(mesh)))
+            .unwrap_or(false);
+        if !up_to_date {
+            meshes.insert(key, InstancedMesh::new(mesh, texture_loader)?);
+        }
+        let instanced = meshes.get_mut(&key).unwrap();
+        let visible = models
+            .iter()
+            .filter(|model| {
+                let visible = frustum.is_visible(&instanced.rendering_mesh.bounds, model);
+                statistics.record(visible);
+                visible
+            })
+            .flat_map(|model| model.as_slice().to_vec())
+            .collect::<Vec<f32>>();
+        if visible.is_empty() {
+            return Ok(());
+        }
+        instanced.set_models(&visible);
+        attach(&instanced.rendering_mesh, *entity);
+        instanced
+            .rendering_mesh
+            .draw_instanced((visible.len() / MODEL_SIZE) as u32);
+        Ok(())
+    }
+}
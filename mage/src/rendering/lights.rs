@@ -0,0 +1,17 @@
+use nalgebra::Vector3;
+
+pub const MAX_POINT_LIGHTS: usize = 8;
+pub const MAX_DIRECTIONAL_LIGHTS: usize = 4;
+
+#[derive(Clone, Debug)]
+pub struct PointLight {
+    pub color: Vector3<f32>,
+    pub intensity: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct DirectionalLight {
+    pub color: Vector3<f32>,
+    pub direction: Vector3<f32>,
+    pub intensity: f32,
+}
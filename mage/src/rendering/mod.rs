@@ -1,9 +1,17 @@
-use nalgebra::{Matrix4, Scale3, Translation3, Vector3};
+use nalgebra::{Matrix3, Matrix4, Rotation3, Scale3, Translation3, UnitQuaternion, Vector3};
 use rapier3d::math::Rotation;
 
+pub mod culling;
 pub mod engine;
+pub mod hierarchy;
+pub mod instanced;
+pub mod lights;
 pub mod model;
 pub mod opengl;
+pub mod skybox;
+pub mod sprite;
+pub mod text;
+pub mod transparent;
 
 #[derive(Clone, Debug)]
 pub struct Transform {
@@ -21,6 +29,34 @@ impl Transform {
         }
     }
 
+    pub fn from_matrix(matrix: &Matrix4<f32>) -> Transform {
+        let position = Vector3::new(matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)]);
+        let linear = matrix.fixed_slice::<3, 3>(0, 0).into_owned();
+        let scale = Vector3::new(
+            linear.column(0).norm(),
+            linear.column(1).norm(),
+            linear.column(2).norm(),
+        );
+        let rotation = Matrix3::from_columns(&[
+            linear.column(0) / scale.x.max(f32::EPSILON),
+            linear.column(1) / scale.y.max(f32::EPSILON),
+            linear.column(2) / scale.z.max(f32::EPSILON),
+        ]);
+        Transform {
+            position,
+            rotation: UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix(&rotation)),
+            scale,
+        }
+    }
+
+    pub fn combine(&self, local: &Transform) -> Transform {
+        Transform {
+            position: self.position + self.rotation * self.scale.component_mul(&local.position),
+            rotation: self.rotation * local.rotation,
+            scale: self.scale.component_mul(&local.scale),
+        }
+    }
+
     pub fn get_model_matrix(&self) -> Matrix4<f32> {
         let t = Translation3::from(self.position);
         let s = Scale3::from(self.scale);
@@ -0,0 +1,92 @@
+use nalgebra::{Matrix4, Point3, Vector3};
+
+use crate::rendering::model::mesh::Mesh;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub max: Vector3<f32>,
+    pub min: Vector3<f32>,
+}
+
+impl Aabb {
+    pub fn from_points(points: &[Vector3<f32>]) -> Aabb {
+        if points.is_empty() {
+            return Aabb {
+                max: Vector3::zeros(),
+                min: Vector3::zeros(),
+            };
+        }
+        points.iter().fold(
+            Aabb {
+                max: Vector3::repeat(f32::MIN),
+                min: Vector3::repeat(f32::MAX),
+            },
+            |aabb, point| Aabb {
+                max: aabb.max.sup(point),
+                min: aabb.min.inf(point),
+            },
+        )
+    }
+
+    pub fn center(&self) -> Vector3<f32> {
+        (self.min + self.max) / 2f32
+    }
+
+    pub fn half_extents(&self) -> Vector3<f32> {
+        (self.max - self.min) / 2f32
+    }
+
+    pub fn transform(&self, model: &Matrix4<f32>) -> Aabb {
+        let center = model.transform_point(&Point3::from(self.center())).coords;
+        let half_extents = self.half_extents();
+        let linear = model.fixed_slice::<3, 3>(0, 0).abs();
+        let extents = linear * half_extents;
+        Aabb {
+            max: center + extents,
+            min: center - extents,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vector3<f32>,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn from_points(points: &[Vector3<f32>]) -> BoundingSphere {
+        let center = Aabb::from_points(points).center();
+        let radius = points
+            .iter()
+            .map(|point| (point - center).norm())
+            .fold(0f32, f32::max);
+        BoundingSphere { center, radius }
+    }
+
+    pub fn transform(&self, model: &Matrix4<f32>) -> BoundingSphere {
+        let center = model.transform_point(&Point3::from(self.center)).coords;
+        let scale = (0..3)
+            .map(|column| model.fixed_slice::<3, 1>(0, column).norm())
+            .fold(0f32, f32::max);
+        BoundingSphere {
+            center,
+            radius: self.radius * scale,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub aabb: Aabb,
+    pub sphere: BoundingSphere,
+}
+
+impl Mesh {
+    pub fn bounds(&self) -> Bounds {
+        Bounds {
+            aabb: Aabb::from_points(&self.vertices),
+            sphere: BoundingSphere::from_points(&self.vertices),
+        }
+    }
+}
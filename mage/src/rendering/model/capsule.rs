@@ -0,0 +1,29 @@
+use nalgebra::Vector2;
+use num_traits::FloatConst;
+
+use crate::rendering::model::mesh::{Mesh, TextureInfo};
+use crate::rendering::model::revolution::{arc, revolve};
+
+pub fn capsule(
+    half_height: f32,
+    radius: f32,
+    segments: usize,
+    rings: usize,
+    textures: Vec<TextureInfo>,
+) -> Mesh {
+    let mut profile = arc(
+        Vector2::new(0f32, -half_height),
+        radius,
+        -f32::FRAC_PI_2(),
+        0f32,
+        rings,
+    );
+    profile.extend(arc(
+        Vector2::new(0f32, half_height),
+        radius,
+        0f32,
+        f32::FRAC_PI_2(),
+        rings,
+    ));
+    revolve(&[profile], segments, textures)
+}
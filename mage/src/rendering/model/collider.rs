@@ -0,0 +1,147 @@
+use nalgebra::{Point3, Vector3};
+use rapier3d::geometry::Collider;
+use rapier3d::parry::shape::{Shape, TypedShape};
+
+use crate::rendering::model::capsule::capsule;
+use crate::rendering::model::cone::cone;
+use crate::rendering::model::cube::cuboid;
+use crate::rendering::model::cylinder::cylinder;
+use crate::rendering::model::mesh::{next_revision, Mesh, TextureInfo};
+use crate::rendering::model::sphere::sphere_with_segments;
+use crate::rendering::opengl::DrawingMode;
+
+const SEGMENTS: usize = 32;
+const RINGS: usize = 8;
+
+fn flat_mesh(points: &[Point3<f32>], triangles: &[[u32; 3]], textures: Vec<TextureInfo>) -> Mesh {
+    let mut vertices = Vec::with_capacity(triangles.len() * 3);
+    let mut normals = Vec::with_capacity(triangles.len() * 3);
+    for [a, b, c] in triangles.iter() {
+        let [a, b, c] = [a, b, c].map(|i| points[*i as usize].coords);
+        let normal = (b - a)
+            .cross(&(c - a))
+            .try_normalize(0f32)
+            .unwrap_or_else(Vector3::y);
+        vertices.extend([a, b, c]);
+        normals.extend([normal; 3]);
+    }
+    Mesh {
+        bitangents: None,
+        colors: None,
+        drawing_mode: DrawingMode::Triangles,
+        indices: None,
+        joints: None,
+        normals: Some(normals),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
+        shininess: None,
+        tangents: None,
+        textures: Some(textures),
+        texture_coordinates: None,
+        vertices,
+        weights: None,
+    }
+}
+
+pub fn shape_mesh(shape: &dyn Shape, textures: Vec<TextureInfo>) -> Option<Mesh> {
+    match shape.as_typed_shape() {
+        TypedShape::Ball(ball) => Some(sphere_with_segments(
+            ball.radius,
+            SEGMENTS,
+            SEGMENTS / 2,
+            textures,
+        )),
+        TypedShape::Cuboid(cuboid_shape) => {
+            let half_extents = cuboid_shape.half_extents;
+            Some(cuboid(
+                half_extents.x,
+                half_extents.y,
+                half_extents.z,
+                textures,
+            ))
+        }
+        TypedShape::RoundCuboid(round) => {
+            let half_extents = round.inner_shape.half_extents;
+            Some(cuboid(
+                half_extents.x,
+                half_extents.y,
+                half_extents.z,
+                textures,
+            ))
+        }
+        TypedShape::Capsule(capsule_shape) => {
+            let mut mesh = capsule(
+                capsule_shape.half_height(),
+                capsule_shape.radius,
+                SEGMENTS,
+                RINGS,
+                textures,
+            );
+            mesh.transform(&capsule_shape.transform_wrt_y().to_homogeneous());
+            Some(mesh)
+        }
+        TypedShape::Cylinder(shape) => Some(cylinder(
+            shape.half_height,
+            shape.radius,
+            SEGMENTS,
+            textures,
+        )),
+        TypedShape::RoundCylinder(round) => Some(cylinder(
+            round.inner_shape.half_height,
+            round.inner_shape.radius,
+            SEGMENTS,
+            textures,
+        )),
+        TypedShape::Cone(shape) => Some(cone(shape.half_height, shape.radius, SEGMENTS, textures)),
+        TypedShape::RoundCone(round) => Some(cone(
+            round.inner_shape.half_height,
+            round.inner_shape.radius,
+            SEGMENTS,
+            textures,
+        )),
+        TypedShape::ConvexPolyhedron(polyhedron) => {
+            let (points, triangles) = polyhedron.to_trimesh();
+            Some(flat_mesh(&points, &triangles, textures))
+        }
+        TypedShape::RoundConvexPolyhedron(round) => {
+            let (points, triangles) = round.inner_shape.to_trimesh();
+            Some(flat_mesh(&points, &triangles, textures))
+        }
+        TypedShape::TriMesh(trimesh) => {
+            Some(flat_mesh(trimesh.vertices(), trimesh.indices(), textures))
+        }
+        TypedShape::HeightField(heightfield) => {
+            let (points, triangles) = heightfield.to_trimesh();
+            Some(flat_mesh(&points, &triangles, textures))
+        }
+        TypedShape::Triangle(triangle) => Some(flat_mesh(
+            &[triangle.a, triangle.b, triangle.c],
+            &[[0, 1, 2]],
+            textures,
+        )),
+        TypedShape::RoundTriangle(round) => {
+            let triangle = &round.inner_shape;
+            Some(flat_mesh(
+                &[triangle.a, triangle.b, triangle.c],
+                &[[0, 1, 2]],
+                textures,
+            ))
+        }
+        TypedShape::Compound(compound) => {
+            let meshes = compound
+                .shapes()
+                .iter()
+                .filter_map(|(isometry, shape)| {
+                    shape_mesh(shape.as_ref(), textures.clone())
+                        .map(|mesh| (mesh, isometry.to_homogeneous()))
+                })
+                .collect::<Vec<_>>();
+            Mesh::merge(&meshes)
+        }
+        _ => None,
+    }
+}
+
+pub fn collider_mesh(collider: &Collider, textures: Vec<TextureInfo>) -> Option<Mesh> {
+    shape_mesh(collider.shape(), textures)
+}
@@ -0,0 +1,23 @@
+use nalgebra::Vector2;
+
+use crate::rendering::model::mesh::{Mesh, TextureInfo};
+use crate::rendering::model::revolution::{revolve, ProfilePoint};
+
+pub fn cone(half_height: f32, radius: f32, segments: usize, textures: Vec<TextureInfo>) -> Mesh {
+    let down = Vector2::new(0f32, -1f32);
+    let side = Vector2::new(2f32 * half_height, radius);
+    revolve(
+        &[
+            vec![
+                ProfilePoint::new(0f32, -half_height, down),
+                ProfilePoint::new(radius, -half_height, down),
+            ],
+            vec![
+                ProfilePoint::new(radius, -half_height, side),
+                ProfilePoint::new(0f32, half_height, side),
+            ],
+        ],
+        segments,
+        textures,
+    )
+}
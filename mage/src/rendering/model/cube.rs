@@ -1,6 +1,6 @@
 use nalgebra::{Vector2, Vector3};
 
-use crate::rendering::model::mesh::{Mesh, TextureInfo};
+use crate::rendering::model::mesh::{next_revision, Mesh, TextureInfo};
 use crate::rendering::opengl::DrawingMode;
 
 const VERTICES: [Vector3<f32>; 36] = [
@@ -121,16 +121,42 @@ const TEXTURE_COORDINATES: [Vector2<f32>; 36] = [
 ];
 
 pub fn cube(textures: Vec<TextureInfo>) -> Mesh {
-    Mesh {
+    let mut mesh = Mesh {
         bitangents: None,
+        colors: None,
         drawing_mode: DrawingMode::Triangles,
         indices: None,
+        joints: None,
         normals: Some(NORMALS.to_vec()),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
         shininess: None,
         tangents: None,
         textures: Some(textures),
         texture_coordinates: Some(TEXTURE_COORDINATES.to_vec()),
         vertices: VERTICES.to_vec(),
+        weights: None,
+    };
+    mesh.generate_tangents();
+    mesh
+}
+
+pub(crate) fn unit_cube() -> Mesh {
+    Mesh {
+        bitangents: None,
+        colors: None,
+        drawing_mode: DrawingMode::Triangles,
+        indices: None,
+        joints: None,
+        normals: None,
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
+        shininess: None,
+        tangents: None,
+        textures: None,
+        texture_coordinates: None,
+        vertices: VERTICES.to_vec(),
+        weights: None,
     }
 }
 
@@ -138,15 +164,22 @@ pub fn cuboid(hx: f32, hy: f32, hz: f32, textures: Vec<TextureInfo>) -> Mesh {
     let vertices = VERTICES
         .iter()
         .map(|v| Vector3::new(v.x * hx, v.y * hy, v.z * hz));
-    Mesh {
+    let mut mesh = Mesh {
         bitangents: None,
+        colors: None,
         drawing_mode: DrawingMode::Triangles,
         indices: None,
+        joints: None,
         normals: Some(NORMALS.to_vec()),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
         shininess: None,
         tangents: None,
         textures: Some(textures),
         texture_coordinates: Some(TEXTURE_COORDINATES.to_vec()),
         vertices: vertices.collect(),
-    }
+        weights: None,
+    };
+    mesh.generate_tangents();
+    mesh
 }
@@ -0,0 +1,33 @@
+use nalgebra::Vector2;
+
+use crate::rendering::model::mesh::{Mesh, TextureInfo};
+use crate::rendering::model::revolution::{revolve, ProfilePoint};
+
+pub fn cylinder(
+    half_height: f32,
+    radius: f32,
+    segments: usize,
+    textures: Vec<TextureInfo>,
+) -> Mesh {
+    let down = Vector2::new(0f32, -1f32);
+    let up = Vector2::new(0f32, 1f32);
+    let side = Vector2::new(1f32, 0f32);
+    revolve(
+        &[
+            vec![
+                ProfilePoint::new(0f32, -half_height, down),
+                ProfilePoint::new(radius, -half_height, down),
+            ],
+            vec![
+                ProfilePoint::new(radius, -half_height, side),
+                ProfilePoint::new(radius, half_height, side),
+            ],
+            vec![
+                ProfilePoint::new(radius, half_height, up),
+                ProfilePoint::new(0f32, half_height, up),
+            ],
+        ],
+        segments,
+        textures,
+    )
+}
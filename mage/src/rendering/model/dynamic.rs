@@ -0,0 +1,175 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::rendering::model::mesh::{fill_buffer, load_textures, Mesh, RenderingMesh};
+use crate::rendering::opengl::buffer::{Buffer, BufferType, BufferUsage};
+use crate::rendering::opengl::vertex_array::VertexArray;
+use crate::resources::texture::TextureLoader;
+use crate::MageError;
+
+#[derive(Clone, Debug)]
+pub struct DynamicMesh {
+    dirty: Option<Range<usize>>,
+    pub keep_mesh: bool,
+    pub usage: BufferUsage,
+}
+
+impl DynamicMesh {
+    pub fn new(usage: BufferUsage) -> DynamicMesh {
+        DynamicMesh {
+            dirty: None,
+            keep_mesh: false,
+            usage,
+        }
+    }
+
+    pub fn dynamic() -> DynamicMesh {
+        DynamicMesh::new(BufferUsage::DynamicDraw)
+    }
+
+    pub fn streaming() -> DynamicMesh {
+        DynamicMesh::new(BufferUsage::StreamDraw)
+    }
+
+    pub fn keeping_mesh(mut self) -> DynamicMesh {
+        self.keep_mesh = true;
+        self
+    }
+
+    // Marks a range of vertices as the only data that changed since the last
+    // upload, so indices and the rest of the vertices are left untouched.
+    pub fn mark_dirty(&mut self, vertices: Range<usize>) {
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(vertices.start)..dirty.end.max(vertices.end),
+            None => vertices,
+        });
+    }
+
+    pub(crate) fn take_dirty(&mut self) -> Option<Range<usize>> {
+        self.dirty.take()
+    }
+}
+
+impl Default for DynamicMesh {
+    fn default() -> Self {
+        DynamicMesh::dynamic()
+    }
+}
+
+impl RenderingMesh {
+    pub fn is_dynamic(&self) -> bool {
+        !matches!(
+            self.usage,
+            BufferUsage::StaticDraw | BufferUsage::StaticRead | BufferUsage::StaticCopy
+        )
+    }
+
+    pub fn update(
+        &mut self,
+        mesh: &Mesh,
+        dirty: Option<Range<usize>>,
+        loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
+        mesh.validate()?;
+        let layout = mesh.layout();
+        let stride = layout.stride() as usize;
+        let vertices = mesh.vertices.len();
+        let relayout = layout != self.layout;
+        let grow = vertices * stride > self.vertex_capacity;
+        let partial = dirty.filter(|_| !relayout && !grow && vertices == self.vertices);
+        self.vertex_array.bind();
+        self.array_buffer.bind();
+        match partial {
+            Some(range) => {
+                let range = range.start.min(vertices)..range.end.min(vertices);
+                if !range.is_empty() {
+                    let data = mesh.interleave_range(&layout, range.clone());
+                    self.array_buffer
+                        .set_sub_data(range.start * stride, data.len(), &data);
+                }
+            }
+            None => {
+                let data = mesh.interleave(&layout);
+                if grow {
+                    self.vertex_capacity = data.len().max(self.vertex_capacity * 2);
+                }
+                fill_buffer(&self.array_buffer, &data, self.vertex_capacity, self.usage);
+                self.update_indices(mesh);
+            }
+        }
+        if relayout {
+            self.layout.unbind();
+            layout.bind();
+            self.layout = layout;
+        }
+        VertexArray::unbind();
+
+        let texture_fingerprint = mesh.texture_fingerprint();
+        if texture_fingerprint != self.texture_fingerprint {
+            self.textures = load_textures(&mesh.textures, loader)?;
+            self.texture_infos = mesh.textures.clone();
+            self.texture_fingerprint = texture_fingerprint;
+        }
+        self.bounds = mesh.bounds();
+        self.drawing_mode = mesh.drawing_mode;
+        self.elements = mesh.len_vertices();
+        self.revision = mesh.revision();
+        self.shininess = mesh.shininess;
+        self.vertices = vertices;
+        if let Some(copy) = self.mesh.as_mut() {
+            *copy = mesh.clone();
+        }
+        Ok(())
+    }
+
+    // Expects the vertex array to be bound, so dropping the element buffer
+    // also detaches it from the vertex array.
+    fn update_indices(&mut self, mesh: &Mesh) {
+        let indices = match &mesh.indices {
+            Some(indices) => indices,
+            None => {
+                self.element_buffer = None;
+                self.index_capacity = 0;
+                return;
+            }
+        };
+        let element_buffer = self
+            .element_buffer
+            .get_or_insert_with(|| Arc::new(Buffer::new(BufferType::ElementArray)));
+        element_buffer.bind();
+        if indices.len() > self.index_capacity {
+            self.index_capacity = indices.len().max(self.index_capacity * 2);
+        }
+        fill_buffer(element_buffer, indices, self.index_capacity, self.usage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DynamicMesh;
+    use crate::rendering::opengl::buffer::BufferUsage;
+
+    #[test]
+    fn mark_dirty_merges_ranges() {
+        let mut dynamic = DynamicMesh::dynamic();
+        assert_eq!(dynamic.take_dirty(), None);
+        dynamic.mark_dirty(4..6);
+        dynamic.mark_dirty(1..2);
+        dynamic.mark_dirty(5..9);
+        assert_eq!(dynamic.take_dirty(), Some(1..9));
+        assert_eq!(dynamic.take_dirty(), None);
+    }
+
+    #[test]
+    fn constructors_pick_buffer_usage() {
+        assert!(matches!(
+            DynamicMesh::default().usage,
+            BufferUsage::DynamicDraw
+        ));
+        assert!(matches!(
+            DynamicMesh::streaming().usage,
+            BufferUsage::StreamDraw
+        ));
+        assert!(DynamicMesh::streaming().keeping_mesh().keep_mesh);
+    }
+}
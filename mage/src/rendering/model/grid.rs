@@ -0,0 +1,55 @@
+use nalgebra::{Vector2, Vector3};
+
+use crate::rendering::model::mesh::{next_revision, Mesh, TextureInfo};
+use crate::rendering::opengl::DrawingMode;
+
+pub fn grid(
+    width: f32,
+    depth: f32,
+    x_segments: usize,
+    z_segments: usize,
+    textures: Vec<TextureInfo>,
+) -> Mesh {
+    let x_segments = x_segments.max(1);
+    let z_segments = z_segments.max(1);
+    let mut vertices = vec![];
+    let mut texture_coordinates = vec![];
+    for z in 0..z_segments + 1 {
+        for x in 0..x_segments + 1 {
+            let u = x as f32 / x_segments as f32;
+            let v = z as f32 / z_segments as f32;
+            vertices.push(Vector3::new((u - 0.5) * width, 0f32, (v - 0.5) * depth));
+            texture_coordinates.push(Vector2::new(u, 1f32 - v));
+        }
+    }
+
+    let columns = x_segments as u32 + 1;
+    let mut indices = vec![];
+    for z in 0..z_segments as u32 {
+        for x in 0..x_segments as u32 {
+            let a = z * columns + x;
+            let b = a + 1;
+            let c = a + columns;
+            let d = c + 1;
+            indices.extend([a, c, b, b, c, d]);
+        }
+    }
+
+    let count = vertices.len();
+    Mesh {
+        bitangents: Some(vec![Vector3::new(0f32, 0f32, -1f32); count]),
+        colors: None,
+        drawing_mode: DrawingMode::Triangles,
+        indices: Some(indices),
+        joints: None,
+        normals: Some(vec![Vector3::new(0f32, 1f32, 0f32); count]),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
+        shininess: None,
+        tangents: Some(vec![Vector3::new(1f32, 0f32, 0f32); count]),
+        textures: Some(textures),
+        texture_coordinates: Some(texture_coordinates),
+        vertices,
+        weights: None,
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use nalgebra::{Vector2, Vector3};
+use num_traits::FloatConst;
+
+use crate::rendering::model::mesh::{next_revision, Mesh, TextureInfo};
+use crate::rendering::opengl::DrawingMode;
+
+const POLE_EPSILON: f32 = 1e-6;
+
+const FACES: [[u32; 3]; 20] = [
+    [0, 11, 5],
+    [0, 5, 1],
+    [0, 1, 7],
+    [0, 7, 10],
+    [0, 10, 11],
+    [1, 5, 9],
+    [5, 11, 4],
+    [11, 10, 2],
+    [10, 7, 6],
+    [7, 1, 8],
+    [3, 9, 4],
+    [3, 4, 2],
+    [3, 2, 6],
+    [3, 6, 8],
+    [3, 8, 9],
+    [4, 9, 5],
+    [2, 4, 11],
+    [6, 2, 10],
+    [8, 6, 7],
+    [9, 8, 1],
+];
+
+fn icosahedron() -> Vec<Vector3<f32>> {
+    let t = (1f32 + 5f32.sqrt()) / 2f32;
+    [
+        (-1f32, t, 0f32),
+        (1f32, t, 0f32),
+        (-1f32, -t, 0f32),
+        (1f32, -t, 0f32),
+        (0f32, -1f32, t),
+        (0f32, 1f32, t),
+        (0f32, -1f32, -t),
+        (0f32, 1f32, -t),
+        (t, 0f32, -1f32),
+        (t, 0f32, 1f32),
+        (-t, 0f32, -1f32),
+        (-t, 0f32, 1f32),
+    ]
+    .iter()
+    .map(|&(x, y, z)| Vector3::new(x, y, z).normalize())
+    .collect()
+}
+
+fn midpoint(
+    directions: &mut Vec<Vector3<f32>>,
+    cache: &mut HashMap<(u32, u32), u32>,
+    a: u32,
+    b: u32,
+) -> u32 {
+    let key = (a.min(b), a.max(b));
+    *cache.entry(key).or_insert_with(|| {
+        let direction = (directions[a as usize] + directions[b as usize]).normalize();
+        directions.push(direction);
+        directions.len() as u32 - 1
+    })
+}
+
+fn is_pole(direction: &Vector3<f32>) -> bool {
+    direction.x.abs() < POLE_EPSILON && direction.z.abs() < POLE_EPSILON
+}
+
+fn texture_coordinate(direction: &Vector3<f32>) -> Vector2<f32> {
+    Vector2::new(
+        direction.z.atan2(direction.x) / (2f32 * f32::PI()) + 0.5,
+        direction.y.clamp(-1f32, 1f32).asin() / f32::PI() + 0.5,
+    )
+}
+
+fn duplicate(
+    directions: &mut Vec<Vector3<f32>>,
+    texture_coordinates: &mut Vec<Vector2<f32>>,
+    index: u32,
+    texture_coordinate: Vector2<f32>,
+) -> u32 {
+    directions.push(directions[index as usize]);
+    texture_coordinates.push(texture_coordinate);
+    directions.len() as u32 - 1
+}
+
+// Each face takes the u of its centroid as reference: vertices across the seam get copies
+// shifted by a whole turn, and pole vertices get a copy at the reference u
+fn split_seams(
+    directions: &mut Vec<Vector3<f32>>,
+    texture_coordinates: &mut Vec<Vector2<f32>>,
+    faces: &mut [[u32; 3]],
+) {
+    let mut wrapped = HashMap::new();
+    for face in faces.iter_mut() {
+        let centroid = face
+            .iter()
+            .map(|index| directions[*index as usize])
+            .sum::<Vector3<f32>>();
+        let reference = texture_coordinate(&centroid.normalize()).x;
+        for index in face.iter_mut() {
+            let original = *index;
+            let uv = texture_coordinates[original as usize];
+            if is_pole(&directions[original as usize]) {
+                let pole = Vector2::new(reference, uv.y);
+                *index = duplicate(directions, texture_coordinates, original, pole);
+                continue;
+            }
+            let shift = (reference - uv.x).round();
+            if shift != 0f32 {
+                *index = *wrapped.entry((original, shift as i32)).or_insert_with(|| {
+                    let shifted = uv + Vector2::new(shift, 0f32);
+                    duplicate(directions, texture_coordinates, original, shifted)
+                });
+            }
+        }
+    }
+}
+
+pub fn icosphere(radius: f32, subdivisions: usize, textures: Vec<TextureInfo>) -> Mesh {
+    let mut directions = icosahedron();
+    let mut faces = FACES.to_vec();
+    for _ in 0..subdivisions {
+        let mut cache = HashMap::new();
+        faces = faces
+            .iter()
+            .flat_map(|&[a, b, c]| {
+                let ab = midpoint(&mut directions, &mut cache, a, b);
+                let bc = midpoint(&mut directions, &mut cache, b, c);
+                let ca = midpoint(&mut directions, &mut cache, c, a);
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    let mut texture_coordinates = directions.iter().map(texture_coordinate).collect();
+    split_seams(&mut directions, &mut texture_coordinates, &mut faces);
+    let tangents = directions
+        .iter()
+        .map(|d| {
+            Vector3::new(-d.z, 0f32, d.x)
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(Vector3::x)
+        })
+        .collect::<Vec<_>>();
+    let bitangents = tangents
+        .iter()
+        .zip(directions.iter())
+        .map(|(tangent, normal)| tangent.cross(normal))
+        .collect();
+
+    Mesh {
+        bitangents: Some(bitangents),
+        colors: None,
+        drawing_mode: DrawingMode::Triangles,
+        indices: Some(faces.into_iter().flatten().collect()),
+        joints: None,
+        normals: Some(directions.clone()),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
+        shininess: None,
+        tangents: Some(tangents),
+        textures: Some(textures),
+        texture_coordinates: Some(texture_coordinates),
+        vertices: directions.iter().map(|d| d * radius).collect(),
+        weights: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::icosphere;
+
+    #[test]
+    fn faces_do_not_span_the_texture_seam() {
+        for subdivisions in 0..3 {
+            let mesh = icosphere(1f32, subdivisions, vec![]);
+            let uvs = mesh.texture_coordinates.unwrap();
+            for face in mesh.indices.unwrap().chunks(3) {
+                let us = face.iter().map(|i| uvs[*i as usize].x).collect::<Vec<_>>();
+                let min = us.iter().copied().fold(f32::INFINITY, f32::min);
+                let max = us.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                assert!(
+                    max - min <= 0.5 + f32::EPSILON,
+                    "face {:?} spans {:?}",
+                    face,
+                    us
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn duplicated_vertices_stay_on_the_sphere() {
+        let mesh = icosphere(2f32, 2, vec![]);
+        let normals = mesh.normals.unwrap();
+        assert_eq!(mesh.vertices.len(), normals.len());
+        assert_eq!(mesh.vertices.len(), mesh.texture_coordinates.unwrap().len());
+        for (vertex, normal) in mesh.vertices.iter().zip(normals.iter()) {
+            assert!((vertex - normal * 2f32).norm() < 1e-5);
+        }
+    }
+}
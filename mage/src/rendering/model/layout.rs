@@ -0,0 +1,172 @@
+use crate::rendering::opengl::vertex_array::{DataType, VertexArray};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VertexSemantic {
+    Bitangent,
+    Color,
+    Joints,
+    Normal,
+    Position,
+    SecondaryTextureCoordinates,
+    Tangent,
+    TextureCoordinates,
+    Weights,
+}
+
+impl VertexSemantic {
+    // Locations 5 to 8 are taken by the per instance model matrix.
+    pub fn location(&self) -> u32 {
+        match self {
+            VertexSemantic::Position => 0,
+            VertexSemantic::Normal => 1,
+            VertexSemantic::TextureCoordinates => 2,
+            VertexSemantic::Tangent => 3,
+            VertexSemantic::Bitangent => 4,
+            VertexSemantic::Joints => 9,
+            VertexSemantic::Weights => 10,
+            VertexSemantic::Color => 11,
+            VertexSemantic::SecondaryTextureCoordinates => 12,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VertexSemantic::Bitangent => "aBitangent",
+            VertexSemantic::Color => "aColor",
+            VertexSemantic::Joints => "aJoints",
+            VertexSemantic::Normal => "aNormal",
+            VertexSemantic::Position => "aPos",
+            VertexSemantic::SecondaryTextureCoordinates => "aTexCoord1",
+            VertexSemantic::Tangent => "aTangent",
+            VertexSemantic::TextureCoordinates => "aTexCoord",
+            VertexSemantic::Weights => "aWeights",
+        }
+    }
+
+    pub fn components(&self) -> u32 {
+        match self {
+            VertexSemantic::TextureCoordinates | VertexSemantic::SecondaryTextureCoordinates => 2,
+            VertexSemantic::Position
+            | VertexSemantic::Normal
+            | VertexSemantic::Tangent
+            | VertexSemantic::Bitangent => 3,
+            VertexSemantic::Color | VertexSemantic::Joints | VertexSemantic::Weights => 4,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VertexAttribute {
+    pub components: u32,
+    pub data_type: DataType,
+    pub offset: u32,
+    pub semantic: VertexSemantic,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VertexLayout {
+    attributes: Vec<VertexAttribute>,
+    stride: u32,
+}
+
+impl VertexLayout {
+    pub fn new(semantics: &[VertexSemantic]) -> VertexLayout {
+        let mut attributes = Vec::with_capacity(semantics.len());
+        let mut stride = 0;
+        for semantic in semantics.iter() {
+            attributes.push(VertexAttribute {
+                components: semantic.components(),
+                data_type: DataType::Float,
+                offset: stride,
+                semantic: *semantic,
+            });
+            stride += semantic.components();
+        }
+        VertexLayout { attributes, stride }
+    }
+
+    pub fn attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+
+    pub fn attribute(&self, semantic: VertexSemantic) -> Option<&VertexAttribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.semantic == semantic)
+    }
+
+    pub fn contains(&self, semantic: VertexSemantic) -> bool {
+        self.attribute(semantic).is_some()
+    }
+
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    pub(crate) fn bind(&self) {
+        for attribute in self.attributes.iter() {
+            VertexArray::set_vertex_attrib_with_padding::<f32>(
+                attribute.data_type,
+                attribute.semantic.location(),
+                self.stride,
+                attribute.components,
+                attribute.offset,
+                false,
+            );
+        }
+    }
+
+    pub(crate) fn unbind(&self) {
+        for attribute in self.attributes.iter() {
+            VertexArray::disable_vertex_attrib(attribute.semantic.location());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Vector2, Vector3};
+
+    use super::{VertexLayout, VertexSemantic};
+    use crate::rendering::model::mesh::Mesh;
+
+    #[test]
+    fn attributes_are_packed_in_order() {
+        let layout = VertexLayout::new(&[
+            VertexSemantic::Position,
+            VertexSemantic::TextureCoordinates,
+            VertexSemantic::Color,
+        ]);
+        let offsets = layout
+            .attributes()
+            .iter()
+            .map(|attribute| (attribute.offset, attribute.components))
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![(0, 3), (3, 2), (5, 4)]);
+        assert_eq!(layout.stride(), 9);
+        assert!(!layout.contains(VertexSemantic::Normal));
+        assert_eq!(
+            layout.attribute(VertexSemantic::Color).map(|a| a.offset),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn mesh_layout_matches_interleaved_data() {
+        let mesh = Mesh {
+            texture_coordinates: Some(vec![Vector2::new(0.5f32, 0.25f32); 2]),
+            vertices: vec![
+                Vector3::new(1f32, 2f32, 3f32),
+                Vector3::new(4f32, 5f32, 6f32),
+            ],
+            ..Mesh::default()
+        };
+        let layout = mesh.layout();
+        assert_eq!(layout.stride(), 5);
+        assert_eq!(
+            mesh.interleave(&layout),
+            vec![1f32, 2f32, 3f32, 0.5f32, 0.25f32, 4f32, 5f32, 6f32, 0.5f32, 0.25f32]
+        );
+        assert_eq!(mesh.interleave_range(&layout, 1..2).len(), 5);
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use nalgebra::Vector3;
+
+#[derive(Clone, Debug)]
+pub struct PbrMaterial {
+    pub albedo: Vector3<f32>,
+    pub ao: f32,
+    pub height_scale: f32,
+    pub metalness: f32,
+    pub opacity: f32,
+    pub roughness: f32,
+}
+
+impl Default for PbrMaterial {
+    fn default() -> PbrMaterial {
+        PbrMaterial {
+            albedo: Vector3::new(1f32, 1f32, 1f32),
+            ao: 1f32,
+            height_scale: 0.05,
+            metalness: 0f32,
+            opacity: 1f32,
+            roughness: 0.5f32,
+        }
+    }
+}
+
+impl PbrMaterial {
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for value in self.albedo.iter().chain(&[
+            self.ao,
+            self.height_scale,
+            self.metalness,
+            self.opacity,
+            self.roughness,
+        ]) {
+            value.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
@@ -1,30 +1,88 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
-use itertools::multizip;
-use nalgebra::{ArrayStorage, Matrix, Vector2, Vector3, U1};
+use nalgebra::{Dim, RawStorage, Vector, Vector2, Vector3, Vector4};
 use russimp::texture::TextureType;
 
+use crate::rendering::model::bounds::Bounds;
+use crate::rendering::model::dynamic::DynamicMesh;
+use crate::rendering::model::layout::{VertexLayout, VertexSemantic};
 use crate::rendering::opengl::buffer::{Buffer, BufferType, BufferUsage};
 use crate::rendering::opengl::program::Program;
 use crate::rendering::opengl::texture::{Texture, TextureParameter, TextureParameterValue};
-use crate::rendering::opengl::vertex_array::{DataType, VertexArray};
-use crate::rendering::opengl::{draw_arrays, draw_elements, DrawingMode, OpenGlType};
+use crate::rendering::opengl::vertex_array::VertexArray;
+use crate::rendering::opengl::{
+    draw_arrays, draw_arrays_instanced, draw_elements, draw_elements_instanced, DrawingMode,
+    OpenGlType,
+};
 use crate::resources::texture::TextureLoader;
 use crate::MageError;
 
-fn flattened_vectors(vectors: &[Vector3<f32>]) -> Vec<f32> {
-    vectors
-        .iter()
-        .flat_map(|v| v.data.as_slice())
-        .cloned()
-        .collect::<Vec<f32>>()
+fn extend_with<D: Dim, S: RawStorage<f32, D>>(
+    data: &mut Vec<f32>,
+    values: &Option<Vec<Vector<f32, D, S>>>,
+    i: usize,
+) {
+    if let Some(values) = values {
+        data.extend(values[i].iter());
+    }
+}
+
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+pub(crate) fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+pub(crate) fn hash_textures(textures: &Option<Vec<TextureInfo>>, hasher: &mut DefaultHasher) {
+    if let Some(textures) = textures {
+        for texture in textures {
+            texture.id.hash(hasher);
+            texture.texture_type.hash(hasher);
+            texture.source.hash(hasher);
+            let mut parameters = 0u64;
+            for parameter in &texture.parameters {
+                let mut parameter_hasher = DefaultHasher::new();
+                parameter.hash(&mut parameter_hasher);
+                parameters = parameters.wrapping_add(parameter_hasher.finish());
+            }
+            parameters.hash(hasher);
+        }
+    }
+}
+
+pub(crate) fn load_textures(
+    texture_infos: &Option<Vec<TextureInfo>>,
+    loader: &mut TextureLoader,
+) -> Result<Vec<Arc<Texture>>, MageError> {
+    let mut textures = vec![];
+    if let Some(texture_infos) = texture_infos {
+        for texture_info in texture_infos.iter() {
+            textures.push(loader.load_texture_2d(texture_info)?);
+        }
+    }
+    Ok(textures)
+}
+
+pub(crate) fn fill_buffer<T>(buffer: &Buffer, data: &[T], capacity: usize, usage: BufferUsage) {
+    buffer.allocate_data_with_usage::<T>(capacity, usage);
+    if !data.is_empty() {
+        buffer.set_sub_data(0, data.len(), data);
+    }
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum TextureSource {
     File(String),
+    Memory(Arc<Vec<u8>>),
     Color(Vector3<u8>),
+    CubeMap([String; 6]),
+    CubeMapCross(String),
+    Equirectangular(String),
 }
 
 #[derive(Clone, Debug)]
@@ -35,17 +93,46 @@ pub struct TextureInfo {
     pub parameters: HashMap<TextureParameter, TextureParameterValue>,
 }
 
+// Build meshes with `..Mesh::default()` for the attributes they lack. The
+// engine only uploads a mesh again when its revision changes, so call
+// `mark_changed` after editing the public fields of a mesh already in the world.
 #[derive(Clone, Debug)]
 pub struct Mesh {
     pub bitangents: Option<Vec<Vector3<f32>>>,
+    pub colors: Option<Vec<Vector4<f32>>>,
     pub drawing_mode: DrawingMode,
     pub indices: Option<Vec<u32>>,
+    pub joints: Option<Vec<Vector4<u32>>>,
     pub normals: Option<Vec<Vector3<f32>>>,
+    pub(crate) revision: u64,
+    pub secondary_texture_coordinates: Option<Vec<Vector2<f32>>>,
     pub shininess: Option<f32>,
     pub tangents: Option<Vec<Vector3<f32>>>,
     pub textures: Option<Vec<TextureInfo>>,
     pub texture_coordinates: Option<Vec<Vector2<f32>>>,
     pub vertices: Vec<Vector3<f32>>,
+    pub weights: Option<Vec<Vector4<f32>>>,
+}
+
+impl Default for Mesh {
+    fn default() -> Mesh {
+        Mesh {
+            bitangents: None,
+            colors: None,
+            drawing_mode: DrawingMode::Triangles,
+            indices: None,
+            joints: None,
+            normals: None,
+            revision: next_revision(),
+            secondary_texture_coordinates: None,
+            shininess: None,
+            tangents: None,
+            textures: None,
+            texture_coordinates: None,
+            vertices: vec![],
+            weights: None,
+        }
+    }
 }
 
 impl Mesh {
@@ -57,253 +144,248 @@ impl Mesh {
         }
     }
 
-    pub fn to_rendering_mesh(&self) -> Result<RenderingMesh, MageError> {
-        let size = self.size() as u32;
-        let mut attribute = 0;
-        let mut start = 0;
-        let vertex_array = Arc::new(VertexArray::new());
-        let array_buffer = Arc::new(Buffer::new(BufferType::Array));
-        vertex_array.bind();
-        array_buffer.bind();
-        array_buffer.set_data(&self.flattened_data(), BufferUsage::StaticDraw);
-        let element_buffer = if let Some(indices) = &self.indices {
-            let element_buffer = Buffer::new(BufferType::ElementArray);
-            element_buffer.bind();
-            element_buffer.set_data(indices, BufferUsage::StaticDraw);
-            Some(Arc::new(element_buffer))
-        } else {
-            None
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    // Rendering meshes are only rebuilt when the revision changes. The mesh
+    // operations already bump it, edits to the public fields need this call.
+    pub fn mark_changed(&mut self) {
+        self.revision = next_revision();
+    }
+
+    pub(crate) fn texture_fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_textures(&self.textures, &mut hasher);
+        hasher.finish()
+    }
+
+    pub fn is_skinned(&self) -> bool {
+        self.joints.is_some() && self.weights.is_some()
+    }
+
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        let indices = match &self.indices {
+            Some(indices) => indices.iter().map(|&i| i as usize).collect::<Vec<usize>>(),
+            None => (0..self.vertices.len()).collect(),
         };
-        VertexArray::set_vertex_attrib_with_padding::<f32>(
-            DataType::Float,
-            attribute,
-            size,
-            3,
-            start,
-            false,
-        );
-        start += 3;
-        attribute += 1;
-        if self.normals.is_some() {
-            VertexArray::set_vertex_attrib_with_padding::<f32>(
-                DataType::Float,
-                attribute,
-                size,
-                3,
-                start,
-                false,
-            );
-            start += 3;
-            attribute += 1;
-        }
-        if self.texture_coordinates.is_some() {
-            VertexArray::set_vertex_attrib_with_padding::<f32>(
-                DataType::Float,
-                attribute,
-                size,
-                2,
-                start,
-                false,
-            );
-            start += 2;
-            attribute += 1;
+        match self.drawing_mode {
+            DrawingMode::Triangles => indices
+                .chunks_exact(3)
+                .map(|t| [t[0], t[1], t[2]])
+                .collect(),
+            DrawingMode::TriangleStrip => indices
+                .windows(3)
+                .enumerate()
+                .map(|(i, t)| {
+                    if i % 2 == 0 {
+                        [t[0], t[1], t[2]]
+                    } else {
+                        [t[1], t[0], t[2]]
+                    }
+                })
+                .filter(|t| t[0] != t[1] && t[1] != t[2] && t[0] != t[2])
+                .collect(),
         }
-        if self.tangents.is_some() {
-            VertexArray::set_vertex_attrib_with_padding::<f32>(
-                DataType::Float,
-                attribute,
-                size,
-                3,
-                start,
-                false,
-            );
-            start += 3;
-            attribute += 1;
-        }
-        if self.bitangents.is_some() {
-            VertexArray::set_vertex_attrib_with_padding::<f32>(
-                DataType::Float,
-                attribute,
-                size,
-                3,
-                start,
-                false,
-            );
+    }
+
+    pub fn generate_tangents(&mut self) {
+        if self.generate_mikktspace_tangents().is_err() {
+            self.generate_uv_tangents();
         }
-        let mut textures = vec![];
+    }
 
-        if let Some(texture_infos) = &self.textures {
-            let mut loader = TextureLoader::new();
-            for texture_info in texture_infos.iter() {
-                textures.push(loader.load_texture_2d(texture_info)?);
+    fn generate_uv_tangents(&mut self) {
+        let texture_coordinates = match &self.texture_coordinates {
+            Some(texture_coordinates) => texture_coordinates,
+            None => return,
+        };
+        let mut tangents = vec![Vector3::zeros(); self.vertices.len()];
+        let mut bitangents = vec![Vector3::zeros(); self.vertices.len()];
+        for [a, b, c] in self.triangles() {
+            let edge1 = self.vertices[b] - self.vertices[a];
+            let edge2 = self.vertices[c] - self.vertices[a];
+            let delta_uv1 = texture_coordinates[b] - texture_coordinates[a];
+            let delta_uv2 = texture_coordinates[c] - texture_coordinates[a];
+            let determinant = delta_uv1.x * delta_uv2.y - delta_uv2.x * delta_uv1.y;
+            if determinant.abs() < f32::EPSILON {
+                continue;
+            }
+            let f = 1f32 / determinant;
+            let tangent = (edge1 * delta_uv2.y - edge2 * delta_uv1.y) * f;
+            let bitangent = (edge2 * delta_uv1.x - edge1 * delta_uv2.x) * f;
+            for i in [a, b, c] {
+                tangents[i] += tangent;
+                bitangents[i] += bitangent;
             }
         }
-        VertexArray::unbind();
-        Ok(RenderingMesh {
-            array_buffer,
-            element_buffer,
-            textures,
-            vertex_array,
-            mesh: self.clone(),
-        })
+        for i in 0..self.vertices.len() {
+            if let Some(normal) = self.normals.as_ref().and_then(|n| n[i].try_normalize(0f32)) {
+                let projection = normal * normal.dot(&tangents[i]);
+                tangents[i] -= projection;
+            }
+            tangents[i] = tangents[i].try_normalize(0f32).unwrap_or_else(Vector3::x);
+            bitangents[i] = bitangents[i].try_normalize(0f32).unwrap_or_else(Vector3::y);
+        }
+        self.tangents = Some(tangents);
+        self.bitangents = Some(bitangents);
+        self.mark_changed();
     }
 
-    pub fn size(&self) -> usize {
-        3 + self.normals.as_ref().map(|_| 3).unwrap_or(0)
-            + self.texture_coordinates.as_ref().map(|_| 2).unwrap_or(0)
-            + self.tangents.as_ref().map(|_| 3).unwrap_or(0)
-            + self.bitangents.as_ref().map(|_| 3).unwrap_or(0)
+    pub fn layout(&self) -> VertexLayout {
+        let mut semantics = vec![VertexSemantic::Position];
+        let optional = [
+            (self.normals.is_some(), VertexSemantic::Normal),
+            (
+                self.texture_coordinates.is_some(),
+                VertexSemantic::TextureCoordinates,
+            ),
+            (self.tangents.is_some(), VertexSemantic::Tangent),
+            (self.bitangents.is_some(), VertexSemantic::Bitangent),
+            (self.is_skinned(), VertexSemantic::Joints),
+            (self.is_skinned(), VertexSemantic::Weights),
+            (self.colors.is_some(), VertexSemantic::Color),
+            (
+                self.secondary_texture_coordinates.is_some(),
+                VertexSemantic::SecondaryTextureCoordinates,
+            ),
+        ];
+        semantics.extend(
+            optional
+                .into_iter()
+                .filter(|(present, _)| *present)
+                .map(|(_, semantic)| semantic),
+        );
+        VertexLayout::new(&semantics)
     }
 
-    pub fn flattened_data(&self) -> Vec<f32> {
-        match (
-            &self.normals,
-            &self.texture_coordinates,
-            &self.tangents,
-            &self.bitangents,
-        ) {
-            (None, None, None, None) => self.get_flattened_vertices(),
-            (Some(normals), None, None, None) => self.flatten_with_vertices(normals),
-            (None, Some(texture_coordinates), None, None) => {
-                self.flatten_with_vertices(texture_coordinates)
-            }
-            (None, None, Some(tangents), None) => self.flatten_with_vertices(tangents),
-            (None, None, None, Some(bitangents)) => self.flatten_with_vertices(bitangents),
-            (Some(normals), Some(texture_coordinates), None, None) => {
-                self.flatten_two_with_vertices(normals, texture_coordinates)
-            }
-            (Some(normals), None, Some(tangents), None) => {
-                self.flatten_two_with_vertices(normals, tangents)
-            }
-            (Some(normals), None, None, Some(bitangents)) => {
-                self.flatten_two_with_vertices(normals, bitangents)
-            }
-            (None, Some(texture_coordinates), Some(tangents), None) => {
-                self.flatten_two_with_vertices(texture_coordinates, tangents)
-            }
-            (None, Some(texture_coordinates), None, Some(bitangents)) => {
-                self.flatten_two_with_vertices(texture_coordinates, bitangents)
-            }
-            (None, None, Some(tangents), Some(bitangents)) => {
-                self.flatten_two_with_vertices(tangents, bitangents)
+    fn write_attribute(&self, semantic: VertexSemantic, i: usize, data: &mut Vec<f32>) {
+        match semantic {
+            VertexSemantic::Position => data.extend(self.vertices[i].iter()),
+            VertexSemantic::Normal => extend_with(data, &self.normals, i),
+            VertexSemantic::TextureCoordinates => extend_with(data, &self.texture_coordinates, i),
+            VertexSemantic::Tangent => extend_with(data, &self.tangents, i),
+            VertexSemantic::Bitangent => extend_with(data, &self.bitangents, i),
+            VertexSemantic::Joints => {
+                if let Some(joints) = &self.joints {
+                    data.extend(joints[i].iter().map(|&j| j as f32));
+                }
             }
-            (Some(normals), Some(texture_coordinates), Some(tangents), None) => {
-                self.flatten_three_with_vertices(normals, texture_coordinates, tangents)
-            }
-            (Some(normals), Some(texture_coordinates), None, Some(bitangents)) => {
-                self.flatten_three_with_vertices(normals, texture_coordinates, bitangents)
-            }
-            (Some(normals), None, Some(tangents), Some(bitangents)) => {
-                self.flatten_three_with_vertices(normals, tangents, bitangents)
-            }
-            (None, Some(texture_coordinates), Some(tangents), Some(bitangents)) => {
-                self.flatten_three_with_vertices(texture_coordinates, tangents, bitangents)
-            }
-            (Some(normals), Some(texture_coordinates), Some(tangents), Some(bitangents)) => {
-                self.flatten_four_with_vertices(normals, texture_coordinates, tangents, bitangents)
+            VertexSemantic::Weights => extend_with(data, &self.weights, i),
+            VertexSemantic::Color => extend_with(data, &self.colors, i),
+            VertexSemantic::SecondaryTextureCoordinates => {
+                extend_with(data, &self.secondary_texture_coordinates, i)
             }
         }
     }
 
-    pub fn vertex_info_size(&self) -> usize {
-        let normals_size = if self.normals.is_some() { 3 } else { 0 };
-        let textures_size = if self.texture_coordinates.is_some() {
-            2
-        } else {
-            0
-        };
-        let tangents_size = if self.tangents.is_some() { 3 } else { 0 };
-        let bitangents_size = if self.bitangents.is_some() { 3 } else { 0 };
-        3 + normals_size + textures_size + tangents_size + bitangents_size
+    pub fn interleave(&self, layout: &VertexLayout) -> Vec<f32> {
+        self.interleave_range(layout, 0..self.vertices.len())
     }
 
-    fn get_flattened_vertices(&self) -> Vec<f32> {
-        flattened_vectors(&self.vertices)
+    pub fn interleave_range(&self, layout: &VertexLayout, vertices: Range<usize>) -> Vec<f32> {
+        let mut data = Vec::with_capacity(vertices.len() * layout.stride() as usize);
+        for i in vertices {
+            for attribute in layout.attributes() {
+                self.write_attribute(attribute.semantic, i, &mut data);
+            }
+        }
+        data
     }
 
-    fn flatten_two_with_vertices<U, V, const C: usize, const C1: usize>(
+    pub fn to_rendering_mesh(
         &self,
-        first: &[Matrix<f32, U, U1, ArrayStorage<f32, C, 1>>],
-        second: &[Matrix<f32, V, U1, ArrayStorage<f32, C1, 1>>],
-    ) -> Vec<f32> {
-        multizip((self.vertices.iter(), first, second))
-            .flat_map(|(v, n, t)| {
-                let mut d = v.data.as_slice().to_vec();
-                d.extend(n.data.as_slice());
-                d.extend(t.data.as_slice());
-                d
-            })
-            .collect::<Vec<f32>>()
+        loader: &mut TextureLoader,
+    ) -> Result<RenderingMesh, MageError> {
+        self.to_rendering_mesh_with(BufferUsage::StaticDraw, false, loader)
     }
 
-    fn flatten_three_with_vertices<U, V, W, const C: usize, const C1: usize, const C2: usize>(
+    pub fn to_dynamic_rendering_mesh(
         &self,
-        first: &[Matrix<f32, U, U1, ArrayStorage<f32, C, 1>>],
-        second: &[Matrix<f32, V, U1, ArrayStorage<f32, C1, 1>>],
-        third: &[Matrix<f32, W, U1, ArrayStorage<f32, C2, 1>>],
-    ) -> Vec<f32> {
-        multizip((self.vertices.iter(), first, second, third))
-            .flat_map(|(v, f, s, t)| {
-                let mut d = v.data.as_slice().to_vec();
-                d.extend(f.data.as_slice());
-                d.extend(s.data.as_slice());
-                d.extend(t.data.as_slice());
-                d
-            })
-            .collect::<Vec<f32>>()
+        dynamic: &DynamicMesh,
+        loader: &mut TextureLoader,
+    ) -> Result<RenderingMesh, MageError> {
+        self.to_rendering_mesh_with(dynamic.usage, dynamic.keep_mesh, loader)
     }
 
-    fn flatten_four_with_vertices<
-        U,
-        V,
-        W,
-        Y,
-        const C: usize,
-        const C1: usize,
-        const C2: usize,
-        const C3: usize,
-    >(
+    pub fn to_rendering_mesh_with(
         &self,
-        first: &[Matrix<f32, U, U1, ArrayStorage<f32, C, 1>>],
-        second: &[Matrix<f32, V, U1, ArrayStorage<f32, C1, 1>>],
-        third: &[Matrix<f32, W, U1, ArrayStorage<f32, C2, 1>>],
-        forth: &[Matrix<f32, Y, U1, ArrayStorage<f32, C3, 1>>],
-    ) -> Vec<f32> {
-        multizip((self.vertices.iter(), first, second, third, forth))
-            .flat_map(|(v, f, s, t, ff)| {
-                let mut d = v.data.as_slice().to_vec();
-                d.extend(f.data.as_slice());
-                d.extend(s.data.as_slice());
-                d.extend(t.data.as_slice());
-                d.extend(ff.data.as_slice());
-                d
-            })
-            .collect::<Vec<f32>>()
+        usage: BufferUsage,
+        keep_mesh: bool,
+        loader: &mut TextureLoader,
+    ) -> Result<RenderingMesh, MageError> {
+        self.validate()?;
+        let layout = self.layout();
+        let data = self.interleave(&layout);
+        let vertex_array = Arc::new(VertexArray::new());
+        let array_buffer = Arc::new(Buffer::new(BufferType::Array));
+        vertex_array.bind();
+        array_buffer.bind();
+        fill_buffer(&array_buffer, &data, data.len(), usage);
+        let element_buffer = if let Some(indices) = &self.indices {
+            let element_buffer = Buffer::new(BufferType::ElementArray);
+            element_buffer.bind();
+            fill_buffer(&element_buffer, indices, indices.len(), usage);
+            Some(Arc::new(element_buffer))
+        } else {
+            None
+        };
+        layout.bind();
+        let textures = load_textures(&self.textures, loader)?;
+        VertexArray::unbind();
+        Ok(RenderingMesh {
+            array_buffer,
+            bounds: self.bounds(),
+            element_buffer,
+            vertex_array,
+            drawing_mode: self.drawing_mode,
+            elements: self.len_vertices(),
+            revision: self.revision,
+            index_capacity: self.indices.as_ref().map(Vec::len).unwrap_or(0),
+            layout,
+            mesh: keep_mesh.then(|| self.clone()),
+            shininess: self.shininess,
+            texture_fingerprint: self.texture_fingerprint(),
+            texture_infos: self.textures.clone(),
+            textures,
+            usage,
+            vertex_capacity: data.len(),
+            vertices: self.vertices.len(),
+        })
     }
 
-    fn flatten_with_vertices<D, const S: usize>(
-        &self,
-        other: &[Matrix<f32, D, U1, ArrayStorage<f32, S, 1>>],
-    ) -> Vec<f32> {
-        self.vertices
-            .iter()
-            .zip(other)
-            .flat_map(|(v, n)| {
-                let mut d = v.data.as_slice().to_vec();
-                d.extend(n.data.as_slice());
-                d
-            })
-            .collect::<Vec<f32>>()
+    pub fn size(&self) -> usize {
+        self.layout().stride() as usize
+    }
+
+    pub fn flattened_data(&self) -> Vec<f32> {
+        self.interleave(&self.layout())
+    }
+
+    pub fn vertex_info_size(&self) -> usize {
+        self.size()
     }
 }
 
 #[derive(Debug)]
 pub struct RenderingMesh {
     pub array_buffer: Arc<Buffer>,
+    pub bounds: Bounds,
     pub element_buffer: Option<Arc<Buffer>>,
     pub vertex_array: Arc<VertexArray>,
-    mesh: Mesh,
-    textures: Vec<Arc<Texture>>,
+    pub(crate) drawing_mode: DrawingMode,
+    pub(crate) elements: usize,
+    pub(crate) index_capacity: usize,
+    pub(crate) layout: VertexLayout,
+    pub(crate) mesh: Option<Mesh>,
+    pub(crate) revision: u64,
+    pub(crate) shininess: Option<f32>,
+    pub(crate) texture_fingerprint: u64,
+    pub(crate) texture_infos: Option<Vec<TextureInfo>>,
+    pub(crate) textures: Vec<Arc<Texture>>,
+    pub(crate) usage: BufferUsage,
+    pub(crate) vertex_capacity: usize,
+    pub(crate) vertices: usize,
 }
 
 impl RenderingMesh {
@@ -311,22 +393,56 @@ impl RenderingMesh {
         self.vertex_array.bind();
         if self.element_buffer.is_some() {
             draw_elements(
-                self.mesh.drawing_mode,
-                self.mesh.len_vertices() as u32,
+                self.drawing_mode,
+                self.elements as u32,
+                OpenGlType::UnsignedInt,
+            );
+        } else {
+            draw_arrays(self.drawing_mode, self.elements as u32);
+        }
+        VertexArray::unbind();
+    }
+
+    pub fn draw_instanced(&self, instances: u32) {
+        self.vertex_array.bind();
+        if self.element_buffer.is_some() {
+            draw_elements_instanced(
+                self.drawing_mode,
+                self.elements as u32,
                 OpenGlType::UnsignedInt,
+                instances,
             );
         } else {
-            draw_arrays(self.mesh.drawing_mode, self.mesh.len_vertices() as u32);
+            draw_arrays_instanced(self.drawing_mode, self.elements as u32, instances);
         }
         VertexArray::unbind();
     }
 
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn layout(&self) -> &VertexLayout {
+        &self.layout
+    }
+
+    pub fn mesh(&self) -> Option<&Mesh> {
+        self.mesh.as_ref()
+    }
+
+    pub fn has_texture(&self, texture_type: TextureType) -> bool {
+        self.texture_infos
+            .as_ref()
+            .map(|infos| infos.iter().any(|info| info.texture_type == texture_type))
+            .unwrap_or(false)
+    }
+
     pub fn attach_to_program(&self, program: &Program) {
-        if let Some(infos) = &self.mesh.textures {
+        if let Some(infos) = &self.texture_infos {
             for (texture, info) in self.textures.iter().zip(infos.iter()) {
                 texture.bind(info.id as u32);
                 let texture_type = match info.texture_type {
-                    TextureType::Diffuse => "diffuse",
+                    TextureType::Diffuse | TextureType::BaseColor => "diffuse",
                     TextureType::Specular => "specular",
                     TextureType::Normals => "normal",
                     TextureType::Height => "height",
@@ -338,7 +454,7 @@ impl RenderingMesh {
                 program.set_uniform_i1(&format!("material.{}", texture_type), info.id as i32);
             }
         }
-        let shininess = self.mesh.shininess.unwrap_or(64f32);
+        let shininess = self.shininess.unwrap_or(64f32);
         program.set_uniform_f1("material.shininess", shininess);
     }
 }
@@ -1,4 +1,17 @@
+pub mod bounds;
+pub mod capsule;
+pub mod collider;
+pub mod cone;
 pub mod cube;
+pub mod cylinder;
+pub mod dynamic;
+pub mod grid;
+pub mod icosphere;
+pub mod layout;
+pub mod material;
 pub mod mesh;
 pub mod plane;
+pub mod processing;
+mod revolution;
 pub mod sphere;
+pub mod torus;
@@ -1,6 +1,6 @@
 use nalgebra::{Vector2, Vector3};
 
-use crate::rendering::model::mesh::{Mesh, TextureInfo};
+use crate::rendering::model::mesh::{next_revision, Mesh, TextureInfo};
 use crate::rendering::opengl::DrawingMode;
 
 const HORIZONTAL_VERTICES: [Vector3<f32>; 4] = [
@@ -37,15 +37,22 @@ pub fn horizontal_plan(textures: Vec<TextureInfo>) -> Mesh {
 }
 
 fn plane(textures: Vec<TextureInfo>, normals: &[Vector3<f32>], vertices: &[Vector3<f32>]) -> Mesh {
-    Mesh {
+    let mut mesh = Mesh {
         bitangents: None,
+        colors: None,
         drawing_mode: DrawingMode::Triangles,
         indices: Some(INDICES.to_vec()),
+        joints: None,
         normals: Some(normals.to_vec()),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
         shininess: None,
         tangents: None,
         textures: Some(textures),
         texture_coordinates: Some(TEXTURE_COORDINATES.to_vec()),
         vertices: vertices.to_vec(),
-    }
+        weights: None,
+    };
+    mesh.generate_tangents();
+    mesh
 }
@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+
+use bevy_mikktspace::Geometry;
+use nalgebra::{Matrix3, Matrix4, Point3, Vector3, Vector4};
+use thiserror::Error;
+
+use crate::rendering::model::mesh::Mesh;
+use crate::rendering::opengl::DrawingMode;
+
+const WELD_EPSILON: f32 = 1e-5;
+
+#[derive(Debug, Error)]
+pub enum MeshError {
+    #[error("Mesh attribute {0} has {1} values but the mesh has {2} vertices")]
+    AttributeLength(&'static str, usize, usize),
+    #[error("Mesh index {0} is out of bounds for {1} vertices")]
+    IndexOutOfBounds(u32, usize),
+    #[error("Mesh has {0} indices, which is not a whole number of triangles")]
+    IncompleteTriangle(usize),
+    #[error("Mesh is missing the {0} attribute")]
+    MissingAttribute(&'static str),
+    #[error("MikkTSpace could not generate tangents for the mesh")]
+    TangentGeneration,
+}
+
+fn quantize(values: &[f32], epsilon: f32, key: &mut Vec<i64>) {
+    key.extend(values.iter().map(|v| (v / epsilon).round() as i64));
+}
+
+fn select<T: Copy>(values: &Option<Vec<T>>, order: &[usize]) -> Option<Vec<T>> {
+    values
+        .as_ref()
+        .map(|values| order.iter().map(|&i| values[i]).collect())
+}
+
+fn extend<T>(target: &mut Option<Vec<T>>, values: Option<Vec<T>>) {
+    match (target.as_mut(), values) {
+        (Some(target), Some(values)) => target.extend(values),
+        _ => *target = None,
+    }
+}
+
+struct MikkTSpace<'a> {
+    mesh: &'a Mesh,
+    normals: &'a [Vector3<f32>],
+    tangents: Vec<Vector4<f32>>,
+    triangles: Vec<[usize; 3]>,
+}
+
+impl<'a> Geometry for MikkTSpace<'a> {
+    fn num_faces(&self) -> usize {
+        self.triangles.len()
+    }
+
+    fn num_vertices_of_face(&self, _face: usize) -> usize {
+        3
+    }
+
+    fn position(&self, face: usize, vert: usize) -> [f32; 3] {
+        self.mesh.vertices[self.triangles[face][vert]].into()
+    }
+
+    fn normal(&self, face: usize, vert: usize) -> [f32; 3] {
+        self.normals[self.triangles[face][vert]].into()
+    }
+
+    fn tex_coord(&self, face: usize, vert: usize) -> [f32; 2] {
+        self.mesh
+            .texture_coordinates
+            .as_ref()
+            .map(|uv| uv[self.triangles[face][vert]].into())
+            .unwrap_or_default()
+    }
+
+    fn set_tangent_encoded(&mut self, tangent: [f32; 4], face: usize, vert: usize) {
+        self.tangents[self.triangles[face][vert]] = tangent.into();
+    }
+}
+
+impl Mesh {
+    pub fn validate(&self) -> Result<(), MeshError> {
+        let count = self.vertices.len();
+        let lengths = [
+            ("normals", self.normals.as_ref().map(Vec::len)),
+            (
+                "texture_coordinates",
+                self.texture_coordinates.as_ref().map(Vec::len),
+            ),
+            ("tangents", self.tangents.as_ref().map(Vec::len)),
+            ("bitangents", self.bitangents.as_ref().map(Vec::len)),
+            ("joints", self.joints.as_ref().map(Vec::len)),
+            ("weights", self.weights.as_ref().map(Vec::len)),
+            ("colors", self.colors.as_ref().map(Vec::len)),
+            (
+                "secondary_texture_coordinates",
+                self.secondary_texture_coordinates.as_ref().map(Vec::len),
+            ),
+        ];
+        for (attribute, length) in lengths {
+            match length {
+                Some(length) if length != count => {
+                    return Err(MeshError::AttributeLength(attribute, length, count))
+                }
+                _ => {}
+            }
+        }
+        if let Some(indices) = &self.indices {
+            if let Some(index) = indices.iter().find(|&&i| i as usize >= count) {
+                return Err(MeshError::IndexOutOfBounds(*index, count));
+            }
+            if matches!(self.drawing_mode, DrawingMode::Triangles) && indices.len() % 3 != 0 {
+                return Err(MeshError::IncompleteTriangle(indices.len()));
+            }
+        }
+        Ok(())
+    }
+
+    fn face_normal(&self, [a, b, c]: [usize; 3]) -> Vector3<f32> {
+        (self.vertices[b] - self.vertices[a]).cross(&(self.vertices[c] - self.vertices[a]))
+    }
+
+    pub fn generate_smooth_normals(&mut self) {
+        let mut groups: HashMap<Vec<i64>, Vector3<f32>> = HashMap::new();
+        let keys = self
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let mut key = vec![];
+                quantize(vertex.as_slice(), WELD_EPSILON, &mut key);
+                key
+            })
+            .collect::<Vec<_>>();
+        for triangle in self.triangles() {
+            let normal = self.face_normal(triangle);
+            for i in triangle {
+                *groups.entry(keys[i].clone()).or_insert_with(Vector3::zeros) += normal;
+            }
+        }
+        self.normals = Some(
+            keys.iter()
+                .map(|key| {
+                    groups
+                        .get(key)
+                        .and_then(|normal| normal.try_normalize(0f32))
+                        .unwrap_or_else(Vector3::y)
+                })
+                .collect(),
+        );
+        self.mark_changed();
+    }
+
+    pub fn generate_flat_normals(&mut self) {
+        self.unweld();
+        let normals = self
+            .vertices
+            .chunks_exact(3)
+            .flat_map(|triangle| {
+                let normal = (triangle[1] - triangle[0])
+                    .cross(&(triangle[2] - triangle[0]))
+                    .try_normalize(0f32)
+                    .unwrap_or_else(Vector3::y);
+                [normal; 3]
+            })
+            .collect();
+        self.normals = Some(normals);
+        self.mark_changed();
+    }
+
+    pub fn generate_mikktspace_tangents(&mut self) -> Result<(), MeshError> {
+        let normals = self
+            .normals
+            .as_ref()
+            .ok_or(MeshError::MissingAttribute("normals"))?;
+        if self.texture_coordinates.is_none() {
+            return Err(MeshError::MissingAttribute("texture_coordinates"));
+        }
+        let mut geometry = MikkTSpace {
+            mesh: self,
+            normals,
+            tangents: vec![Vector4::new(1f32, 0f32, 0f32, 1f32); self.vertices.len()],
+            triangles: self.triangles(),
+        };
+        if !bevy_mikktspace::generate_tangents(&mut geometry) {
+            return Err(MeshError::TangentGeneration);
+        }
+        let tangents = geometry.tangents;
+        let bitangents = tangents
+            .iter()
+            .zip(normals.iter())
+            .map(|(tangent, normal)| normal.cross(&tangent.xyz()) * tangent.w)
+            .collect();
+        self.tangents = Some(tangents.iter().map(|tangent| tangent.xyz()).collect());
+        self.bitangents = Some(bitangents);
+        self.mark_changed();
+        Ok(())
+    }
+
+    fn reorder(&mut self, order: &[usize]) {
+        self.vertices = order.iter().map(|&i| self.vertices[i]).collect();
+        self.normals = select(&self.normals, order);
+        self.texture_coordinates = select(&self.texture_coordinates, order);
+        self.tangents = select(&self.tangents, order);
+        self.bitangents = select(&self.bitangents, order);
+        self.joints = select(&self.joints, order);
+        self.weights = select(&self.weights, order);
+        self.colors = select(&self.colors, order);
+        self.secondary_texture_coordinates = select(&self.secondary_texture_coordinates, order);
+        self.drawing_mode = DrawingMode::Triangles;
+        self.mark_changed();
+    }
+
+    pub fn unweld(&mut self) {
+        let order = self.triangles().into_iter().flatten().collect::<Vec<_>>();
+        self.reorder(&order);
+        self.indices = None;
+    }
+
+    pub fn weld(&mut self, epsilon: f32) {
+        let epsilon = epsilon.max(f32::EPSILON);
+        let mut unique: HashMap<Vec<i64>, u32> = HashMap::new();
+        let mut order = vec![];
+        let mut indices = vec![];
+        for i in self.triangles().into_iter().flatten() {
+            let mut key = vec![];
+            quantize(self.vertices[i].as_slice(), epsilon, &mut key);
+            for attribute in [&self.normals, &self.tangents, &self.bitangents]
+                .into_iter()
+                .flatten()
+            {
+                quantize(attribute[i].as_slice(), epsilon, &mut key);
+            }
+            for texture_coordinates in [
+                &self.texture_coordinates,
+                &self.secondary_texture_coordinates,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                quantize(texture_coordinates[i].as_slice(), epsilon, &mut key);
+            }
+            if let Some(joints) = &self.joints {
+                key.extend(joints[i].iter().map(|&j| j as i64));
+            }
+            for vectors in [&self.weights, &self.colors].into_iter().flatten() {
+                quantize(vectors[i].as_slice(), epsilon, &mut key);
+            }
+            let index = *unique.entry(key).or_insert_with(|| {
+                order.push(i);
+                order.len() as u32 - 1
+            });
+            indices.push(index);
+        }
+        self.reorder(&order);
+        self.indices = Some(indices);
+    }
+
+    pub fn transform(&mut self, model: &Matrix4<f32>) {
+        let linear: Matrix3<f32> = model.fixed_slice::<3, 3>(0, 0).into_owned();
+        let normal_matrix = linear
+            .try_inverse()
+            .map(|inverse| inverse.transpose())
+            .unwrap_or(linear);
+        for vertex in self.vertices.iter_mut() {
+            *vertex = model.transform_point(&Point3::from(*vertex)).coords;
+        }
+        let normalized = |matrix: &Matrix3<f32>, vector: &Vector3<f32>| {
+            (matrix * vector).try_normalize(0f32).unwrap_or(*vector)
+        };
+        if let Some(normals) = self.normals.as_mut() {
+            for normal in normals.iter_mut() {
+                *normal = normalized(&normal_matrix, normal);
+            }
+        }
+        for vectors in [&mut self.tangents, &mut self.bitangents]
+            .into_iter()
+            .flatten()
+        {
+            for vector in vectors.iter_mut() {
+                *vector = normalized(&linear, vector);
+            }
+        }
+        if linear.determinant() < 0f32 {
+            let indices = self
+                .triangles()
+                .into_iter()
+                .flat_map(|[a, b, c]| [a as u32, c as u32, b as u32]);
+            self.indices = Some(indices.collect());
+            self.drawing_mode = DrawingMode::Triangles;
+        }
+        self.mark_changed();
+    }
+
+    pub fn merge(meshes: &[(Mesh, Matrix4<f32>)]) -> Option<Mesh> {
+        let mut merged: Option<Mesh> = None;
+        for (mesh, model) in meshes.iter() {
+            let mut mesh = mesh.clone();
+            mesh.indices = Some(
+                mesh.triangles()
+                    .into_iter()
+                    .flatten()
+                    .map(|i| i as u32)
+                    .collect(),
+            );
+            mesh.drawing_mode = DrawingMode::Triangles;
+            mesh.transform(model);
+            let target = match merged.as_mut() {
+                Some(target) => target,
+                None => {
+                    merged = Some(mesh);
+                    continue;
+                }
+            };
+            let offset = target.vertices.len() as u32;
+            if let (Some(target), Some(indices)) = (target.indices.as_mut(), mesh.indices) {
+                target.extend(indices.into_iter().map(|i| i + offset));
+            }
+            target.vertices.extend(mesh.vertices);
+            extend(&mut target.normals, mesh.normals);
+            extend(&mut target.texture_coordinates, mesh.texture_coordinates);
+            extend(&mut target.tangents, mesh.tangents);
+            extend(&mut target.bitangents, mesh.bitangents);
+            extend(&mut target.joints, mesh.joints);
+            extend(&mut target.weights, mesh.weights);
+            extend(&mut target.colors, mesh.colors);
+            extend(
+                &mut target.secondary_texture_coordinates,
+                mesh.secondary_texture_coordinates,
+            );
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+
+    use super::MeshError;
+    use crate::rendering::model::mesh::Mesh;
+
+    fn triangles(vertices: Vec<Vector3<f32>>) -> Mesh {
+        Mesh {
+            vertices,
+            ..Mesh::default()
+        }
+    }
+
+    fn quad() -> Mesh {
+        let [a, b, c, d] = [
+            Vector3::new(0f32, 0f32, 0f32),
+            Vector3::new(1f32, 0f32, 0f32),
+            Vector3::new(1f32, 1f32, 0f32),
+            Vector3::new(0f32, 1f32, 0f32),
+        ];
+        triangles(vec![a, b, c, a, c, d])
+    }
+
+    #[test]
+    fn weld_deduplicates_shared_vertices() {
+        let mut mesh = quad();
+        let corners = mesh.vertices.clone();
+        mesh.weld(1e-5);
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.indices, Some(vec![0, 1, 2, 0, 2, 3]));
+        for (index, corner) in mesh.indices.as_ref().unwrap().iter().zip(corners) {
+            assert_eq!(mesh.vertices[*index as usize], corner);
+        }
+    }
+
+    #[test]
+    fn weld_keeps_vertices_with_different_attributes() {
+        let mut mesh = quad();
+        let mut normals = vec![Vector3::z(); 6];
+        normals[3] = -Vector3::z();
+        mesh.normals = Some(normals);
+        mesh.weld(1e-5);
+        assert_eq!(mesh.vertices.len(), 5);
+        assert_eq!(mesh.normals.as_ref().map(Vec::len), Some(5));
+        mesh.validate().unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_mismatched_attribute_lengths() {
+        let mut mesh = quad();
+        mesh.normals = Some(vec![Vector3::z(); 5]);
+        assert!(matches!(
+            mesh.validate(),
+            Err(MeshError::AttributeLength("normals", 5, 6))
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_out_of_bounds_indices() {
+        let mut mesh = quad();
+        mesh.indices = Some(vec![0, 1, 6]);
+        assert!(matches!(
+            mesh.validate(),
+            Err(MeshError::IndexOutOfBounds(6, 6))
+        ));
+    }
+}
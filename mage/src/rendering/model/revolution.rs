@@ -0,0 +1,132 @@
+use nalgebra::{Vector2, Vector3};
+use num_traits::FloatConst;
+
+use crate::rendering::model::mesh::{next_revision, Mesh, TextureInfo};
+use crate::rendering::opengl::DrawingMode;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ProfilePoint {
+    pub(crate) normal: Vector2<f32>,
+    pub(crate) position: Vector2<f32>,
+}
+
+impl ProfilePoint {
+    pub(crate) fn new(radius: f32, y: f32, normal: Vector2<f32>) -> ProfilePoint {
+        ProfilePoint {
+            normal: normal.try_normalize(0f32).unwrap_or_else(Vector2::y),
+            position: Vector2::new(radius, y),
+        }
+    }
+}
+
+fn around(direction: &Vector2<f32>, phi: f32) -> Vector3<f32> {
+    Vector3::new(
+        direction.x * phi.cos(),
+        direction.y,
+        direction.x * phi.sin(),
+    )
+}
+
+// Sweeps each strip of (radius, height) points around the Y axis. Strips are
+// listed from the bottom of the silhouette to its top so faces point outwards,
+// and v follows the length travelled along the silhouette.
+pub(crate) fn revolve(
+    strips: &[Vec<ProfilePoint>],
+    segments: usize,
+    textures: Vec<TextureInfo>,
+) -> Mesh {
+    let segments = segments.max(3);
+    let length = strips
+        .iter()
+        .flat_map(|strip| strip.windows(2))
+        .map(|pair| (pair[1].position - pair[0].position).norm())
+        .sum::<f32>()
+        .max(f32::EPSILON);
+    let mut travelled = 0f32;
+    let mut vertices = vec![];
+    let mut normals = vec![];
+    let mut texture_coordinates = vec![];
+    let mut tangents = vec![];
+    let mut bitangents = vec![];
+    let mut indices = vec![];
+
+    for strip in strips.iter().filter(|strip| strip.len() > 1) {
+        let start = vertices.len() as u32;
+        for (i, point) in strip.iter().enumerate() {
+            if i > 0 {
+                travelled += (point.position - strip[i - 1].position).norm();
+            }
+            let v = travelled / length;
+            let along = if i + 1 < strip.len() {
+                strip[i + 1].position - point.position
+            } else {
+                point.position - strip[i - 1].position
+            };
+            for segment in 0..segments + 1 {
+                let u = segment as f32 / segments as f32;
+                let phi = u * 2f32 * f32::PI();
+                vertices.push(around(&point.position, phi));
+                normals.push(around(&point.normal, phi));
+                texture_coordinates.push(Vector2::new(u, v));
+                tangents.push(Vector3::new(-phi.sin(), 0f32, phi.cos()));
+                bitangents.push(
+                    around(&along, phi)
+                        .try_normalize(0f32)
+                        .unwrap_or_else(Vector3::y),
+                );
+            }
+        }
+        let columns = segments as u32 + 1;
+        for row in 0..strip.len() - 1 {
+            let lower_ring = strip[row].position.x > f32::EPSILON;
+            let upper_ring = strip[row + 1].position.x > f32::EPSILON;
+            for column in 0..segments as u32 {
+                let a = start + row as u32 * columns + column;
+                let b = a + columns;
+                let c = a + 1;
+                let d = b + 1;
+                if lower_ring {
+                    indices.extend([a, b, c]);
+                }
+                if upper_ring {
+                    indices.extend([c, b, d]);
+                }
+            }
+        }
+    }
+
+    Mesh {
+        bitangents: Some(bitangents),
+        colors: None,
+        drawing_mode: DrawingMode::Triangles,
+        indices: Some(indices),
+        joints: None,
+        normals: Some(normals),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
+        shininess: None,
+        tangents: Some(tangents),
+        textures: Some(textures),
+        texture_coordinates: Some(texture_coordinates),
+        vertices,
+        weights: None,
+    }
+}
+
+pub(crate) fn arc(
+    center: Vector2<f32>,
+    radius: f32,
+    from: f32,
+    to: f32,
+    rings: usize,
+) -> Vec<ProfilePoint> {
+    let rings = rings.max(1);
+    (0..rings + 1)
+        .map(|ring| {
+            let angle = from + (to - from) * ring as f32 / rings as f32;
+            let normal = Vector2::new(angle.cos(), angle.sin());
+            let position = center + normal * radius;
+            ProfilePoint::new(position.x.max(0f32), position.y, normal)
+        })
+        .collect()
+}
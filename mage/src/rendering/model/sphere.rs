@@ -1,4 +1,4 @@
-use crate::rendering::model::mesh::{Mesh, TextureInfo};
+use crate::rendering::model::mesh::{next_revision, Mesh, TextureInfo};
 use crate::rendering::opengl::DrawingMode;
 use nalgebra::{Vector2, Vector3};
 use num_traits::FloatConst;
@@ -6,17 +6,25 @@ use num_traits::FloatConst;
 const X_SEGMENT: usize = 128;
 const Y_SEGMENT: usize = 128;
 
-type ModelData = (Vec<Vector3<f32>>, Vec<Vector2<f32>>, Vec<Vector3<f32>>);
+type ModelData = (
+    Vec<Vector3<f32>>,
+    Vec<Vector2<f32>>,
+    Vec<Vector3<f32>>,
+    Vec<Vector3<f32>>,
+    Vec<Vector3<f32>>,
+);
 
-fn positions_uv_normals(radius: f32) -> ModelData {
+fn positions_uv_normals(radius: f32, x_segments: usize, y_segments: usize) -> ModelData {
     let mut positions = vec![];
     let mut uv = vec![];
     let mut normals = vec![];
+    let mut tangents = vec![];
+    let mut bitangents = vec![];
 
-    for x in 0..X_SEGMENT + 1 {
-        for y in 0..Y_SEGMENT + 1 {
-            let x_segment = x as f32 / X_SEGMENT as f32;
-            let y_segment = y as f32 / Y_SEGMENT as f32;
+    for x in 0..x_segments + 1 {
+        for y in 0..y_segments + 1 {
+            let x_segment = x as f32 / x_segments as f32;
+            let y_segment = y as f32 / y_segments as f32;
             let x_pos =
                 radius * (x_segment * 2f32 * f32::PI()).cos() * (y_segment * f32::PI()).sin();
             let y_pos = radius * (y_segment * f32::PI()).cos();
@@ -25,25 +33,33 @@ fn positions_uv_normals(radius: f32) -> ModelData {
             positions.push(Vector3::new(x_pos, y_pos, z_pos));
             uv.push(Vector2::new(x_segment as f32, y_segment as f32));
             normals.push(Vector3::new(x_pos, y_pos, z_pos));
+            let phi = x_segment * 2f32 * f32::PI();
+            let theta = y_segment * f32::PI();
+            tangents.push(Vector3::new(-phi.sin(), 0f32, phi.cos()));
+            bitangents.push(Vector3::new(
+                phi.cos() * theta.cos(),
+                -theta.sin(),
+                phi.sin() * theta.cos(),
+            ));
         }
     }
-    (positions, uv, normals)
+    (positions, uv, normals, tangents, bitangents)
 }
 
-fn indices() -> Vec<u32> {
+fn indices(x_segments: usize, y_segments: usize) -> Vec<u32> {
     let mut indices = vec![];
     let mut odd_row = false;
 
-    for y in 0..Y_SEGMENT {
+    for y in 0..y_segments {
         if !odd_row {
-            for x in 0..X_SEGMENT + 1 {
-                indices.push(y as u32 * (X_SEGMENT as u32 + 1) + x as u32);
-                indices.push((y as u32 + 1) * (X_SEGMENT as u32 + 1) + x as u32);
+            for x in 0..x_segments + 1 {
+                indices.push(y as u32 * (x_segments as u32 + 1) + x as u32);
+                indices.push((y as u32 + 1) * (x_segments as u32 + 1) + x as u32);
             }
         } else {
-            for x in (0..X_SEGMENT + 1).rev() {
-                indices.push((y as u32 + 1) * (X_SEGMENT as u32 + 1) + x as u32);
-                indices.push(y as u32 * (X_SEGMENT as u32 + 1) + x as u32);
+            for x in (0..x_segments + 1).rev() {
+                indices.push((y as u32 + 1) * (x_segments as u32 + 1) + x as u32);
+                indices.push(y as u32 * (x_segments as u32 + 1) + x as u32);
             }
         }
         odd_row = !odd_row;
@@ -53,18 +69,35 @@ fn indices() -> Vec<u32> {
 }
 
 pub fn sphere(radius: f32, textures: Vec<TextureInfo>) -> Mesh {
-    let indices = indices();
-    let (vertices, uv, normals) = positions_uv_normals(radius);
+    sphere_with_segments(radius, X_SEGMENT, Y_SEGMENT, textures)
+}
+
+pub fn sphere_with_segments(
+    radius: f32,
+    x_segments: usize,
+    y_segments: usize,
+    textures: Vec<TextureInfo>,
+) -> Mesh {
+    let x_segments = x_segments.max(3);
+    let y_segments = y_segments.max(2);
+    let indices = indices(x_segments, y_segments);
+    let (vertices, uv, normals, tangents, bitangents) =
+        positions_uv_normals(radius, x_segments, y_segments);
 
     Mesh {
         vertices,
         drawing_mode: DrawingMode::TriangleStrip,
         normals: Some(normals),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
         indices: Some(indices),
-        tangents: None,
-        bitangents: None,
+        tangents: Some(tangents),
+        bitangents: Some(bitangents),
+        colors: None,
         textures: Some(textures),
         texture_coordinates: Some(uv),
         shininess: None,
+        joints: None,
+        weights: None,
     }
 }
@@ -0,0 +1,22 @@
+use nalgebra::Vector2;
+use num_traits::FloatConst;
+
+use crate::rendering::model::mesh::{Mesh, TextureInfo};
+use crate::rendering::model::revolution::{arc, revolve};
+
+pub fn torus(
+    major_radius: f32,
+    minor_radius: f32,
+    segments: usize,
+    sides: usize,
+    textures: Vec<TextureInfo>,
+) -> Mesh {
+    let profile = arc(
+        Vector2::new(major_radius, 0f32),
+        minor_radius,
+        -f32::PI(),
+        f32::PI(),
+        sides.max(3),
+    );
+    revolve(&[profile], segments, textures)
+}
@@ -61,11 +61,15 @@ impl Buffer {
     }
 
     pub fn allocate_data<T>(&self, size: usize) {
+        self.allocate_data_with_usage::<T>(size, BufferUsage::StaticDraw);
+    }
+
+    pub fn allocate_data_with_usage<T>(&self, size: usize, usage: BufferUsage) {
         gl_function!(BufferData(
             self.1,
             (size_of::<T>() * size) as isize,
             ptr::null(),
-            gl::STATIC_DRAW
+            usage as u32
         ))
     }
 
@@ -97,6 +101,10 @@ impl Buffer {
         ));
     }
 
+    pub fn bind_transform_feedback(&self, index: u32) {
+        gl_function!(BindBufferBase(gl::TRANSFORM_FEEDBACK_BUFFER, index, self.0));
+    }
+
     pub fn bind(&self) {
         gl_function!(BindBuffer(self.1, self.0));
     }
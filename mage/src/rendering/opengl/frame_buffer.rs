@@ -144,6 +144,10 @@ impl FrameBuffer {
     }
 
     pub fn multisample(width: u32, height: u32) -> FrameBuffer {
+        FrameBuffer::multisample_with_samples(width, height, 4)
+    }
+
+    pub fn multisample_with_samples(width: u32, height: u32, samples: u32) -> FrameBuffer {
         let mut frame_buffer = 0u32;
         gl_function!(GenFramebuffers(1, &mut frame_buffer));
         gl_function!(BindFramebuffer(gl::FRAMEBUFFER, frame_buffer));
@@ -152,7 +156,7 @@ impl FrameBuffer {
         texture.just_bind();
         gl_function!(TexImage2DMultisample(
             TextureDimension::Texture2DMultisample as _,
-            4,
+            samples as _,
             gl::RGB,
             width as i32,
             height as i32,
@@ -172,7 +176,7 @@ impl FrameBuffer {
         render_buffer.bind();
         gl_function!(RenderbufferStorageMultisample(
             gl::RENDERBUFFER,
-            4,
+            samples as _,
             gl::DEPTH24_STENCIL8,
             width as _,
             height as _
@@ -268,6 +272,114 @@ impl FrameBuffer {
         }
     }
 
+    pub fn cube_map(width: u32, height: u32, mipmaps: bool) -> FrameBuffer {
+        let mut frame_buffer = 0u32;
+        gl_function!(GenFramebuffers(1, &mut frame_buffer));
+        gl_function!(BindFramebuffer(gl::FRAMEBUFFER, frame_buffer));
+
+        let texture = Texture::new(TextureDimension::CubeMap);
+        texture.just_bind();
+        texture.allocate_cube_map_faces(width, height, TextureFormat::FloatingPoint);
+        for parameter in [
+            TextureParameter::TextureWrapS,
+            TextureParameter::TextureWrapT,
+            TextureParameter::TextureWrapR,
+        ] {
+            texture.set_parameter(parameter, TextureParameterValue::ClampToEdge);
+        }
+        texture.set_parameter(
+            TextureParameter::TextureMinFilter,
+            if mipmaps {
+                TextureParameterValue::LinearMipmapLinear
+            } else {
+                TextureParameterValue::Linear
+            },
+        );
+        texture.set_parameter(
+            TextureParameter::TextureMagFilter,
+            TextureParameterValue::Linear,
+        );
+        if mipmaps {
+            texture.generate_mipmap();
+        }
+        texture.unbind();
+
+        let render_buffer = RenderBuffer::new();
+        render_buffer.bind();
+        gl_function!(RenderbufferStorage(
+            gl::RENDERBUFFER,
+            gl::DEPTH_COMPONENT24,
+            width as _,
+            height as _
+        ));
+        RenderBuffer::unbind();
+        gl_function!(FramebufferRenderbuffer(
+            gl::FRAMEBUFFER,
+            gl::DEPTH_ATTACHMENT,
+            gl::RENDERBUFFER,
+            render_buffer.0
+        ));
+        FrameBuffer::unbind();
+
+        FrameBuffer {
+            texture,
+            _render_buffer: Some(render_buffer),
+            resource: frame_buffer,
+        }
+    }
+
+    pub fn attach_cube_map_face(&self, face: u32, level: u32) {
+        gl_function!(FramebufferTexture2D(
+            gl::FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT0,
+            gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
+            self.texture.0,
+            level as _
+        ));
+    }
+
+    pub fn resize_depth(&self, width: u32, height: u32) {
+        if let Some(render_buffer) = &self._render_buffer {
+            render_buffer.bind();
+            gl_function!(RenderbufferStorage(
+                gl::RENDERBUFFER,
+                gl::DEPTH_COMPONENT24,
+                width as _,
+                height as _
+            ));
+            RenderBuffer::unbind();
+        }
+    }
+
+    pub fn blit(&self, width: u32, height: u32) {
+        self.read_bind();
+        gl_function!(BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0));
+        FrameBuffer::blit_color(width, height);
+        FrameBuffer::unbind();
+    }
+
+    pub fn blit_into(&self, target: &FrameBuffer, width: u32, height: u32) {
+        self.read_bind();
+        target.draw_bind();
+        FrameBuffer::blit_color(width, height);
+        FrameBuffer::unbind();
+    }
+
+    fn blit_color(width: u32, height: u32) {
+        gl_function!(BlitFramebuffer(
+            0,
+            0,
+            width as _,
+            height as _,
+            0,
+            0,
+            width as _,
+            height as _,
+            gl::COLOR_BUFFER_BIT,
+            gl::NEAREST
+        ));
+    }
+
     pub fn draw_bind(&self) {
         gl_function!(BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.resource));
     }
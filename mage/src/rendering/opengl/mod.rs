@@ -36,6 +36,15 @@ pub enum OpenGlType {
     UnsignedInt = gl::UNSIGNED_INT,
 }
 
+impl OpenGlType {
+    pub fn size(&self) -> usize {
+        match self {
+            OpenGlType::UnsignedByte => std::mem::size_of::<u8>(),
+            OpenGlType::UnsignedInt => std::mem::size_of::<u32>(),
+        }
+    }
+}
+
 #[repr(u32)]
 #[derive(Clone, Copy, Debug)]
 pub enum DrawingMode {
@@ -46,7 +55,46 @@ pub enum DrawingMode {
 #[repr(u32)]
 #[derive(Clone, Copy, Debug)]
 pub enum Feature {
+    Blend = gl::BLEND,
     Depth = gl::DEPTH_TEST,
+    RasterizerDiscard = gl::RASTERIZER_DISCARD,
+    TextureCubeMapSeamless = gl::TEXTURE_CUBE_MAP_SEAMLESS,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug)]
+pub enum DepthFunction {
+    Always = gl::ALWAYS,
+    Equal = gl::EQUAL,
+    Greater = gl::GREATER,
+    GreaterEqual = gl::GEQUAL,
+    Less = gl::LESS,
+    LessEqual = gl::LEQUAL,
+    Never = gl::NEVER,
+    NotEqual = gl::NOTEQUAL,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug)]
+pub enum BlendFactor {
+    DestinationAlpha = gl::DST_ALPHA,
+    DestinationColor = gl::DST_COLOR,
+    One = gl::ONE,
+    OneMinusDestinationAlpha = gl::ONE_MINUS_DST_ALPHA,
+    OneMinusDestinationColor = gl::ONE_MINUS_DST_COLOR,
+    OneMinusSourceAlpha = gl::ONE_MINUS_SRC_ALPHA,
+    OneMinusSourceColor = gl::ONE_MINUS_SRC_COLOR,
+    SourceAlpha = gl::SRC_ALPHA,
+    SourceColor = gl::SRC_COLOR,
+    Zero = gl::ZERO,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlendMode {
+    Additive,
+    Alpha,
+    Multiply,
+    Premultiplied,
 }
 
 #[repr(u32)]
@@ -74,6 +122,10 @@ pub fn draw_arrays(mode: DrawingMode, vertices: u32) {
     gl_function!(DrawArrays(mode as _, 0, vertices as _,));
 }
 
+pub fn draw_points(points: u32) {
+    gl_function!(DrawArrays(gl::POINTS, 0, points as _));
+}
+
 pub fn draw_elements(mode: DrawingMode, vertices: u32, indices_type: OpenGlType) {
     gl_function!(DrawElements(
         mode as _,
@@ -83,6 +135,91 @@ pub fn draw_elements(mode: DrawingMode, vertices: u32, indices_type: OpenGlType)
     ));
 }
 
+pub fn draw_elements_from(mode: DrawingMode, vertices: u32, indices_type: OpenGlType, from: usize) {
+    gl_function!(DrawElements(
+        mode as _,
+        vertices as _,
+        indices_type as _,
+        (from * indices_type.size()) as *const _
+    ));
+}
+
+pub fn draw_arrays_instanced(mode: DrawingMode, vertices: u32, instances: u32) {
+    gl_function!(DrawArraysInstanced(
+        mode as _,
+        0,
+        vertices as _,
+        instances as _
+    ));
+}
+
+pub fn draw_elements_instanced(
+    mode: DrawingMode,
+    vertices: u32,
+    indices_type: OpenGlType,
+    instances: u32,
+) {
+    gl_function!(DrawElementsInstanced(
+        mode as _,
+        vertices as _,
+        indices_type as _,
+        std::ptr::null(),
+        instances as _
+    ));
+}
+
+pub fn begin_transform_feedback() {
+    gl_function!(BeginTransformFeedback(gl::POINTS));
+}
+
+pub fn end_transform_feedback() {
+    gl_function!(EndTransformFeedback());
+}
+
 pub fn enable(feature: Feature) {
     gl_function!(Enable(feature as _));
 }
+
+pub fn disable(feature: Feature) {
+    gl_function!(Disable(feature as _));
+}
+
+pub fn set_blend_function(source: BlendFactor, destination: BlendFactor) {
+    gl_function!(BlendFunc(source as _, destination as _));
+}
+
+pub fn set_blend_mode(mode: BlendMode) {
+    match mode {
+        BlendMode::Additive => set_blend_function(BlendFactor::SourceAlpha, BlendFactor::One),
+        BlendMode::Alpha => {
+            set_blend_function(BlendFactor::SourceAlpha, BlendFactor::OneMinusSourceAlpha)
+        }
+        BlendMode::Multiply => set_blend_function(BlendFactor::DestinationColor, BlendFactor::Zero),
+        BlendMode::Premultiplied => {
+            set_blend_function(BlendFactor::One, BlendFactor::OneMinusSourceAlpha)
+        }
+    }
+}
+
+pub fn set_depth_mask(enabled: bool) {
+    gl_function!(DepthMask(enabled as _));
+}
+
+pub fn set_depth_function(function: DepthFunction) {
+    gl_function!(DepthFunc(function as _));
+}
+
+pub fn set_viewport(x: i32, y: i32, width: u32, height: u32) {
+    gl_function!(Viewport(x, y, width as _, height as _));
+}
+
+pub fn get_viewport() -> (i32, i32, u32, u32) {
+    let mut viewport = [0i32; 4];
+    gl_function!(GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr()));
+    (
+        viewport[0],
+        viewport[1],
+        viewport[2] as u32,
+        viewport[3] as u32,
+    )
+}
@@ -80,6 +80,34 @@ impl Program {
         })
     }
 
+    pub fn with_transform_feedback(
+        vertex_shader: Shader,
+        varyings: &[&str],
+    ) -> Result<Program, MageError> {
+        let resource = gl_function!(CreateProgram());
+        gl_function!(AttachShader(resource, vertex_shader.0));
+        let varyings = varyings
+            .iter()
+            .map(|varying| CString::new(*varying).unwrap())
+            .collect::<Vec<_>>();
+        let pointers = varyings
+            .iter()
+            .map(|varying| varying.as_ptr())
+            .collect::<Vec<_>>();
+        gl_function!(TransformFeedbackVaryings(
+            resource,
+            pointers.len() as _,
+            pointers.as_ptr(),
+            gl::INTERLEAVED_ATTRIBS
+        ));
+        gl_function!(LinkProgram(resource));
+        check_success(resource, gl::LINK_STATUS)?;
+        Ok(Program {
+            resource,
+            uniforms: RefCell::new(HashMap::new()),
+        })
+    }
+
     pub fn use_program(&self) {
         gl_function!(UseProgram(self.resource));
     }
@@ -143,6 +143,39 @@ impl Texture {
         ));
     }
 
+    pub fn allocate_cube_map_faces(&self, width: u32, height: u32, format: TextureFormat) {
+        for face in 0..6 {
+            match format {
+                TextureFormat::FloatingPoint => gl_function!(TexImage2D(
+                    gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
+                    0,
+                    gl::RGBA16F as _,
+                    width as _,
+                    height as _,
+                    0,
+                    gl::RGBA as _,
+                    gl::FLOAT,
+                    ptr::null(),
+                )),
+                TextureFormat::UnsignedByteWithAlpha => gl_function!(TexImage2D(
+                    gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
+                    0,
+                    gl::RGBA as _,
+                    width as _,
+                    height as _,
+                    0,
+                    gl::RGBA as _,
+                    gl::UNSIGNED_BYTE,
+                    ptr::null(),
+                )),
+                TextureFormat::Depth => {
+                    self.alloc_depth_cube_map_face(face, width as _, height as _)
+                }
+                _ => unimplemented!(),
+            }
+        }
+    }
+
     pub fn set_cube_map_face(&self, face: u32, width: usize, height: usize, data: &[u8]) {
         gl_function!(TexImage2D(
             gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
@@ -157,6 +190,52 @@ impl Texture {
         ));
     }
 
+    pub fn set_cube_map_face_with_format<T>(
+        &self,
+        face: u32,
+        width: u32,
+        height: u32,
+        data: &[T],
+        format: TextureFormat,
+    ) {
+        match format {
+            TextureFormat::UnsignedByte => gl_function!(TexImage2D(
+                gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
+                0,
+                gl::RGB as _,
+                width as _,
+                height as _,
+                0,
+                gl::RGB as _,
+                gl::UNSIGNED_BYTE,
+                transmute(&(data[0]) as *const T)
+            )),
+            TextureFormat::UnsignedByteWithAlpha => gl_function!(TexImage2D(
+                gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
+                0,
+                gl::RGBA as _,
+                width as _,
+                height as _,
+                0,
+                gl::RGBA as _,
+                gl::UNSIGNED_BYTE,
+                transmute(&(data[0]) as *const T)
+            )),
+            TextureFormat::FloatingPoint => gl_function!(TexImage2D(
+                gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
+                0,
+                gl::RGBA16F as _,
+                width as _,
+                height as _,
+                0,
+                gl::RGBA as _,
+                gl::FLOAT,
+                transmute(&(data[0]) as *const T)
+            )),
+            _ => unimplemented!(),
+        }
+    }
+
     pub fn set_image_2d<T>(&self, width: u32, height: u32, data: &[T], format: TextureFormat) {
         match (self.1, format) {
             (TextureDimension::Texture2D, TextureFormat::UnsignedByte) => gl_function!(TexImage2D(
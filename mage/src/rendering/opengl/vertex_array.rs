@@ -7,7 +7,7 @@ use itertools::Itertools;
 use crate::gl_function;
 
 #[repr(u32)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum DataType {
     Byte = gl::BYTE,
     UnsignedByte = gl::UNSIGNED_BYTE,
@@ -78,6 +78,14 @@ impl VertexArray {
         ));
     }
 
+    pub fn disable_vertex_attrib(attribute: u32) {
+        gl_function!(DisableVertexAttribArray(attribute));
+    }
+
+    pub fn set_vertex_attrib_divisor(attribute: u32, divisor: u32) {
+        gl_function!(VertexAttribDivisor(attribute, divisor));
+    }
+
     pub fn unbind() {
         gl_function!(BindVertexArray(0));
     }
@@ -0,0 +1,63 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use crate::rendering::model::cube::unit_cube;
+use crate::rendering::model::mesh::{RenderingMesh, TextureInfo};
+use crate::rendering::opengl::program::Program;
+use crate::rendering::opengl::texture::Texture;
+use crate::rendering::opengl::{set_depth_function, DepthFunction};
+use crate::resources::texture::TextureLoader;
+use crate::MageError;
+
+#[derive(Clone, Debug)]
+pub struct Skybox {
+    pub texture: TextureInfo,
+}
+
+impl Skybox {
+    pub fn new(texture: TextureInfo) -> Skybox {
+        Skybox { texture }
+    }
+
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.texture.id.hash(&mut hasher);
+        self.texture.source.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn to_rendering_skybox(
+        &self,
+        loader: &mut TextureLoader,
+    ) -> Result<RenderingSkybox, MageError> {
+        Ok(RenderingSkybox {
+            fingerprint: self.fingerprint(),
+            mesh: unit_cube().to_rendering_mesh(loader)?,
+            texture: loader.load_texture_cubemap(&self.texture)?,
+            texture_unit: self.texture.id as u32,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct RenderingSkybox {
+    fingerprint: u64,
+    mesh: RenderingMesh,
+    texture: Arc<Texture>,
+    texture_unit: u32,
+}
+
+impl RenderingSkybox {
+    pub(crate) fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    pub fn draw(&self, program: &Program) {
+        set_depth_function(DepthFunction::LessEqual);
+        self.texture.bind(self.texture_unit);
+        program.set_uniform_i1("skybox", self.texture_unit as i32);
+        self.mesh.draw();
+        set_depth_function(DepthFunction::Less);
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use hecs::World;
+
+use crate::core::system::System;
+use crate::core::time::time_scale;
+use crate::rendering::model::mesh::TextureInfo;
+use crate::rendering::sprite::{Sprite, TextureAtlas, TextureRegion};
+use crate::MageError;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlaybackMode {
+    Loop,
+    Once,
+    PingPong,
+}
+
+#[derive(Clone, Debug)]
+pub struct AnimationFrame {
+    pub duration: u64,
+    pub event: Option<String>,
+    pub region: TextureRegion,
+}
+
+#[derive(Clone, Debug)]
+pub struct SpriteAnimationClip {
+    pub frames: Vec<AnimationFrame>,
+    pub mode: PlaybackMode,
+}
+
+impl SpriteAnimationClip {
+    pub fn from_atlas(
+        atlas: &TextureAtlas,
+        frames: &[usize],
+        duration: u64,
+        mode: PlaybackMode,
+    ) -> SpriteAnimationClip {
+        let frames = frames
+            .iter()
+            .map(|&frame| (frame, duration))
+            .collect::<Vec<_>>();
+        SpriteAnimationClip::from_atlas_with_durations(atlas, &frames, mode)
+    }
+
+    pub fn from_atlas_with_durations(
+        atlas: &TextureAtlas,
+        frames: &[(usize, u64)],
+        mode: PlaybackMode,
+    ) -> SpriteAnimationClip {
+        SpriteAnimationClip {
+            frames: frames
+                .iter()
+                .filter_map(|&(frame, duration)| {
+                    atlas.frame(frame).map(|region| AnimationFrame {
+                        duration,
+                        event: None,
+                        region,
+                    })
+                })
+                .collect(),
+            mode,
+        }
+    }
+
+    pub fn with_event(mut self, frame: usize, event: &str) -> SpriteAnimationClip {
+        if let Some(frame) = self.frames.get_mut(frame) {
+            frame.event = Some(event.to_string());
+        }
+        self
+    }
+
+    fn next_frame(&self, frame: usize, forward: bool) -> Option<(usize, bool)> {
+        let last = self.frames.len() - 1;
+        match self.mode {
+            PlaybackMode::Once if frame < last => Some((frame + 1, true)),
+            PlaybackMode::Once => None,
+            PlaybackMode::Loop if frame < last => Some((frame + 1, true)),
+            PlaybackMode::Loop => Some((0, true)),
+            PlaybackMode::PingPong if last == 0 => Some((0, true)),
+            PlaybackMode::PingPong if forward && frame < last => Some((frame + 1, true)),
+            PlaybackMode::PingPong if forward => Some((frame - 1, false)),
+            PlaybackMode::PingPong if frame > 0 => Some((frame - 1, false)),
+            PlaybackMode::PingPong => Some((frame + 1, true)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpriteAnimation {
+    clips: HashMap<String, SpriteAnimationClip>,
+    texture: TextureInfo,
+}
+
+impl SpriteAnimation {
+    pub fn new(texture: TextureInfo) -> SpriteAnimation {
+        SpriteAnimation {
+            clips: HashMap::new(),
+            texture,
+        }
+    }
+
+    pub fn from_atlas(atlas: &TextureAtlas) -> SpriteAnimation {
+        SpriteAnimation::new(atlas.texture().clone())
+    }
+
+    pub fn add_clip(&mut self, name: &str, clip: SpriteAnimationClip) {
+        self.clips.insert(name.to_string(), clip);
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&SpriteAnimationClip> {
+        self.clips.get(name)
+    }
+
+    pub fn texture(&self) -> &TextureInfo {
+        &self.texture
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpriteAnimationPlayer {
+    animation: Arc<SpriteAnimation>,
+    clip: Option<String>,
+    elapsed: f32,
+    entered: bool,
+    events: Vec<String>,
+    finished: bool,
+    forward: bool,
+    frame: usize,
+    pub paused: bool,
+    pub speed: f32,
+}
+
+impl SpriteAnimationPlayer {
+    pub fn new(animation: Arc<SpriteAnimation>) -> SpriteAnimationPlayer {
+        SpriteAnimationPlayer {
+            animation,
+            clip: None,
+            elapsed: 0f32,
+            entered: false,
+            events: vec![],
+            finished: false,
+            forward: true,
+            frame: 0,
+            paused: false,
+            speed: 1f32,
+        }
+    }
+
+    pub fn play(&mut self, clip: &str) {
+        if self.clip.as_deref() == Some(clip) && !self.finished {
+            return;
+        }
+        self.restart(clip);
+    }
+
+    pub fn restart(&mut self, clip: &str) {
+        self.clip = Some(clip.to_string());
+        self.elapsed = 0f32;
+        self.entered = true;
+        self.finished = false;
+        self.forward = true;
+        self.frame = 0;
+    }
+
+    pub fn stop(&mut self) {
+        self.clip = None;
+        self.finished = true;
+    }
+
+    pub fn current_clip(&self) -> Option<&str> {
+        self.clip.as_deref()
+    }
+
+    pub fn current_frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn region(&self) -> Option<TextureRegion> {
+        self.clip
+            .as_ref()
+            .and_then(|name| self.animation.clip(name))
+            .and_then(|clip| clip.frames.get(self.frame))
+            .map(|frame| frame.region)
+    }
+
+    fn advance(&mut self, delta_time: u64, time_scale: f32) {
+        self.events.clear();
+        let animation = self.animation.clone();
+        let clip = match self.clip.as_ref().and_then(|name| animation.clip(name)) {
+            Some(clip) if !clip.frames.is_empty() => clip,
+            _ => return,
+        };
+        if self.entered {
+            self.entered = false;
+            self.events.extend(clip.frames[self.frame].event.clone());
+        }
+        if self.paused || self.finished {
+            return;
+        }
+        self.elapsed += delta_time as f32 * self.speed * time_scale;
+        while self.elapsed >= clip.frames[self.frame].duration.max(1) as f32 {
+            self.elapsed -= clip.frames[self.frame].duration.max(1) as f32;
+            match clip.next_frame(self.frame, self.forward) {
+                Some((frame, forward)) => {
+                    self.frame = frame;
+                    self.forward = forward;
+                    self.events.extend(clip.frames[frame].event.clone());
+                }
+                None => {
+                    self.elapsed = 0f32;
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+pub struct SpriteAnimationSystem;
+
+impl System for SpriteAnimationSystem {
+    fn name(&self) -> &str {
+        "SpriteAnimation"
+    }
+
+    fn start(&self, _world: &mut World) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn early_update(&self, world: &mut World, delta_time: u64) -> Result<(), MageError> {
+        let time_scale = time_scale(world);
+        for (_e, (player, sprite)) in world.query_mut::<(&mut SpriteAnimationPlayer, &mut Sprite)>()
+        {
+            player.advance(delta_time, time_scale);
+            if let Some(region) = player.region() {
+                sprite.region = region;
+            }
+            if sprite.texture.source != player.animation.texture.source {
+                sprite.texture = player.animation.texture.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn late_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+}
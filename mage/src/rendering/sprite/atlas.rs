@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use nalgebra::Vector2;
+
+use crate::rendering::model::mesh::TextureInfo;
+use crate::rendering::sprite::{Sprite, TextureRegion};
+
+#[derive(Clone, Debug)]
+pub struct TextureAtlas {
+    frames: Vec<TextureRegion>,
+    height: u32,
+    names: HashMap<String, usize>,
+    texture: TextureInfo,
+    width: u32,
+}
+
+impl TextureAtlas {
+    pub fn new(texture: TextureInfo, width: u32, height: u32) -> TextureAtlas {
+        TextureAtlas {
+            frames: vec![],
+            height,
+            names: HashMap::new(),
+            texture,
+            width,
+        }
+    }
+
+    pub fn grid(
+        texture: TextureInfo,
+        width: u32,
+        height: u32,
+        columns: u32,
+        rows: u32,
+    ) -> TextureAtlas {
+        let mut atlas = TextureAtlas::new(texture, width, height);
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+        let cell_width = width / columns;
+        let cell_height = height / rows;
+        for row in 0..rows {
+            for column in 0..columns {
+                atlas.frames.push(TextureRegion::from_pixels(
+                    column * cell_width,
+                    row * cell_height,
+                    cell_width,
+                    cell_height,
+                    width,
+                    height,
+                ));
+            }
+        }
+        atlas
+    }
+
+    pub fn add_frame(&mut self, x: u32, y: u32, width: u32, height: u32) -> usize {
+        self.frames.push(TextureRegion::from_pixels(
+            x,
+            y,
+            width,
+            height,
+            self.width,
+            self.height,
+        ));
+        self.frames.len() - 1
+    }
+
+    pub fn add_region(&mut self, name: &str, x: u32, y: u32, width: u32, height: u32) -> usize {
+        let index = self.add_frame(x, y, width, height);
+        self.names.insert(name.to_string(), index);
+        index
+    }
+
+    pub fn frame(&self, index: usize) -> Option<TextureRegion> {
+        self.frames.get(index).copied()
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    pub fn region(&self, name: &str) -> Option<TextureRegion> {
+        self.index_of(name).and_then(|index| self.frame(index))
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn texture(&self) -> &TextureInfo {
+        &self.texture
+    }
+
+    pub fn sprite(&self, index: usize, size: Vector2<f32>) -> Option<Sprite> {
+        self.frame(index).map(|region| Sprite {
+            region,
+            ..Sprite::new(self.texture.clone(), size)
+        })
+    }
+
+    pub fn named_sprite(&self, name: &str, size: Vector2<f32>) -> Option<Sprite> {
+        self.index_of(name)
+            .and_then(|index| self.sprite(index, size))
+    }
+}
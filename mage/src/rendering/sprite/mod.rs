@@ -0,0 +1,119 @@
+use nalgebra::{Matrix4, Point3, Vector2, Vector4};
+
+use crate::rendering::model::mesh::TextureInfo;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextureRegion {
+    pub max: Vector2<f32>,
+    pub min: Vector2<f32>,
+}
+
+impl TextureRegion {
+    pub fn full() -> TextureRegion {
+        TextureRegion {
+            max: Vector2::new(1f32, 1f32),
+            min: Vector2::new(0f32, 0f32),
+        }
+    }
+
+    pub fn from_pixels(
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        texture_width: u32,
+        texture_height: u32,
+    ) -> TextureRegion {
+        let texture_width = texture_width as f32;
+        let texture_height = texture_height as f32;
+        TextureRegion {
+            max: Vector2::new(
+                (x + width) as f32 / texture_width,
+                1f32 - y as f32 / texture_height,
+            ),
+            min: Vector2::new(
+                x as f32 / texture_width,
+                1f32 - (y + height) as f32 / texture_height,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Sprite {
+    pub anchor: Vector2<f32>,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub layer: i32,
+    pub region: TextureRegion,
+    pub size: Vector2<f32>,
+    pub texture: TextureInfo,
+    pub tint: Vector4<f32>,
+}
+
+impl Sprite {
+    pub fn new(texture: TextureInfo, size: Vector2<f32>) -> Sprite {
+        Sprite {
+            anchor: Vector2::new(0.5f32, 0.5f32),
+            flip_x: false,
+            flip_y: false,
+            layer: 0,
+            region: TextureRegion::full(),
+            size,
+            texture,
+            tint: Vector4::new(1f32, 1f32, 1f32, 1f32),
+        }
+    }
+
+    pub(crate) fn vertices(&self, model: &Matrix4<f32>) -> Vec<f32> {
+        let (left, right) = if self.flip_x {
+            (self.region.max.x, self.region.min.x)
+        } else {
+            (self.region.min.x, self.region.max.x)
+        };
+        let (bottom, top) = if self.flip_y {
+            (self.region.max.y, self.region.min.y)
+        } else {
+            (self.region.min.y, self.region.max.y)
+        };
+        let corners = [
+            (0f32, 0f32, left, bottom),
+            (1f32, 0f32, right, bottom),
+            (1f32, 1f32, right, top),
+            (0f32, 1f32, left, top),
+        ];
+        corners
+            .iter()
+            .flat_map(|&(x, y, u, v)| {
+                let position = model.transform_point(&Point3::new(
+                    (x - self.anchor.x) * self.size.x,
+                    (y - self.anchor.y) * self.size.y,
+                    0f32,
+                ));
+                [
+                    position.x,
+                    position.y,
+                    position.z,
+                    u,
+                    v,
+                    self.tint.x,
+                    self.tint.y,
+                    self.tint.z,
+                    self.tint.w,
+                ]
+            })
+            .collect()
+    }
+}
+
+mod animation;
+pub use animation::{
+    AnimationFrame, PlaybackMode, SpriteAnimation, SpriteAnimationClip, SpriteAnimationPlayer,
+    SpriteAnimationSystem,
+};
+
+mod atlas;
+pub use atlas::TextureAtlas;
+
+mod renderer;
+pub use renderer::SpriteRenderer;
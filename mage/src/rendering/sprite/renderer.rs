@@ -0,0 +1,175 @@
+use std::cell::Cell;
+use std::sync::Arc;
+
+use hecs::World;
+
+use crate::rendering::engine::SHADER_LIBRARY;
+use crate::rendering::opengl::buffer::{Buffer, BufferType, BufferUsage};
+use crate::rendering::opengl::program::Program;
+use crate::rendering::opengl::shader::ShaderType;
+use crate::rendering::opengl::texture::Texture;
+use crate::rendering::opengl::vertex_array::{DataType, VertexArray};
+use crate::rendering::opengl::{
+    draw_elements_from, set_blend_mode, BlendMode, DrawingMode, OpenGlType,
+};
+use crate::rendering::sprite::Sprite;
+use crate::rendering::transparent::{begin_transparent, end_transparent};
+use crate::rendering::Transform;
+use crate::resources::shader::ShaderLoader;
+use crate::resources::texture::TextureLoader;
+use crate::MageError;
+
+const VERTEX_SHADER: &str = "sprite-vertex.glsl";
+const FRAGMENT_SHADER: &str = "sprite-fragment.glsl";
+const VERTEX_SIZE: usize = 9;
+const VERTICES_PER_SPRITE: usize = 4;
+const INDICES_PER_SPRITE: usize = 6;
+const SPRITE_UNIT: u32 = 0;
+
+struct SpriteBatch {
+    count: usize,
+    start: usize,
+    texture: Arc<Texture>,
+}
+
+pub struct SpriteRenderer {
+    array_buffer: Buffer,
+    capacity: Cell<usize>,
+    element_buffer: Buffer,
+    program: Program,
+    vertex_array: VertexArray,
+}
+
+impl SpriteRenderer {
+    pub fn new() -> Result<SpriteRenderer, MageError> {
+        let shader_loader = ShaderLoader::new(&SHADER_LIBRARY)?;
+        let program = Program::new(
+            shader_loader.load(ShaderType::Vertex, VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, FRAGMENT_SHADER)?,
+        )?;
+        let vertex_array = VertexArray::new();
+        let array_buffer = Buffer::new(BufferType::Array);
+        let element_buffer = Buffer::new(BufferType::ElementArray);
+        vertex_array.bind();
+        array_buffer.bind();
+        element_buffer.bind();
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            0,
+            VERTEX_SIZE as u32,
+            3,
+            0,
+            false,
+        );
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            1,
+            VERTEX_SIZE as u32,
+            2,
+            3,
+            false,
+        );
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            2,
+            VERTEX_SIZE as u32,
+            4,
+            5,
+            false,
+        );
+        VertexArray::unbind();
+        array_buffer.unbind();
+        element_buffer.unbind();
+        Ok(SpriteRenderer {
+            array_buffer,
+            capacity: Cell::new(0),
+            element_buffer,
+            program,
+            vertex_array,
+        })
+    }
+
+    fn reserve(&self, sprites: usize) {
+        if sprites <= self.capacity.get() {
+            return;
+        }
+        let capacity = sprites.next_power_of_two();
+        let indices = (0..capacity as u32)
+            .flat_map(|sprite| {
+                let first = sprite * VERTICES_PER_SPRITE as u32;
+                [first, first + 1, first + 2, first + 2, first + 3, first]
+            })
+            .collect::<Vec<u32>>();
+        self.vertex_array.bind();
+        self.array_buffer.bind();
+        self.array_buffer.allocate_data_with_usage::<f32>(
+            capacity * VERTICES_PER_SPRITE * VERTEX_SIZE,
+            BufferUsage::DynamicDraw,
+        );
+        self.element_buffer.bind();
+        self.element_buffer
+            .set_data(&indices, BufferUsage::StaticDraw);
+        VertexArray::unbind();
+        self.array_buffer.unbind();
+        self.element_buffer.unbind();
+        self.capacity.set(capacity);
+    }
+
+    pub fn render(
+        &self,
+        world: &World,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
+        let mut sprites = vec![];
+        for (_e, (sprite, transform)) in world.query::<(&Sprite, &Transform)>().iter() {
+            let texture = texture_loader.load_texture_2d(&sprite.texture)?;
+            sprites.push((
+                sprite.layer,
+                texture,
+                sprite.vertices(&transform.get_model_matrix()),
+            ));
+        }
+        if sprites.is_empty() {
+            return Ok(());
+        }
+        sprites.sort_by_key(|(layer, texture, _)| (*layer, Arc::as_ptr(texture) as usize));
+
+        let mut batches: Vec<SpriteBatch> = vec![];
+        let mut vertices: Vec<f32> =
+            Vec::with_capacity(sprites.len() * VERTICES_PER_SPRITE * VERTEX_SIZE);
+        for (i, (_layer, texture, sprite_vertices)) in sprites.iter().enumerate() {
+            match batches.last_mut() {
+                Some(batch) if Arc::ptr_eq(&batch.texture, texture) => batch.count += 1,
+                _ => batches.push(SpriteBatch {
+                    count: 1,
+                    start: i,
+                    texture: texture.clone(),
+                }),
+            }
+            vertices.extend(sprite_vertices);
+        }
+
+        self.reserve(sprites.len());
+        self.array_buffer.bind();
+        self.array_buffer.set_sub_data(0, vertices.len(), &vertices);
+        self.array_buffer.unbind();
+
+        self.program.use_program();
+        self.program.set_uniform_i1("sprite", SPRITE_UNIT as i32);
+        begin_transparent();
+        set_blend_mode(BlendMode::Alpha);
+        self.vertex_array.bind();
+        for batch in batches {
+            batch.texture.bind(SPRITE_UNIT);
+            draw_elements_from(
+                DrawingMode::Triangles,
+                (batch.count * INDICES_PER_SPRITE) as u32,
+                OpenGlType::UnsignedInt,
+                batch.start * INDICES_PER_SPRITE,
+            );
+        }
+        VertexArray::unbind();
+        end_transparent();
+        Ok(())
+    }
+}
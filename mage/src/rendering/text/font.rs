@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::fs::{read, read_to_string};
+use std::path::Path;
+
+use fontdue::FontSettings;
+use image::io::Reader;
+use image::RgbaImage;
+use nalgebra::Vector2;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::rendering::sprite::TextureRegion;
+use crate::MageError;
+
+const ATLAS_WIDTH: u32 = 512;
+const GLYPH_PADDING: u32 = 1;
+const DEFAULT_CHARACTERS: std::ops::Range<u8> = 32..127;
+
+#[derive(Debug, Error)]
+pub enum FontError {
+    #[error("Invalid font: {0}")]
+    InvalidFont(String),
+    #[error("Missing attribute {0} in bitmap font")]
+    MissingAttribute(String),
+    #[error("Bitmap fonts with {0} pages are not supported")]
+    MultiplePages(u32),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Glyph {
+    pub advance: f32,
+    pub offset: Vector2<f32>,
+    pub region: TextureRegion,
+    pub size: Vector2<f32>,
+}
+
+#[derive(Debug)]
+pub struct Font {
+    ascent: f32,
+    glyphs: HashMap<char, Glyph>,
+    image: RgbaImage,
+    kerning: HashMap<(char, char), f32>,
+    line_height: f32,
+    size: f32,
+}
+
+impl Font {
+    pub fn from_ttf(path: &str, size: f32) -> Result<Font, MageError> {
+        let characters = DEFAULT_CHARACTERS.map(char::from).collect::<String>();
+        Font::from_ttf_with_characters(path, size, &characters)
+    }
+
+    pub fn from_ttf_with_characters(
+        path: &str,
+        size: f32,
+        characters: &str,
+    ) -> Result<Font, MageError> {
+        let font = fontdue::Font::from_bytes(read(path)?, FontSettings::default())
+            .map_err(|e| FontError::InvalidFont(e.to_string()))?;
+        let line_metrics = font
+            .horizontal_line_metrics(size)
+            .ok_or_else(|| FontError::InvalidFont(path.to_string()))?;
+        let mut rasterized = characters
+            .chars()
+            .map(|c| {
+                let (metrics, bitmap) = font.rasterize(c, size);
+                (c, metrics, bitmap)
+            })
+            .collect::<Vec<_>>();
+        rasterized.sort_by_key(|(_, metrics, _)| std::cmp::Reverse(metrics.height));
+
+        let sizes = rasterized
+            .iter()
+            .map(|(_, metrics, _)| (metrics.width as u32, metrics.height as u32))
+            .collect::<Vec<_>>();
+        let (positions, width, height) = pack(&sizes);
+        let mut image = RgbaImage::new(width, height);
+        let mut glyphs = HashMap::new();
+        for ((c, metrics, bitmap), (x, y)) in rasterized.iter().zip(positions) {
+            for (i, coverage) in bitmap.iter().enumerate() {
+                let px = x + (i % metrics.width) as u32;
+                let py = y + (i / metrics.width) as u32;
+                image.put_pixel(px, py, image::Rgba([255, 255, 255, *coverage]));
+            }
+            glyphs.insert(
+                *c,
+                Glyph {
+                    advance: metrics.advance_width,
+                    offset: Vector2::new(metrics.xmin as f32, metrics.ymin as f32),
+                    region: TextureRegion::from_pixels(
+                        x,
+                        y,
+                        metrics.width as u32,
+                        metrics.height as u32,
+                        width,
+                        height,
+                    ),
+                    size: Vector2::new(metrics.width as f32, metrics.height as f32),
+                },
+            );
+        }
+
+        let mut kerning = HashMap::new();
+        for left in characters.chars() {
+            for right in characters.chars() {
+                match font.horizontal_kern(left, right, size) {
+                    Some(amount) if amount != 0f32 => {
+                        kerning.insert((left, right), amount);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Font {
+            ascent: line_metrics.ascent,
+            glyphs,
+            image,
+            kerning,
+            line_height: line_metrics.new_line_size,
+            size,
+        })
+    }
+
+    pub fn from_bmfont(path: &str) -> Result<Font, MageError> {
+        let content = read_to_string(path)?;
+        let mut ascent = None;
+        let mut line_height = None;
+        let mut size = None;
+        let mut page = None;
+        let mut characters = vec![];
+        let mut kerning = HashMap::new();
+        let regex = Regex::new(r#"(\w+)=("[^"]*"|\S+)"#)?;
+        for line in content.lines() {
+            let tag = line.split_whitespace().next().unwrap_or_default();
+            let attributes = bmfont_attributes(&regex, line);
+            match tag {
+                "info" => size = Some(bmfont_attribute::<f32>(&attributes, "size")?.abs()),
+                "common" => {
+                    line_height = Some(bmfont_attribute::<f32>(&attributes, "lineHeight")?);
+                    ascent = Some(bmfont_attribute::<f32>(&attributes, "base")?);
+                    let pages = bmfont_attribute::<u32>(&attributes, "pages")?;
+                    if pages != 1 {
+                        return Err(FontError::MultiplePages(pages).into());
+                    }
+                }
+                "page" => page = attributes.get("file").cloned(),
+                "char" => characters.push(attributes),
+                "kerning" => {
+                    let first = bmfont_character(&attributes, "first")?;
+                    let second = bmfont_character(&attributes, "second")?;
+                    kerning.insert(
+                        (first, second),
+                        bmfont_attribute::<f32>(&attributes, "amount")?,
+                    );
+                }
+                _ => {}
+            }
+        }
+        let missing = |name: &str| FontError::MissingAttribute(name.to_string());
+        let page = page.ok_or_else(|| missing("page"))?;
+        let ascent = ascent.ok_or_else(|| missing("base"))?;
+        let line_height = line_height.ok_or_else(|| missing("lineHeight"))?;
+        let image_path = Path::new(path)
+            .parent()
+            .map(|parent| parent.join(&page))
+            .unwrap_or_else(|| page.into());
+        let image = Reader::open(image_path)?.decode()?.to_rgba8();
+
+        let mut glyphs = HashMap::new();
+        for attributes in characters {
+            let c = bmfont_character(&attributes, "id")?;
+            let width = bmfont_attribute::<u32>(&attributes, "width")?;
+            let height = bmfont_attribute::<u32>(&attributes, "height")?;
+            let y_offset = bmfont_attribute::<f32>(&attributes, "yoffset")?;
+            glyphs.insert(
+                c,
+                Glyph {
+                    advance: bmfont_attribute(&attributes, "xadvance")?,
+                    offset: Vector2::new(
+                        bmfont_attribute(&attributes, "xoffset")?,
+                        ascent - y_offset - height as f32,
+                    ),
+                    region: TextureRegion::from_pixels(
+                        bmfont_attribute(&attributes, "x")?,
+                        bmfont_attribute(&attributes, "y")?,
+                        width,
+                        height,
+                        image.width(),
+                        image.height(),
+                    ),
+                    size: Vector2::new(width as f32, height as f32),
+                },
+            );
+        }
+
+        Ok(Font {
+            ascent,
+            glyphs,
+            image,
+            kerning,
+            line_height,
+            size: size.unwrap_or(line_height),
+        })
+    }
+
+    pub fn ascent(&self) -> f32 {
+        self.ascent
+    }
+
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c)
+    }
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    pub fn kerning(&self, left: char, right: char) -> f32 {
+        self.kerning.get(&(left, right)).copied().unwrap_or(0f32)
+    }
+
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+}
+
+// The atlas grows wider than `ATLAS_WIDTH` when a single glyph would not fit.
+fn pack(sizes: &[(u32, u32)]) -> (Vec<(u32, u32)>, u32, u32) {
+    let widest = sizes.iter().map(|(width, _)| *width).max().unwrap_or(0);
+    let atlas_width = ATLAS_WIDTH.max((widest + 2 * GLYPH_PADDING).next_power_of_two());
+    let mut positions = vec![];
+    let mut x = GLYPH_PADDING;
+    let mut y = GLYPH_PADDING;
+    let mut row_height = 0;
+    for &(width, height) in sizes {
+        if x + width + GLYPH_PADDING > atlas_width {
+            x = GLYPH_PADDING;
+            y += row_height + GLYPH_PADDING;
+            row_height = 0;
+        }
+        positions.push((x, y));
+        x += width + GLYPH_PADDING;
+        row_height = row_height.max(height);
+    }
+    (
+        positions,
+        atlas_width,
+        (y + row_height + GLYPH_PADDING).next_power_of_two(),
+    )
+}
+
+fn bmfont_attributes(regex: &Regex, line: &str) -> HashMap<String, String> {
+    regex
+        .captures_iter(line)
+        .map(|captures| {
+            (
+                captures[1].to_string(),
+                captures[2].trim_matches('"').to_string(),
+            )
+        })
+        .collect()
+}
+
+fn bmfont_attribute<T: std::str::FromStr>(
+    attributes: &HashMap<String, String>,
+    name: &str,
+) -> Result<T, FontError> {
+    attributes
+        .get(name)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| FontError::MissingAttribute(name.to_string()))
+}
+
+fn bmfont_character(attributes: &HashMap<String, String>, name: &str) -> Result<char, FontError> {
+    char::from_u32(bmfont_attribute(attributes, name)?)
+        .ok_or_else(|| FontError::MissingAttribute(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack, ATLAS_WIDTH, GLYPH_PADDING};
+
+    #[test]
+    fn pack_wraps_rows_inside_the_atlas() {
+        let (positions, width, height) = pack(&[(300, 20), (300, 10)]);
+        assert_eq!(width, ATLAS_WIDTH);
+        assert_eq!(
+            positions,
+            vec![
+                (GLYPH_PADDING, GLYPH_PADDING),
+                (GLYPH_PADDING, 2 * GLYPH_PADDING + 20)
+            ]
+        );
+        assert!(height >= 3 * GLYPH_PADDING + 30);
+    }
+
+    #[test]
+    fn pack_grows_the_atlas_for_wide_glyphs() {
+        let (positions, width, _) = pack(&[(ATLAS_WIDTH, 20)]);
+        assert!(positions[0].0 + ATLAS_WIDTH + GLYPH_PADDING <= width);
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use nalgebra::{Matrix4, Point3, Vector2, Vector4};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TextAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TextSpace {
+    Screen,
+    #[default]
+    World,
+}
+
+#[derive(Clone, Debug)]
+pub struct Text {
+    pub alignment: TextAlignment,
+    pub color: Vector4<f32>,
+    pub content: String,
+    pub font: Arc<Font>,
+    pub max_width: Option<f32>,
+    pub size: f32,
+    pub space: TextSpace,
+}
+
+impl Text {
+    pub fn new(font: Arc<Font>, content: &str, size: f32) -> Text {
+        Text::new_with_space(font, content, size, TextSpace::World)
+    }
+
+    pub fn new_with_space(font: Arc<Font>, content: &str, size: f32, space: TextSpace) -> Text {
+        Text {
+            alignment: TextAlignment::Left,
+            color: Vector4::new(1f32, 1f32, 1f32, 1f32),
+            content: content.to_string(),
+            font,
+            max_width: None,
+            size,
+            space,
+        }
+    }
+
+    fn scale(&self) -> f32 {
+        self.size / self.font.size()
+    }
+
+    fn line_width(&self, line: &str) -> f32 {
+        let mut width = 0f32;
+        let mut previous = None;
+        for c in line.chars() {
+            if let Some(previous) = previous {
+                width += self.font.kerning(previous, c);
+            }
+            width += self
+                .font
+                .glyph(c)
+                .map(|glyph| glyph.advance)
+                .unwrap_or(0f32);
+            previous = Some(c);
+        }
+        width * self.scale()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![];
+        for paragraph in self.content.split('\n') {
+            let max_width = match self.max_width {
+                Some(max_width) => max_width,
+                None => {
+                    lines.push(paragraph.to_string());
+                    continue;
+                }
+            };
+            let mut line = String::new();
+            for word in paragraph.split(' ') {
+                let candidate = if line.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{} {}", line, word)
+                };
+                if !line.is_empty() && self.line_width(&candidate) > max_width {
+                    lines.push(line);
+                    line = word.to_string();
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    pub fn measure(&self) -> Vector2<f32> {
+        let lines = self.lines();
+        let width = lines
+            .iter()
+            .map(|line| self.line_width(line))
+            .fold(0f32, f32::max);
+        Vector2::new(
+            width,
+            lines.len() as f32 * self.font.line_height() * self.scale(),
+        )
+    }
+
+    pub(crate) fn vertices(&self, model: &Matrix4<f32>) -> Vec<f32> {
+        let scale = self.scale();
+        let mut vertices = vec![];
+        for (i, line) in self.lines().iter().enumerate() {
+            let mut x = match self.alignment {
+                TextAlignment::Left => 0f32,
+                TextAlignment::Center => -self.line_width(line) / 2f32,
+                TextAlignment::Right => -self.line_width(line),
+            };
+            let baseline = -(self.font.ascent() + i as f32 * self.font.line_height()) * scale;
+            let mut previous = None;
+            for c in line.chars() {
+                if let Some(previous) = previous {
+                    x += self.font.kerning(previous, c) * scale;
+                }
+                previous = Some(c);
+                let glyph = match self.font.glyph(c) {
+                    Some(glyph) => glyph,
+                    None => continue,
+                };
+                let left = x + glyph.offset.x * scale;
+                let bottom = baseline + glyph.offset.y * scale;
+                let right = left + glyph.size.x * scale;
+                let top = bottom + glyph.size.y * scale;
+                let corners = [
+                    (left, bottom, glyph.region.min.x, glyph.region.min.y),
+                    (right, bottom, glyph.region.max.x, glyph.region.min.y),
+                    (right, top, glyph.region.max.x, glyph.region.max.y),
+                    (left, top, glyph.region.min.x, glyph.region.max.y),
+                ];
+                for (x, y, u, v) in corners {
+                    let position = model.transform_point(&Point3::new(x, y, 0f32));
+                    vertices.extend([
+                        position.x,
+                        position.y,
+                        position.z,
+                        u,
+                        v,
+                        self.color.x,
+                        self.color.y,
+                        self.color.z,
+                        self.color.w,
+                    ]);
+                }
+                x += glyph.advance * scale;
+            }
+        }
+        vertices
+    }
+}
+
+mod font;
+pub use font::{Font, FontError, Glyph};
+
+mod renderer;
+pub(crate) use renderer::font_texture;
+pub use renderer::TextRenderer;
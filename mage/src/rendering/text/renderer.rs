@@ -0,0 +1,240 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use hecs::World;
+use image::imageops::flip_vertical;
+use nalgebra::Matrix4;
+
+use crate::rendering::engine::SHADER_LIBRARY;
+use crate::rendering::opengl::buffer::{Buffer, BufferType, BufferUsage};
+use crate::rendering::opengl::program::Program;
+use crate::rendering::opengl::shader::ShaderType;
+use crate::rendering::opengl::texture::{
+    Texture, TextureDimension, TextureFormat, TextureParameter, TextureParameterValue,
+};
+use crate::rendering::opengl::vertex_array::{DataType, VertexArray};
+use crate::rendering::opengl::{
+    disable, draw_elements_from, enable, get_viewport, set_blend_mode, BlendMode, DrawingMode,
+    Feature, OpenGlType,
+};
+use crate::rendering::text::{Font, Text, TextSpace};
+use crate::rendering::transparent::{begin_transparent, end_transparent};
+use crate::rendering::Transform;
+use crate::resources::shader::ShaderLoader;
+use crate::MageError;
+
+const VERTEX_SHADER: &str = "text-vertex.glsl";
+const FRAGMENT_SHADER: &str = "text-fragment.glsl";
+const VERTEX_SIZE: usize = 9;
+const VERTICES_PER_GLYPH: usize = 4;
+const INDICES_PER_GLYPH: usize = 6;
+const GLYPHS_UNIT: u32 = 0;
+
+type FontTexture = (Weak<Font>, Arc<Texture>);
+
+struct TextBatch {
+    count: usize,
+    space: TextSpace,
+    start: usize,
+    texture: Arc<Texture>,
+}
+
+pub struct TextRenderer {
+    array_buffer: Buffer,
+    capacity: Cell<usize>,
+    element_buffer: Buffer,
+    program: Program,
+    textures: RefCell<HashMap<usize, FontTexture>>,
+    vertex_array: VertexArray,
+}
+
+impl TextRenderer {
+    pub fn new() -> Result<TextRenderer, MageError> {
+        let shader_loader = ShaderLoader::new(&SHADER_LIBRARY)?;
+        let program = Program::new(
+            shader_loader.load(ShaderType::Vertex, VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, FRAGMENT_SHADER)?,
+        )?;
+        let vertex_array = VertexArray::new();
+        let array_buffer = Buffer::new(BufferType::Array);
+        let element_buffer = Buffer::new(BufferType::ElementArray);
+        vertex_array.bind();
+        array_buffer.bind();
+        element_buffer.bind();
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            0,
+            VERTEX_SIZE as u32,
+            3,
+            0,
+            false,
+        );
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            1,
+            VERTEX_SIZE as u32,
+            2,
+            3,
+            false,
+        );
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            2,
+            VERTEX_SIZE as u32,
+            4,
+            5,
+            false,
+        );
+        VertexArray::unbind();
+        array_buffer.unbind();
+        element_buffer.unbind();
+        Ok(TextRenderer {
+            array_buffer,
+            capacity: Cell::new(0),
+            element_buffer,
+            program,
+            textures: RefCell::new(HashMap::new()),
+            vertex_array,
+        })
+    }
+
+    fn reserve(&self, glyphs: usize) {
+        if glyphs <= self.capacity.get() {
+            return;
+        }
+        let capacity = glyphs.next_power_of_two();
+        let indices = (0..capacity as u32)
+            .flat_map(|glyph| {
+                let first = glyph * VERTICES_PER_GLYPH as u32;
+                [first, first + 1, first + 2, first + 2, first + 3, first]
+            })
+            .collect::<Vec<u32>>();
+        self.vertex_array.bind();
+        self.array_buffer.bind();
+        self.array_buffer.allocate_data_with_usage::<f32>(
+            capacity * VERTICES_PER_GLYPH * VERTEX_SIZE,
+            BufferUsage::DynamicDraw,
+        );
+        self.element_buffer.bind();
+        self.element_buffer
+            .set_data(&indices, BufferUsage::StaticDraw);
+        VertexArray::unbind();
+        self.array_buffer.unbind();
+        self.element_buffer.unbind();
+        self.capacity.set(capacity);
+    }
+
+    fn texture(&self, font: &Arc<Font>) -> Arc<Texture> {
+        let mut textures = self.textures.borrow_mut();
+        textures.retain(|_, (font, _)| font.strong_count() > 0);
+        let key = Arc::as_ptr(font) as usize;
+        if let Some((cached, texture)) = textures.get(&key) {
+            if Weak::ptr_eq(cached, &Arc::downgrade(font)) {
+                return texture.clone();
+            }
+        }
+        let texture = Arc::new(font_texture(font, GLYPHS_UNIT));
+        textures.insert(key, (Arc::downgrade(font), texture.clone()));
+        texture
+    }
+
+    pub fn render(&self, world: &World) -> Result<(), MageError> {
+        let mut texts = vec![];
+        for (_e, (text, transform)) in world.query::<(&Text, &Transform)>().iter() {
+            let vertices = text.vertices(&transform.get_model_matrix());
+            if !vertices.is_empty() {
+                texts.push((text.space, self.texture(&text.font), vertices));
+            }
+        }
+        if texts.is_empty() {
+            return Ok(());
+        }
+        texts.sort_by_key(|(space, texture, _)| {
+            (*space == TextSpace::Screen, Arc::as_ptr(texture) as usize)
+        });
+
+        let mut batches: Vec<TextBatch> = vec![];
+        let mut vertices: Vec<f32> = vec![];
+        for (space, texture, text_vertices) in texts.iter() {
+            let start = vertices.len() / (VERTICES_PER_GLYPH * VERTEX_SIZE);
+            let count = text_vertices.len() / (VERTICES_PER_GLYPH * VERTEX_SIZE);
+            match batches.last_mut() {
+                Some(batch) if batch.space == *space && Arc::ptr_eq(&batch.texture, texture) => {
+                    batch.count += count
+                }
+                _ => batches.push(TextBatch {
+                    count,
+                    space: *space,
+                    start,
+                    texture: texture.clone(),
+                }),
+            }
+            vertices.extend(text_vertices);
+        }
+
+        self.reserve(vertices.len() / (VERTICES_PER_GLYPH * VERTEX_SIZE));
+        self.array_buffer.bind();
+        self.array_buffer.set_sub_data(0, vertices.len(), &vertices);
+        self.array_buffer.unbind();
+
+        let (_x, _y, width, height) = get_viewport();
+        self.program.use_program();
+        self.program.set_uniform_i1("glyphs", GLYPHS_UNIT as i32);
+        self.program.set_uniform_matrix4(
+            "screenProjection",
+            Matrix4::new_orthographic(0f32, width as f32, 0f32, height as f32, -1f32, 1f32),
+        );
+        begin_transparent();
+        set_blend_mode(BlendMode::Alpha);
+        self.vertex_array.bind();
+        for batch in batches {
+            let screen_space = batch.space == TextSpace::Screen;
+            self.program
+                .set_uniform_i1("screenSpace", screen_space as i32);
+            if screen_space {
+                disable(Feature::Depth);
+            }
+            batch.texture.bind(GLYPHS_UNIT);
+            draw_elements_from(
+                DrawingMode::Triangles,
+                (batch.count * INDICES_PER_GLYPH) as u32,
+                OpenGlType::UnsignedInt,
+                batch.start * INDICES_PER_GLYPH,
+            );
+        }
+        VertexArray::unbind();
+        enable(Feature::Depth);
+        end_transparent();
+        Ok(())
+    }
+}
+
+pub(crate) fn font_texture(font: &Font, unit: u32) -> Texture {
+    let texture = Texture::new(TextureDimension::Texture2D);
+    let image = flip_vertical(font.image());
+    texture.bind(unit);
+    texture.set_image_2d(
+        image.width(),
+        image.height(),
+        image.as_raw(),
+        TextureFormat::UnsignedByteWithAlpha,
+    );
+    texture.set_parameter(
+        TextureParameter::TextureWrapS,
+        TextureParameterValue::ClampToEdge,
+    );
+    texture.set_parameter(
+        TextureParameter::TextureWrapT,
+        TextureParameterValue::ClampToEdge,
+    );
+    texture.set_parameter(
+        TextureParameter::TextureMinFilter,
+        TextureParameterValue::Linear,
+    );
+    texture.set_parameter(
+        TextureParameter::TextureMagFilter,
+        TextureParameterValue::Linear,
+    );
+    texture
+}
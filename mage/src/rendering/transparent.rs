@@ -0,0 +1,71 @@
+use std::cmp::Ordering;
+
+use hecs::{Entity, World};
+use nalgebra::{Point3, Vector3};
+
+use crate::rendering::model::mesh::RenderingMesh;
+use crate::rendering::opengl::{disable, enable, set_depth_mask, BlendMode, Feature};
+use crate::rendering::Transform;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RenderQueue {
+    #[default]
+    Opaque,
+    AlphaTest(f32),
+    Transparent(BlendMode),
+}
+
+impl RenderQueue {
+    pub fn alpha_cutoff(&self) -> f32 {
+        match self {
+            RenderQueue::AlphaTest(cutoff) => *cutoff,
+            _ => 0f32,
+        }
+    }
+}
+
+pub(crate) struct RenderOrder {
+    pub(crate) opaque: Vec<Entity>,
+    pub(crate) transparent: Vec<(Entity, BlendMode)>,
+}
+
+pub(crate) fn render_order(world: &World, camera_position: Vector3<f32>) -> RenderOrder {
+    let mut opaque = vec![];
+    let mut alpha_test = vec![];
+    let mut transparent = vec![];
+    for (e, (mesh, transform, queue)) in world
+        .query::<(&RenderingMesh, &Transform, Option<&RenderQueue>)>()
+        .iter()
+    {
+        match queue.copied().unwrap_or_default() {
+            RenderQueue::Opaque => opaque.push(e),
+            RenderQueue::AlphaTest(_) => alpha_test.push(e),
+            RenderQueue::Transparent(mode) => {
+                let center = transform
+                    .get_model_matrix()
+                    .transform_point(&Point3::from(mesh.bounds.sphere.center));
+                let distance = (center.coords - camera_position).norm_squared();
+                transparent.push((e, mode, distance));
+            }
+        }
+    }
+    opaque.extend(alpha_test);
+    transparent.sort_by(|(_, _, a), (_, _, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+    RenderOrder {
+        opaque,
+        transparent: transparent
+            .into_iter()
+            .map(|(e, mode, _)| (e, mode))
+            .collect(),
+    }
+}
+
+pub(crate) fn begin_transparent() {
+    enable(Feature::Blend);
+    set_depth_mask(false);
+}
+
+pub(crate) fn end_transparent() {
+    set_depth_mask(true);
+    disable(Feature::Blend);
+}
@@ -0,0 +1,376 @@
+use std::fs;
+use std::path::Path;
+
+use crate::resources::gltf::json::{Accessor, Document};
+use crate::resources::gltf::GltfError;
+use crate::MageError;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A;
+const GLB_BIN_CHUNK: u32 = 0x004E_4942;
+const DATA_URI_PREFIX: &str = "data:";
+const BASE64_MARKER: &str = ";base64,";
+
+const BYTE: u32 = 5120;
+const UNSIGNED_BYTE: u32 = 5121;
+const SHORT: u32 = 5122;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, GltfError> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(GltfError::InvalidGlb)
+}
+
+pub(crate) fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), GltfError> {
+    if read_u32(bytes, 0)? != GLB_MAGIC {
+        return Err(GltfError::InvalidGlb);
+    }
+    let length = (read_u32(bytes, 8)? as usize).min(bytes.len());
+    let mut offset = 12;
+    let mut json = None;
+    let mut binary = None;
+    while offset + 8 <= length {
+        let chunk_length = read_u32(bytes, offset)? as usize;
+        let chunk_type = read_u32(bytes, offset + 4)?;
+        let chunk = bytes
+            .get(offset + 8..offset + 8 + chunk_length)
+            .ok_or(GltfError::InvalidGlb)?;
+        match chunk_type {
+            GLB_JSON_CHUNK => json = Some(chunk),
+            GLB_BIN_CHUNK if binary.is_none() => binary = Some(chunk),
+            _ => {}
+        }
+        offset += 8 + chunk_length;
+    }
+    Ok((json.ok_or(GltfError::InvalidGlb)?, binary))
+}
+
+pub(crate) fn load_uri(uri: &str, directory: &Path) -> Result<Vec<u8>, MageError> {
+    if let Some(data) = uri.strip_prefix(DATA_URI_PREFIX) {
+        let encoded = data
+            .find(BASE64_MARKER)
+            .map(|index| &data[index + BASE64_MARKER.len()..])
+            .ok_or_else(|| GltfError::UnsupportedUri(uri.chars().take(32).collect()))?;
+        Ok(base64::decode(encoded)?)
+    } else {
+        Ok(fs::read(directory.join(percent_decode(uri)))?)
+    }
+}
+
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| uri.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+pub(crate) fn load_buffers(
+    document: &Document,
+    binary: Option<&[u8]>,
+    directory: &Path,
+) -> Result<Vec<Vec<u8>>, MageError> {
+    document
+        .buffers
+        .iter()
+        .map(|buffer| match (&buffer.uri, binary) {
+            (Some(uri), _) => load_uri(uri, directory),
+            (None, Some(binary)) => Ok(binary.to_vec()),
+            (None, None) => Err(GltfError::MissingBinary.into()),
+        })
+        .collect()
+}
+
+fn components(kind: &str) -> Result<usize, GltfError> {
+    match kind {
+        "SCALAR" => Ok(1),
+        "VEC2" => Ok(2),
+        "VEC3" => Ok(3),
+        "VEC4" | "MAT2" => Ok(4),
+        "MAT3" => Ok(9),
+        "MAT4" => Ok(16),
+        _ => Err(GltfError::InvalidAccessor(kind.to_owned())),
+    }
+}
+
+fn component_size(component_type: u32) -> Result<usize, GltfError> {
+    match component_type {
+        BYTE | UNSIGNED_BYTE => Ok(1),
+        SHORT | UNSIGNED_SHORT => Ok(2),
+        UNSIGNED_INT | FLOAT => Ok(4),
+        _ => Err(GltfError::InvalidAccessor(component_type.to_string())),
+    }
+}
+
+fn read_component(bytes: &[u8], component_type: u32, normalized: bool) -> f32 {
+    match (component_type, normalized) {
+        (BYTE, true) => (bytes[0] as i8 as f32 / 127f32).max(-1f32),
+        (BYTE, false) => bytes[0] as i8 as f32,
+        (UNSIGNED_BYTE, true) => bytes[0] as f32 / 255f32,
+        (UNSIGNED_BYTE, false) => bytes[0] as f32,
+        (SHORT, true) => (i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32767f32).max(-1f32),
+        (SHORT, false) => i16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+        (UNSIGNED_SHORT, true) => u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 65535f32,
+        (UNSIGNED_SHORT, false) => u16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+        (UNSIGNED_INT, _) => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+        _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    }
+}
+
+fn read_index(bytes: &[u8], component_type: u32) -> u32 {
+    match component_type {
+        UNSIGNED_BYTE => bytes[0] as u32,
+        UNSIGNED_SHORT => u16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+        _ => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    }
+}
+
+fn view_slice<'a>(
+    document: &Document,
+    buffers: &'a [Vec<u8>],
+    view: usize,
+    offset: usize,
+) -> Result<(&'a [u8], Option<usize>), GltfError> {
+    let view = document
+        .buffer_views
+        .get(view)
+        .ok_or(GltfError::InvalidIndex("bufferView", view))?;
+    let buffer = buffers
+        .get(view.buffer)
+        .ok_or(GltfError::InvalidIndex("buffer", view.buffer))?;
+    let slice = buffer
+        .get(view.byte_offset..view.byte_offset + view.byte_length)
+        .and_then(|slice| slice.get(offset..))
+        .ok_or_else(|| GltfError::InvalidAccessor("out of bounds".to_owned()))?;
+    Ok((slice, view.byte_stride))
+}
+
+fn read_elements<T>(
+    bytes: &[u8],
+    count: usize,
+    stride: usize,
+    element_size: usize,
+    read: impl Fn(&[u8]) -> T,
+) -> Result<Vec<T>, GltfError> {
+    (0..count)
+        .map(|i| {
+            bytes
+                .get(i * stride..i * stride + element_size)
+                .map(&read)
+                .ok_or_else(|| GltfError::InvalidAccessor("out of bounds".to_owned()))
+        })
+        .collect()
+}
+
+pub(crate) fn read_accessor(
+    document: &Document,
+    buffers: &[Vec<u8>],
+    index: usize,
+) -> Result<(Vec<f32>, usize), GltfError> {
+    let accessor: &Accessor = document
+        .accessors
+        .get(index)
+        .ok_or(GltfError::InvalidIndex("accessor", index))?;
+    let components = components(&accessor.kind)?;
+    let size = component_size(accessor.component_type)?;
+    let element_size = components * size;
+    let read = |bytes: &[u8]| -> Vec<f32> {
+        (0..components)
+            .map(|c| {
+                read_component(
+                    &bytes[c * size..],
+                    accessor.component_type,
+                    accessor.normalized,
+                )
+            })
+            .collect()
+    };
+
+    let mut values = match accessor.buffer_view {
+        Some(view) => {
+            let (bytes, stride) = view_slice(document, buffers, view, accessor.byte_offset)?;
+            read_elements(
+                bytes,
+                accessor.count,
+                stride.unwrap_or(element_size),
+                element_size,
+                read,
+            )?
+            .concat()
+        }
+        None => vec![0f32; accessor.count * components],
+    };
+
+    if let Some(sparse) = &accessor.sparse {
+        let index_size = component_size(sparse.indices.component_type)?;
+        let (bytes, _) = view_slice(
+            document,
+            buffers,
+            sparse.indices.buffer_view,
+            sparse.indices.byte_offset,
+        )?;
+        let indices = read_elements(bytes, sparse.count, index_size, index_size, |b| {
+            read_index(b, sparse.indices.component_type) as usize
+        })?;
+        let (bytes, _) = view_slice(
+            document,
+            buffers,
+            sparse.values.buffer_view,
+            sparse.values.byte_offset,
+        )?;
+        let replacements = read_elements(bytes, sparse.count, element_size, element_size, read)?;
+        for (index, replacement) in indices.into_iter().zip(replacements) {
+            if let Some(target) = values.get_mut(index * components..(index + 1) * components) {
+                target.copy_from_slice(&replacement);
+            }
+        }
+    }
+    Ok((values, components))
+}
+
+pub(crate) fn read_indices(
+    document: &Document,
+    buffers: &[Vec<u8>],
+    index: usize,
+) -> Result<Vec<u32>, GltfError> {
+    let accessor = document
+        .accessors
+        .get(index)
+        .ok_or(GltfError::InvalidIndex("accessor", index))?;
+    match (
+        accessor.component_type,
+        &accessor.buffer_view,
+        &accessor.sparse,
+    ) {
+        (UNSIGNED_BYTE | UNSIGNED_SHORT | UNSIGNED_INT, Some(view), None) => {
+            let size = component_size(accessor.component_type)?;
+            let (bytes, stride) = view_slice(document, buffers, *view, accessor.byte_offset)?;
+            read_elements(bytes, accessor.count, stride.unwrap_or(size), size, |b| {
+                read_index(b, accessor.component_type)
+            })
+        }
+        _ => Ok(read_accessor(document, buffers, index)?
+            .0
+            .into_iter()
+            .map(|v| v as u32)
+            .collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_accessor, split_glb, GLB_BIN_CHUNK, GLB_JSON_CHUNK, GLB_MAGIC};
+    use crate::resources::gltf::json::Document;
+
+    fn floats(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn document(json: &str) -> Document {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn split_glb_reads_json_and_binary_chunks() {
+        let mut bytes = vec![];
+        for word in [GLB_MAGIC, 2, 36, 4, GLB_JSON_CHUNK] {
+            bytes.extend(word.to_le_bytes());
+        }
+        bytes.extend(b"{}  ");
+        bytes.extend(4u32.to_le_bytes());
+        bytes.extend(GLB_BIN_CHUNK.to_le_bytes());
+        bytes.extend([1u8, 2, 3, 4]);
+        let (json, binary) = split_glb(&bytes).unwrap();
+        assert_eq!(json, b"{}  ");
+        assert_eq!(binary, Some(&[1u8, 2, 3, 4][..]));
+        assert!(split_glb(&bytes[4..]).is_err());
+    }
+
+    #[test]
+    fn read_accessor_honours_byte_stride() {
+        let document = document(
+            r#"{
+                "accessors": [{"bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC2"}],
+                "bufferViews": [{"buffer": 0, "byteLength": 24, "byteStride": 12}]
+            }"#,
+        );
+        let buffers = vec![floats(&[1f32, 2f32, 9f32, 3f32, 4f32, 9f32])];
+        let (values, components) = read_accessor(&document, &buffers, 0).unwrap();
+        assert_eq!(components, 2);
+        assert_eq!(values, vec![1f32, 2f32, 3f32, 4f32]);
+    }
+
+    #[test]
+    fn read_accessor_applies_sparse_values() {
+        let document = document(
+            r#"{
+                "accessors": [{
+                    "componentType": 5126,
+                    "count": 3,
+                    "type": "SCALAR",
+                    "sparse": {
+                        "count": 1,
+                        "indices": {"bufferView": 0, "componentType": 5121},
+                        "values": {"bufferView": 1}
+                    }
+                }],
+                "bufferViews": [
+                    {"buffer": 0, "byteLength": 1},
+                    {"buffer": 1, "byteLength": 4}
+                ]
+            }"#,
+        );
+        let buffers = vec![vec![2u8], floats(&[5f32])];
+        let (values, _) = read_accessor(&document, &buffers, 0).unwrap();
+        assert_eq!(values, vec![0f32, 0f32, 5f32]);
+    }
+
+    #[test]
+    fn read_accessor_normalizes_integers() {
+        let document = document(
+            r#"{
+                "accessors": [
+                    {"bufferView": 0, "componentType": 5121, "count": 1, "normalized": true, "type": "VEC2"},
+                    {"bufferView": 0, "componentType": 5120, "count": 1, "normalized": true, "type": "VEC2"}
+                ],
+                "bufferViews": [{"buffer": 0, "byteLength": 2}]
+            }"#,
+        );
+        let buffers = vec![vec![255u8, 128u8]];
+        let (unsigned, _) = read_accessor(&document, &buffers, 0).unwrap();
+        assert_eq!(unsigned, vec![1f32, 128f32 / 255f32]);
+        let (signed, _) = read_accessor(&document, &buffers, 1).unwrap();
+        assert_eq!(signed, vec![-1f32 / 127f32, -1f32]);
+    }
+
+    #[test]
+    fn read_accessor_rejects_out_of_bounds_views() {
+        let document = document(
+            r#"{
+                "accessors": [{"bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC3"}],
+                "bufferViews": [{"buffer": 0, "byteLength": 12}]
+            }"#,
+        );
+        let buffers = vec![floats(&[1f32, 2f32, 3f32])];
+        assert!(read_accessor(&document, &buffers, 0).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Document {
+    pub(crate) accessors: Vec<Accessor>,
+    pub(crate) animations: Vec<Animation>,
+    pub(crate) buffer_views: Vec<BufferView>,
+    pub(crate) buffers: Vec<Buffer>,
+    pub(crate) cameras: Vec<Camera>,
+    pub(crate) extensions: DocumentExtensions,
+    pub(crate) images: Vec<Image>,
+    pub(crate) materials: Vec<Material>,
+    pub(crate) meshes: Vec<Mesh>,
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) samplers: Vec<Sampler>,
+    pub(crate) scene: Option<usize>,
+    pub(crate) scenes: Vec<Scene>,
+    pub(crate) skins: Vec<Skin>,
+    pub(crate) textures: Vec<Texture>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct DocumentExtensions {
+    #[serde(rename = "KHR_lights_punctual")]
+    pub(crate) lights_punctual: Option<LightsPunctual>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LightsPunctual {
+    pub(crate) lights: Vec<Light>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Accessor {
+    pub(crate) buffer_view: Option<usize>,
+    #[serde(default)]
+    pub(crate) byte_offset: usize,
+    pub(crate) component_type: u32,
+    pub(crate) count: usize,
+    #[serde(default)]
+    pub(crate) normalized: bool,
+    pub(crate) sparse: Option<Sparse>,
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Sparse {
+    pub(crate) count: usize,
+    pub(crate) indices: SparseIndices,
+    pub(crate) values: SparseValues,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SparseIndices {
+    pub(crate) buffer_view: usize,
+    #[serde(default)]
+    pub(crate) byte_offset: usize,
+    pub(crate) component_type: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SparseValues {
+    pub(crate) buffer_view: usize,
+    #[serde(default)]
+    pub(crate) byte_offset: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BufferView {
+    pub(crate) buffer: usize,
+    pub(crate) byte_length: usize,
+    #[serde(default)]
+    pub(crate) byte_offset: usize,
+    pub(crate) byte_stride: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Buffer {
+    pub(crate) uri: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Perspective {
+    pub(crate) aspect_ratio: Option<f32>,
+    pub(crate) yfov: f32,
+    pub(crate) zfar: Option<f32>,
+    pub(crate) znear: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Orthographic {
+    pub(crate) xmag: f32,
+    pub(crate) ymag: f32,
+    pub(crate) zfar: f32,
+    pub(crate) znear: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Camera {
+    pub(crate) name: Option<String>,
+    pub(crate) orthographic: Option<Orthographic>,
+    pub(crate) perspective: Option<Perspective>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Image {
+    pub(crate) buffer_view: Option<usize>,
+    pub(crate) uri: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TextureReference {
+    pub(crate) index: usize,
+    #[serde(default)]
+    pub(crate) tex_coord: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct PbrMetallicRoughness {
+    pub(crate) base_color_factor: Option<[f32; 4]>,
+    pub(crate) base_color_texture: Option<TextureReference>,
+    pub(crate) metallic_factor: Option<f32>,
+    pub(crate) metallic_roughness_texture: Option<TextureReference>,
+    pub(crate) roughness_factor: Option<f32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Material {
+    pub(crate) alpha_cutoff: Option<f32>,
+    pub(crate) alpha_mode: Option<String>,
+    pub(crate) name: Option<String>,
+    pub(crate) normal_texture: Option<TextureReference>,
+    pub(crate) occlusion_texture: Option<OcclusionTexture>,
+    pub(crate) pbr_metallic_roughness: PbrMetallicRoughness,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OcclusionTexture {
+    pub(crate) index: usize,
+    pub(crate) strength: Option<f32>,
+    #[serde(default)]
+    pub(crate) tex_coord: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Mesh {
+    pub(crate) primitives: Vec<Primitive>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Primitive {
+    pub(crate) attributes: HashMap<String, usize>,
+    pub(crate) indices: Option<usize>,
+    pub(crate) material: Option<usize>,
+    pub(crate) mode: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct NodeExtensions {
+    #[serde(rename = "KHR_lights_punctual")]
+    pub(crate) lights_punctual: Option<NodeLight>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NodeLight {
+    pub(crate) light: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Node {
+    pub(crate) camera: Option<usize>,
+    pub(crate) children: Vec<usize>,
+    pub(crate) extensions: NodeExtensions,
+    pub(crate) matrix: Option<[f32; 16]>,
+    pub(crate) mesh: Option<usize>,
+    pub(crate) name: Option<String>,
+    pub(crate) rotation: Option<[f32; 4]>,
+    pub(crate) scale: Option<[f32; 3]>,
+    pub(crate) skin: Option<usize>,
+    pub(crate) translation: Option<[f32; 3]>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Sampler {
+    pub(crate) mag_filter: Option<u32>,
+    pub(crate) min_filter: Option<u32>,
+    pub(crate) wrap_s: Option<u32>,
+    pub(crate) wrap_t: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Scene {
+    pub(crate) name: Option<String>,
+    pub(crate) nodes: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Texture {
+    pub(crate) sampler: Option<usize>,
+    pub(crate) source: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Light {
+    pub(crate) color: Option<[f32; 3]>,
+    pub(crate) intensity: Option<f32>,
+    pub(crate) name: Option<String>,
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Skin {
+    pub(crate) inverse_bind_matrices: Option<usize>,
+    pub(crate) joints: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AnimationTarget {
+    pub(crate) node: Option<usize>,
+    pub(crate) path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AnimationChannel {
+    pub(crate) sampler: usize,
+    pub(crate) target: AnimationTarget,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AnimationSampler {
+    pub(crate) input: usize,
+    pub(crate) interpolation: Option<String>,
+    pub(crate) output: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Animation {
+    pub(crate) channels: Vec<AnimationChannel>,
+    pub(crate) name: Option<String>,
+    pub(crate) samplers: Vec<AnimationSampler>,
+}
@@ -0,0 +1,899 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use hecs::{Entity, World};
+use log::warn;
+use nalgebra::{Matrix4, Point3, Quaternion, UnitQuaternion, Vector2, Vector3, Vector4};
+use rapier3d::geometry::{Collider, ColliderBuilder};
+use russimp::texture::TextureType;
+use thiserror::Error;
+
+use crate::animation::{AnimationClip, AnimationPlayer, Channel, Interpolation, Keyframes, Skin};
+use crate::gameplay::camera::{FixedCamera, FixedCameraBuilder};
+use crate::physics::PendingColliders;
+use crate::rendering::hierarchy::{Children, Name, Parent};
+use crate::rendering::lights::{DirectionalLight, PointLight};
+use crate::rendering::model::material::PbrMaterial;
+use crate::rendering::model::mesh::{next_revision, Mesh, TextureInfo, TextureSource};
+use crate::rendering::opengl::texture::{TextureParameter, TextureParameterValue};
+use crate::rendering::opengl::{BlendMode, DrawingMode};
+use crate::rendering::transparent::RenderQueue;
+use crate::rendering::Transform;
+use crate::MageError;
+
+mod buffer;
+mod json;
+
+use buffer::{load_buffers, load_uri, read_accessor, read_indices, split_glb};
+use json::Document;
+
+const DEFAULT_ALPHA_CUTOFF: f32 = 0.5;
+const MODE_TRIANGLES: u32 = 4;
+const MODE_TRIANGLE_STRIP: u32 = 5;
+const MODE_TRIANGLE_FAN: u32 = 6;
+
+#[derive(Debug, Error)]
+pub enum GltfError {
+    #[error("Invalid GLB container")]
+    InvalidGlb,
+    #[error("Buffer without uri and no binary chunk")]
+    MissingBinary,
+    #[error("Unsupported uri {0}")]
+    UnsupportedUri(String),
+    #[error("Invalid accessor: {0}")]
+    InvalidAccessor(String),
+    #[error("Invalid {0} index {1}")]
+    InvalidIndex(&'static str, usize),
+    #[error("Primitive without POSITION attribute")]
+    MissingPositions,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GltfColliders {
+    ConvexHull,
+    #[default]
+    None,
+    TriMesh,
+}
+
+#[derive(Clone, Debug)]
+pub enum GltfProjection {
+    Orthographic {
+        xmag: f32,
+        ymag: f32,
+        zfar: f32,
+        znear: f32,
+    },
+    Perspective {
+        aspect_ratio: Option<f32>,
+        yfov: f32,
+        zfar: Option<f32>,
+        znear: f32,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct GltfCamera {
+    pub name: String,
+    pub projection: GltfProjection,
+    pub transform: Transform,
+}
+
+impl GltfCamera {
+    pub fn to_fixed_camera(&self, width: u32, height: u32) -> Option<FixedCamera> {
+        let (yfov, zfar, znear) = match self.projection {
+            GltfProjection::Perspective {
+                yfov, zfar, znear, ..
+            } => (yfov, zfar, znear),
+            GltfProjection::Orthographic { .. } => return None,
+        };
+        let mut builder = FixedCameraBuilder::new(width, height, self.transform.position);
+        builder.fov(yfov.to_degrees());
+        builder.close_plane(znear);
+        if let Some(zfar) = zfar {
+            builder.far_plane(zfar);
+        }
+        builder.front(self.transform.rotation * -Vector3::z_axis());
+        builder.world_up(self.transform.rotation * Vector3::y_axis());
+        Some(builder.build())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GltfMaterial {
+    pub material: PbrMaterial,
+    pub name: String,
+    pub queue: RenderQueue,
+    pub textures: Vec<TextureInfo>,
+    pub texture_coordinates: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct GltfPrimitive {
+    pub material: Option<usize>,
+    pub mesh: Mesh,
+    pub texture_coordinates: Vec<Vec<Vector2<f32>>>,
+}
+
+pub struct GltfModel {
+    pub animations: Vec<Arc<AnimationClip>>,
+    pub cameras: Vec<GltfCamera>,
+    pub entities: Vec<Entity>,
+    pub root: Entity,
+}
+
+struct SpawnContext {
+    meshes: HashMap<usize, Vec<GltfPrimitive>>,
+    model: GltfModel,
+    nodes: HashMap<usize, Entity>,
+    skinned: Vec<(Entity, usize)>,
+}
+
+pub(crate) struct GltfData {
+    pub(crate) buffers: Vec<Vec<u8>>,
+    pub(crate) directory: PathBuf,
+    pub(crate) document: Document,
+    pub(crate) materials: Vec<GltfMaterial>,
+}
+
+impl GltfData {
+    pub(crate) fn open(path: &str) -> Result<GltfData, MageError> {
+        let bytes = fs::read(path)?;
+        let directory = Path::new(path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let (json, binary) = if bytes.starts_with(b"glTF") {
+            split_glb(&bytes)?
+        } else {
+            (bytes.as_slice(), None)
+        };
+        let document: Document = serde_json::from_slice(json)?;
+        let buffers = load_buffers(&document, binary, &directory)?;
+        let mut data = GltfData {
+            buffers,
+            directory,
+            document,
+            materials: vec![],
+        };
+        data.materials = (0..data.document.materials.len())
+            .map(|index| data.material(index))
+            .collect::<Result<_, _>>()?;
+        Ok(data)
+    }
+
+    pub(crate) fn accessor(&self, index: usize) -> Result<(Vec<f32>, usize), GltfError> {
+        read_accessor(&self.document, &self.buffers, index)
+    }
+
+    pub(crate) fn node_transform(&self, index: usize) -> Transform {
+        let node = &self.document.nodes[index];
+        if let Some(matrix) = node.matrix {
+            return Transform::from_matrix(&Matrix4::from_column_slice(&matrix));
+        }
+        let [x, y, z] = node.translation.unwrap_or([0f32; 3]);
+        let [i, j, k, w] = node.rotation.unwrap_or([0f32, 0f32, 0f32, 1f32]);
+        let [sx, sy, sz] = node.scale.unwrap_or([1f32; 3]);
+        Transform {
+            position: Vector3::new(x, y, z),
+            rotation: UnitQuaternion::from_quaternion(Quaternion::new(w, i, j, k)),
+            scale: Vector3::new(sx, sy, sz),
+        }
+    }
+
+    fn vectors(&self, index: usize, expected: usize) -> Result<Vec<f32>, GltfError> {
+        let (values, components) = self.accessor(index)?;
+        if components != expected {
+            return Err(GltfError::InvalidAccessor(format!(
+                "expected VEC{} at {}",
+                expected, index
+            )));
+        }
+        Ok(values)
+    }
+
+    fn vectors3(&self, index: usize) -> Result<Vec<Vector3<f32>>, GltfError> {
+        Ok(self
+            .vectors(index, 3)?
+            .chunks(3)
+            .map(|v| Vector3::new(v[0], v[1], v[2]))
+            .collect())
+    }
+
+    fn vectors4(&self, index: usize) -> Result<Vec<Vector4<f32>>, GltfError> {
+        Ok(self
+            .vectors(index, 4)?
+            .chunks(4)
+            .map(|v| Vector4::new(v[0], v[1], v[2], v[3]))
+            .collect())
+    }
+
+    fn texture_parameters(
+        &self,
+        sampler: Option<usize>,
+    ) -> HashMap<TextureParameter, TextureParameterValue> {
+        let sampler = sampler.and_then(|sampler| self.document.samplers.get(sampler));
+        let value = |value: Option<u32>, default: TextureParameterValue| match value {
+            Some(9728) => TextureParameterValue::Nearest,
+            Some(9729) => TextureParameterValue::Linear,
+            Some(9984) => TextureParameterValue::NearestMipmapNearest,
+            Some(9985) => TextureParameterValue::LinearMipmapNearest,
+            Some(9986) => TextureParameterValue::NearestMipmapLinear,
+            Some(9987) => TextureParameterValue::LinearMipmapLinear,
+            Some(33071) => TextureParameterValue::ClampToEdge,
+            Some(33648) => TextureParameterValue::MirroredRepeat,
+            Some(10497) => TextureParameterValue::Repeat,
+            _ => default,
+        };
+        HashMap::from([
+            (
+                TextureParameter::TextureWrapS,
+                value(
+                    sampler.and_then(|s| s.wrap_s),
+                    TextureParameterValue::Repeat,
+                ),
+            ),
+            (
+                TextureParameter::TextureWrapT,
+                value(
+                    sampler.and_then(|s| s.wrap_t),
+                    TextureParameterValue::Repeat,
+                ),
+            ),
+            (
+                TextureParameter::TextureMinFilter,
+                value(
+                    sampler.and_then(|s| s.min_filter),
+                    TextureParameterValue::LinearMipmapLinear,
+                ),
+            ),
+            (
+                TextureParameter::TextureMagFilter,
+                value(
+                    sampler.and_then(|s| s.mag_filter),
+                    TextureParameterValue::Linear,
+                ),
+            ),
+        ])
+    }
+
+    fn texture(
+        &self,
+        index: usize,
+        id: usize,
+        texture_type: TextureType,
+    ) -> Result<Option<TextureInfo>, MageError> {
+        let texture = self
+            .document
+            .textures
+            .get(index)
+            .ok_or(GltfError::InvalidIndex("texture", index))?;
+        let image = match texture
+            .source
+            .and_then(|source| self.document.images.get(source))
+        {
+            Some(image) => image,
+            None => return Ok(None),
+        };
+        let source = match (&image.uri, image.buffer_view) {
+            (Some(uri), _) if !uri.starts_with("data:") => {
+                TextureSource::File(self.directory.join(uri).to_string_lossy().to_string())
+            }
+            (Some(uri), _) => TextureSource::Memory(Arc::new(load_uri(uri, &self.directory)?)),
+            (None, Some(view)) => {
+                let view = self
+                    .document
+                    .buffer_views
+                    .get(view)
+                    .ok_or(GltfError::InvalidIndex("bufferView", view))?;
+                let bytes = self
+                    .buffers
+                    .get(view.buffer)
+                    .and_then(|buffer| {
+                        buffer.get(view.byte_offset..view.byte_offset + view.byte_length)
+                    })
+                    .ok_or(GltfError::InvalidIndex("buffer", view.buffer))?;
+                TextureSource::Memory(Arc::new(bytes.to_vec()))
+            }
+            (None, None) => return Ok(None),
+        };
+        Ok(Some(TextureInfo {
+            id,
+            texture_type,
+            source,
+            parameters: self.texture_parameters(texture.sampler),
+        }))
+    }
+
+    fn material(&self, index: usize) -> Result<GltfMaterial, MageError> {
+        let material = self
+            .document
+            .materials
+            .get(index)
+            .ok_or(GltfError::InvalidIndex("material", index))?;
+        let pbr = &material.pbr_metallic_roughness;
+        let [r, g, b, a] = pbr.base_color_factor.unwrap_or([1f32; 4]);
+        let references = [
+            (pbr.base_color_texture.as_ref(), TextureType::BaseColor),
+            (
+                pbr.metallic_roughness_texture.as_ref(),
+                TextureType::Metalness,
+            ),
+            (
+                pbr.metallic_roughness_texture.as_ref(),
+                TextureType::Roughness,
+            ),
+            (material.normal_texture.as_ref(), TextureType::Normals),
+        ];
+        let mut textures = vec![];
+        let mut texture_coordinates = None;
+        for (reference, texture_type) in references {
+            if let Some(reference) = reference {
+                if let Some(texture) =
+                    self.texture(reference.index, textures.len(), texture_type)?
+                {
+                    textures.push(texture);
+                    texture_coordinates.get_or_insert(reference.tex_coord);
+                }
+            }
+        }
+        if let Some(occlusion) = &material.occlusion_texture {
+            if let Some(texture) = self.texture(
+                occlusion.index,
+                textures.len(),
+                TextureType::AmbientOcclusion,
+            )? {
+                textures.push(texture);
+                texture_coordinates.get_or_insert(occlusion.tex_coord);
+            }
+        }
+        let queue = match material.alpha_mode.as_deref() {
+            Some("MASK") => {
+                RenderQueue::AlphaTest(material.alpha_cutoff.unwrap_or(DEFAULT_ALPHA_CUTOFF))
+            }
+            Some("BLEND") => RenderQueue::Transparent(BlendMode::Alpha),
+            _ => RenderQueue::Opaque,
+        };
+        Ok(GltfMaterial {
+            material: PbrMaterial {
+                albedo: Vector3::new(r, g, b),
+                ao: material
+                    .occlusion_texture
+                    .as_ref()
+                    .and_then(|occlusion| occlusion.strength)
+                    .unwrap_or(1f32),
+                metalness: pbr.metallic_factor.unwrap_or(1f32),
+                opacity: a,
+                roughness: pbr.roughness_factor.unwrap_or(1f32),
+                ..PbrMaterial::default()
+            },
+            name: material.name.clone().unwrap_or_default(),
+            queue,
+            textures,
+            texture_coordinates: texture_coordinates.unwrap_or(0),
+        })
+    }
+
+    pub(crate) fn primitives(&self, index: usize) -> Result<Vec<GltfPrimitive>, MageError> {
+        let mesh = self
+            .document
+            .meshes
+            .get(index)
+            .ok_or(GltfError::InvalidIndex("mesh", index))?;
+        let mut primitives = vec![];
+        for primitive in mesh.primitives.iter() {
+            let mode = primitive.mode.unwrap_or(MODE_TRIANGLES);
+            if !matches!(
+                mode,
+                MODE_TRIANGLES | MODE_TRIANGLE_STRIP | MODE_TRIANGLE_FAN
+            ) {
+                warn!("Skipping glTF primitive with unsupported mode {}", mode);
+                continue;
+            }
+            let attribute = |name: &str| primitive.attributes.get(name).copied();
+            let vertices =
+                self.vectors3(attribute("POSITION").ok_or(GltfError::MissingPositions)?)?;
+            let normals = attribute("NORMAL")
+                .map(|index| self.vectors3(index))
+                .transpose()?;
+            let mut texture_coordinates = vec![];
+            while let Some(index) = attribute(&format!("TEXCOORD_{}", texture_coordinates.len())) {
+                texture_coordinates.push(
+                    self.vectors(index, 2)?
+                        .chunks(2)
+                        .map(|uv| Vector2::new(uv[0], 1f32 - uv[1]))
+                        .collect::<Vec<_>>(),
+                );
+            }
+            let colors = attribute("COLOR_0")
+                .map(|index| {
+                    let (values, components) = self.accessor(index)?;
+                    if components != 3 && components != 4 {
+                        return Err(GltfError::InvalidAccessor(format!(
+                            "expected VEC3 or VEC4 at {}",
+                            index
+                        )));
+                    }
+                    Ok(values
+                        .chunks(components)
+                        .map(|c| Vector4::new(c[0], c[1], c[2], c.get(3).copied().unwrap_or(1f32)))
+                        .collect::<Vec<_>>())
+                })
+                .transpose()?;
+            let (tangents, bitangents) = match (attribute("TANGENT"), &normals) {
+                (Some(index), Some(normals)) => {
+                    let values = self.vectors(index, 4)?;
+                    let tangents = values
+                        .chunks(4)
+                        .map(|t| Vector3::new(t[0], t[1], t[2]))
+                        .collect::<Vec<_>>();
+                    let bitangents = values
+                        .chunks(4)
+                        .zip(normals.iter())
+                        .map(|(t, n)| n.cross(&Vector3::new(t[0], t[1], t[2])) * -t[3])
+                        .collect::<Vec<_>>();
+                    (Some(tangents), Some(bitangents))
+                }
+                _ => (None, None),
+            };
+            let joints = attribute("JOINTS_0")
+                .map(|index| self.vectors4(index))
+                .transpose()?
+                .map(|joints| {
+                    joints
+                        .into_iter()
+                        .map(|j| j.map(|component| component as u32))
+                        .collect::<Vec<_>>()
+                });
+            let weights = attribute("WEIGHTS_0")
+                .map(|index| self.vectors4(index))
+                .transpose()?;
+            let (joints, weights) = match (joints, weights) {
+                (Some(joints), Some(weights)) => (Some(joints), Some(weights)),
+                _ => (None, None),
+            };
+            let mut indices = match primitive.indices {
+                Some(index) => read_indices(&self.document, &self.buffers, index)?,
+                None => (0..vertices.len() as u32).collect(),
+            };
+            if mode == MODE_TRIANGLE_FAN {
+                indices = (1..indices.len().saturating_sub(1))
+                    .flat_map(|i| [indices[0], indices[i], indices[i + 1]])
+                    .collect();
+            }
+
+            let material = primitive
+                .material
+                .map(|material| {
+                    self.materials
+                        .get(material)
+                        .ok_or(GltfError::InvalidIndex("material", material))
+                })
+                .transpose()?;
+            let set = material
+                .as_ref()
+                .map(|material| material.texture_coordinates)
+                .unwrap_or(0);
+            let mut mesh = Mesh {
+                bitangents,
+                colors,
+                drawing_mode: if mode == MODE_TRIANGLE_STRIP {
+                    DrawingMode::TriangleStrip
+                } else {
+                    DrawingMode::Triangles
+                },
+                indices: Some(indices),
+                joints,
+                normals,
+                revision: next_revision(),
+                secondary_texture_coordinates: texture_coordinates
+                    .get(if set == 1 { 0 } else { 1 })
+                    .cloned(),
+                shininess: None,
+                tangents,
+                textures: material
+                    .map(|material| material.textures.clone())
+                    .filter(|textures| !textures.is_empty()),
+                texture_coordinates: texture_coordinates
+                    .get(set)
+                    .or_else(|| texture_coordinates.first())
+                    .cloned(),
+                vertices,
+                weights,
+            };
+            let has_normal_map = mesh
+                .textures
+                .iter()
+                .flatten()
+                .any(|texture| texture.texture_type == TextureType::Normals);
+            if mesh.tangents.is_none() && has_normal_map {
+                mesh.generate_tangents();
+            }
+            primitives.push(GltfPrimitive {
+                material: primitive.material,
+                mesh,
+                texture_coordinates,
+            });
+        }
+        Ok(primitives)
+    }
+
+    fn node_name(&self, index: usize) -> String {
+        self.document.nodes[index]
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("node_{}", index))
+    }
+
+    fn skin(&self, index: usize, nodes: &HashMap<usize, Entity>) -> Result<Skin, GltfError> {
+        let skin = self
+            .document
+            .skins
+            .get(index)
+            .ok_or(GltfError::InvalidIndex("skin", index))?;
+        let joints = skin
+            .joints
+            .iter()
+            .map(|joint| {
+                nodes
+                    .get(joint)
+                    .copied()
+                    .ok_or(GltfError::InvalidIndex("joint", *joint))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let inverse_bind_matrices = match skin.inverse_bind_matrices {
+            Some(accessor) => self
+                .accessor(accessor)?
+                .0
+                .chunks_exact(16)
+                .map(Matrix4::from_column_slice)
+                .collect(),
+            None => vec![Matrix4::identity(); joints.len()],
+        };
+        Ok(Skin {
+            inverse_bind_matrices,
+            joints,
+        })
+    }
+
+    fn animation(&self, index: usize) -> Result<AnimationClip, GltfError> {
+        let animation = &self.document.animations[index];
+        let mut channels = vec![];
+        for channel in animation.channels.iter() {
+            let node = match channel.target.node {
+                Some(node) if node < self.document.nodes.len() => node,
+                _ => continue,
+            };
+            let sampler = animation
+                .samplers
+                .get(channel.sampler)
+                .ok_or(GltfError::InvalidIndex("sampler", channel.sampler))?;
+            let (values, components) = self.accessor(sampler.output)?;
+            let keyframes = match (channel.target.path.as_str(), components) {
+                ("rotation", 4) => Keyframes::Rotation(
+                    values
+                        .chunks(4)
+                        .map(|q| Quaternion::new(q[3], q[0], q[1], q[2]))
+                        .collect(),
+                ),
+                ("scale", 3) => Keyframes::Scale(
+                    values
+                        .chunks(3)
+                        .map(|v| Vector3::new(v[0], v[1], v[2]))
+                        .collect(),
+                ),
+                ("translation", 3) => Keyframes::Translation(
+                    values
+                        .chunks(3)
+                        .map(|v| Vector3::new(v[0], v[1], v[2]))
+                        .collect(),
+                ),
+                (path, _) => {
+                    warn!("Skipping glTF animation channel with path {}", path);
+                    continue;
+                }
+            };
+            let interpolation = match sampler.interpolation.as_deref() {
+                Some("STEP") => Interpolation::Step,
+                Some("CUBICSPLINE") => Interpolation::CubicSpline,
+                _ => Interpolation::Linear,
+            };
+            channels.push(Channel {
+                interpolation,
+                keyframes,
+                target: self.node_name(node),
+                times: self.accessor(sampler.input)?.0,
+            });
+        }
+        let name = animation
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("animation_{}", index));
+        Ok(AnimationClip::new(&name, channels))
+    }
+
+    pub(crate) fn animations(&self) -> Result<Vec<AnimationClip>, GltfError> {
+        (0..self.document.animations.len())
+            .map(|index| self.animation(index))
+            .collect()
+    }
+
+    fn camera(&self, index: usize, transform: &Transform) -> Option<GltfCamera> {
+        let camera = self.document.cameras.get(index)?;
+        let projection = match (&camera.perspective, &camera.orthographic) {
+            (Some(p), _) => GltfProjection::Perspective {
+                aspect_ratio: p.aspect_ratio,
+                yfov: p.yfov,
+                zfar: p.zfar,
+                znear: p.znear,
+            },
+            (None, Some(o)) => GltfProjection::Orthographic {
+                xmag: o.xmag,
+                ymag: o.ymag,
+                zfar: o.zfar,
+                znear: o.znear,
+            },
+            (None, None) => return None,
+        };
+        Some(GltfCamera {
+            name: camera.name.clone().unwrap_or_default(),
+            projection,
+            transform: transform.clone(),
+        })
+    }
+
+    fn insert_light(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        index: usize,
+        transform: &Transform,
+    ) -> Result<(), MageError> {
+        let light = match self
+            .document
+            .extensions
+            .lights_punctual
+            .as_ref()
+            .and_then(|lights| lights.lights.get(index))
+        {
+            Some(light) => light,
+            None => return Ok(()),
+        };
+        let [r, g, b] = light.color.unwrap_or([1f32; 3]);
+        let color = Vector3::new(r, g, b);
+        let intensity = light.intensity.unwrap_or(1f32);
+        match light.kind.as_str() {
+            "directional" => world.insert_one(
+                entity,
+                DirectionalLight {
+                    color,
+                    direction: transform.rotation * -Vector3::z(),
+                    intensity,
+                },
+            )?,
+            kind => {
+                if kind != "point" {
+                    warn!(
+                        "Loading glTF {} light {} as a point light",
+                        kind,
+                        light.name.as_deref().unwrap_or_default()
+                    );
+                }
+                world.insert_one(entity, PointLight { color, intensity })?
+            }
+        }
+        Ok(())
+    }
+}
+
+fn collider(mesh: &Mesh, mode: GltfColliders) -> Option<Collider> {
+    let points = mesh
+        .vertices
+        .iter()
+        .map(|v| Point3::from(*v))
+        .collect::<Vec<_>>();
+    match mode {
+        GltfColliders::ConvexHull => ColliderBuilder::convex_hull(&points).map(|b| b.build()),
+        GltfColliders::TriMesh => {
+            let indices = mesh
+                .triangles()
+                .into_iter()
+                .map(|[a, b, c]| [a as u32, b as u32, c as u32])
+                .collect::<Vec<_>>();
+            (!indices.is_empty()).then(|| ColliderBuilder::trimesh(points, indices).build())
+        }
+        GltfColliders::None => None,
+    }
+}
+
+pub struct GltfLoader {
+    pub colliders: GltfColliders,
+    pub scene: Option<usize>,
+}
+
+impl GltfLoader {
+    pub fn new() -> GltfLoader {
+        GltfLoader {
+            colliders: GltfColliders::None,
+            scene: None,
+        }
+    }
+
+    pub fn load_meshes(&self, path: &str) -> Result<Vec<GltfPrimitive>, MageError> {
+        let data = GltfData::open(path)?;
+        let mut primitives = vec![];
+        for index in 0..data.document.meshes.len() {
+            primitives.extend(data.primitives(index)?);
+        }
+        Ok(primitives)
+    }
+
+    pub fn load_animations(&self, path: &str) -> Result<Vec<AnimationClip>, MageError> {
+        Ok(GltfData::open(path)?.animations()?)
+    }
+
+    pub fn load(
+        &self,
+        path: &str,
+        world: &mut World,
+        transform: &Transform,
+    ) -> Result<GltfModel, MageError> {
+        let data = GltfData::open(path)?;
+        let scene_index = self.scene.or(data.document.scene).unwrap_or(0);
+        let (name, nodes) = match data.document.scenes.get(scene_index) {
+            Some(scene) => (scene.name.clone(), scene.nodes.clone()),
+            None => (None, (0..data.document.nodes.len()).collect()),
+        };
+        let name = name.unwrap_or_else(|| {
+            Path::new(path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        let root = world.spawn((transform.clone(), Name(name), Children::default()));
+        let mut context = SpawnContext {
+            meshes: HashMap::new(),
+            model: GltfModel {
+                animations: vec![],
+                cameras: vec![],
+                entities: vec![root],
+                root,
+            },
+            nodes: HashMap::new(),
+            skinned: vec![],
+        };
+        for node in nodes {
+            self.spawn_node(&data, world, node, root, transform, &mut context)?;
+        }
+        for (entity, skin) in context.skinned.iter() {
+            world.insert_one(*entity, data.skin(*skin, &context.nodes)?)?;
+        }
+        let animations = data
+            .animations()?
+            .into_iter()
+            .map(Arc::new)
+            .collect::<Vec<_>>();
+        if !animations.is_empty() {
+            let mut player = AnimationPlayer::new();
+            for animation in animations.iter() {
+                player.add_shared_clip(animation.clone());
+            }
+            world.insert_one(root, player)?;
+        }
+        context.model.animations = animations;
+        Ok(context.model)
+    }
+
+    fn spawn_node(
+        &self,
+        data: &GltfData,
+        world: &mut World,
+        index: usize,
+        parent: Entity,
+        parent_transform: &Transform,
+        context: &mut SpawnContext,
+    ) -> Result<(), MageError> {
+        let node = data
+            .document
+            .nodes
+            .get(index)
+            .ok_or(GltfError::InvalidIndex("node", index))?;
+        let local = data.node_transform(index);
+        let global = parent_transform.combine(&local);
+        let entity = world.spawn((
+            global.clone(),
+            Name(data.node_name(index)),
+            Parent {
+                entity: parent,
+                local,
+            },
+            Children::default(),
+        ));
+        context.model.entities.push(entity);
+        context.nodes.insert(index, entity);
+        world.get_mut::<Children>(parent)?.0.push(entity);
+
+        if let Some(camera) = node.camera.and_then(|camera| data.camera(camera, &global)) {
+            context.model.cameras.push(camera);
+        }
+        if let Some(light) = &node.extensions.lights_punctual {
+            data.insert_light(world, entity, light.light, &global)?;
+        }
+        if let Some(mesh) = node.mesh {
+            if let Entry::Vacant(entry) = context.meshes.entry(mesh) {
+                entry.insert(data.primitives(mesh)?);
+            }
+            for primitive in context.meshes[&mesh].iter() {
+                let material = primitive
+                    .material
+                    .and_then(|material| data.materials.get(material));
+                let mesh_entity = world.spawn((
+                    primitive.mesh.clone(),
+                    global.clone(),
+                    Parent {
+                        entity,
+                        local: Transform::identity(),
+                    },
+                ));
+                if let Some(material) = material {
+                    world.insert(mesh_entity, (material.material.clone(), material.queue))?;
+                }
+                if let Some(collider) = collider(&primitive.mesh, self.colliders) {
+                    world.insert_one(mesh_entity, PendingColliders(vec![collider]))?;
+                }
+                if let (Some(skin), true) = (node.skin, primitive.mesh.is_skinned()) {
+                    context.skinned.push((mesh_entity, skin));
+                }
+                context.model.entities.push(mesh_entity);
+                world.get_mut::<Children>(entity)?.0.push(mesh_entity);
+            }
+        }
+
+        for child in node.children.iter() {
+            self.spawn_node(data, world, *child, entity, &global, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for GltfLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{GltfData, GltfError};
+
+    #[test]
+    fn vectors_reject_mismatched_component_counts() {
+        let document = serde_json::from_str(
+            r#"{
+                "accessors": [{"bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC2"}],
+                "bufferViews": [{"buffer": 0, "byteLength": 16}]
+            }"#,
+        )
+        .unwrap();
+        let data = GltfData {
+            buffers: vec![vec![0u8; 16]],
+            directory: PathBuf::new(),
+            document,
+            materials: vec![],
+        };
+        assert!(matches!(
+            data.vectors3(0),
+            Err(GltfError::InvalidAccessor(_))
+        ));
+        assert!(matches!(
+            data.vectors4(0),
+            Err(GltfError::InvalidAccessor(_))
+        ));
+        assert_eq!(data.vectors(0, 2).unwrap().len(), 4);
+    }
+}
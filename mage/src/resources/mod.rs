@@ -1,2 +1,4 @@
+pub mod gltf;
+pub mod model;
 pub mod shader;
 pub mod texture;
@@ -0,0 +1,408 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use hecs::{Entity, World};
+use log::warn;
+use nalgebra::{Matrix4, Quaternion, Vector2, Vector3, Vector4};
+use russimp::animation::Animation;
+use russimp::material::{Material, PropertyTypeInfo};
+use russimp::mesh::Mesh as AssimpMesh;
+use russimp::node::Node;
+use russimp::scene::{PostProcess, PostProcessSteps, Scene};
+use russimp::texture::TextureType;
+use russimp::{Matrix4x4, Vector3D};
+use thiserror::Error;
+
+use crate::animation::{AnimationClip, AnimationPlayer, Channel, Interpolation, Keyframes, Skin};
+use crate::rendering::hierarchy::{Children, Name, Parent};
+use crate::rendering::model::mesh::{next_revision, Mesh, TextureInfo, TextureSource};
+use crate::rendering::opengl::texture::{TextureParameter, TextureParameterValue};
+use crate::rendering::opengl::DrawingMode;
+use crate::rendering::Transform;
+use crate::MageError;
+
+const SHININESS_KEY: &str = "$mat.shininess";
+const DEFAULT_TICKS_PER_SECOND: f64 = 25.0;
+const SUPPORTED_TEXTURES: [TextureType; 8] = [
+    TextureType::Diffuse,
+    TextureType::BaseColor,
+    TextureType::Specular,
+    TextureType::Normals,
+    TextureType::Height,
+    TextureType::Metalness,
+    TextureType::Roughness,
+    TextureType::AmbientOcclusion,
+];
+
+#[derive(Debug, Error)]
+pub enum ModelLoaderError {
+    #[error("Model {0} has no root node")]
+    MissingRoot(String),
+}
+
+fn vector(v: &Vector3D) -> Vector3<f32> {
+    Vector3::new(v.x, v.y, v.z)
+}
+
+fn optional_vectors(vectors: &[Vector3D]) -> Option<Vec<Vector3<f32>>> {
+    (!vectors.is_empty()).then(|| vectors.iter().map(vector).collect())
+}
+
+fn matrix(m: &Matrix4x4) -> Matrix4<f32> {
+    Matrix4::new(
+        m.a1, m.a2, m.a3, m.a4, m.b1, m.b2, m.b3, m.b4, m.c1, m.c2, m.c3, m.c4, m.d1, m.d2, m.d3,
+        m.d4,
+    )
+}
+
+fn skinning(mesh: &AssimpMesh) -> (Vec<Vector4<u32>>, Vec<Vector4<f32>>) {
+    let mut influences = vec![vec![]; mesh.vertices.len()];
+    for (bone, data) in mesh.bones.iter().enumerate() {
+        for weight in data.weights.iter() {
+            if let Some(influences) = influences.get_mut(weight.vertex_id as usize) {
+                influences.push((bone as u32, weight.weight));
+            }
+        }
+    }
+    let mut joints = Vec::with_capacity(influences.len());
+    let mut weights = Vec::with_capacity(influences.len());
+    for mut influences in influences {
+        influences.sort_by(|a, b| b.1.total_cmp(&a.1));
+        influences.truncate(4);
+        let mut joint = Vector4::zeros();
+        let mut weight = Vector4::zeros();
+        for (i, (bone, value)) in influences.into_iter().enumerate() {
+            joint[i] = bone;
+            weight[i] = value;
+        }
+        let total = weight.sum();
+        if total > 0f32 {
+            weight /= total;
+        }
+        joints.push(joint);
+        weights.push(weight);
+    }
+    (joints, weights)
+}
+
+fn animation(animation: &Animation) -> AnimationClip {
+    let ticks_per_second = if animation.ticks_per_second > 0.0 {
+        animation.ticks_per_second
+    } else {
+        DEFAULT_TICKS_PER_SECOND
+    };
+    let seconds = |time: f64| (time / ticks_per_second) as f32;
+    let mut channels = vec![];
+    for node in animation.channels.iter() {
+        let channel = |times: Vec<f32>, keyframes: Keyframes| Channel {
+            interpolation: Interpolation::Linear,
+            keyframes,
+            target: node.name.clone(),
+            times,
+        };
+        if !node.position_keys.is_empty() {
+            channels.push(channel(
+                node.position_keys.iter().map(|k| seconds(k.time)).collect(),
+                Keyframes::Translation(
+                    node.position_keys
+                        .iter()
+                        .map(|k| vector(&k.value))
+                        .collect(),
+                ),
+            ));
+        }
+        if !node.rotation_keys.is_empty() {
+            channels.push(channel(
+                node.rotation_keys.iter().map(|k| seconds(k.time)).collect(),
+                Keyframes::Rotation(
+                    node.rotation_keys
+                        .iter()
+                        .map(|k| Quaternion::new(k.value.w, k.value.x, k.value.y, k.value.z))
+                        .collect(),
+                ),
+            ));
+        }
+        if !node.scaling_keys.is_empty() {
+            channels.push(channel(
+                node.scaling_keys.iter().map(|k| seconds(k.time)).collect(),
+                Keyframes::Scale(node.scaling_keys.iter().map(|k| vector(&k.value)).collect()),
+            ));
+        }
+    }
+    AnimationClip::new(&animation.name, channels)
+}
+
+fn shininess(material: &Material) -> Option<f32> {
+    material
+        .properties
+        .iter()
+        .find_map(|property| match (&property.data, property.key.as_str()) {
+            (PropertyTypeInfo::FloatArray(values), SHININESS_KEY) => values.first().copied(),
+            _ => None,
+        })
+        .filter(|shininess| *shininess > 0f32)
+}
+
+fn texture_parameters() -> HashMap<TextureParameter, TextureParameterValue> {
+    HashMap::from([
+        (
+            TextureParameter::TextureWrapS,
+            TextureParameterValue::Repeat,
+        ),
+        (
+            TextureParameter::TextureWrapT,
+            TextureParameterValue::Repeat,
+        ),
+        (
+            TextureParameter::TextureMinFilter,
+            TextureParameterValue::LinearMipmapLinear,
+        ),
+        (
+            TextureParameter::TextureMagFilter,
+            TextureParameterValue::Linear,
+        ),
+    ])
+}
+
+fn textures(material: &Material, directory: &Path) -> Vec<TextureInfo> {
+    let mut textures = vec![];
+    for texture_type in SUPPORTED_TEXTURES {
+        let texture = match material
+            .textures
+            .get(&texture_type)
+            .and_then(|textures| textures.first())
+        {
+            Some(texture) => texture,
+            None => continue,
+        };
+        if texture.path.starts_with('*') {
+            warn!("Skipping embedded texture {}", texture.path);
+            continue;
+        }
+        let path = directory.join(texture.path.replace('\\', "/"));
+        textures.push(TextureInfo {
+            id: textures.len(),
+            texture_type,
+            source: TextureSource::File(path.to_string_lossy().to_string()),
+            parameters: texture_parameters(),
+        });
+    }
+    textures
+}
+
+pub struct Model {
+    pub animations: Vec<Arc<AnimationClip>>,
+    pub entities: Vec<Entity>,
+    pub root: Entity,
+}
+
+struct SpawnContext {
+    entities: Vec<Entity>,
+    names: HashMap<String, Entity>,
+    skinned: Vec<(Entity, usize)>,
+}
+
+pub struct ModelLoader {
+    pub post_process: PostProcessSteps,
+}
+
+impl ModelLoader {
+    pub fn new() -> ModelLoader {
+        ModelLoader {
+            post_process: vec![
+                PostProcess::Triangulate,
+                PostProcess::GenerateSmoothNormals,
+                PostProcess::CalculateTangentSpace,
+                PostProcess::JoinIdenticalVertices,
+                PostProcess::SortByPrimitiveType,
+                PostProcess::LimitBoneWeights,
+            ],
+        }
+    }
+
+    pub fn load_meshes(&self, path: &str) -> Result<Vec<Mesh>, MageError> {
+        let scene = Scene::from_file(path, self.post_process.clone())?;
+        let directory = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+        Ok(scene
+            .meshes
+            .iter()
+            .map(|mesh| convert_mesh(&scene, mesh, directory))
+            .collect())
+    }
+
+    pub fn load_animations(&self, path: &str) -> Result<Vec<AnimationClip>, MageError> {
+        let scene = Scene::from_file(path, self.post_process.clone())?;
+        Ok(scene.animations.iter().map(animation).collect())
+    }
+
+    pub fn load(
+        &self,
+        path: &str,
+        world: &mut World,
+        transform: &Transform,
+    ) -> Result<Model, MageError> {
+        let scene = Scene::from_file(path, self.post_process.clone())?;
+        let directory = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+        let meshes = scene
+            .meshes
+            .iter()
+            .map(|mesh| convert_mesh(&scene, mesh, directory))
+            .collect::<Vec<_>>();
+        let root_node = scene
+            .root
+            .clone()
+            .ok_or_else(|| ModelLoaderError::MissingRoot(path.to_owned()))?;
+        let name = Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let root = world.spawn((transform.clone(), Name(name), Children::default()));
+        let mut context = SpawnContext {
+            entities: vec![root],
+            names: HashMap::new(),
+            skinned: vec![],
+        };
+        spawn_node(world, &root_node, &meshes, root, transform, &mut context)?;
+        for (entity, index) in context.skinned.iter() {
+            let bones = &scene.meshes[*index].bones;
+            let joints = bones
+                .iter()
+                .map(|bone| {
+                    context.names.get(&bone.name).copied().unwrap_or_else(|| {
+                        warn!("Bone {} has no matching node", bone.name);
+                        root
+                    })
+                })
+                .collect();
+            let inverse_bind_matrices = bones
+                .iter()
+                .map(|bone| matrix(&bone.offset_matrix))
+                .collect();
+            world.insert_one(
+                *entity,
+                Skin {
+                    inverse_bind_matrices,
+                    joints,
+                },
+            )?;
+        }
+        let animations = scene
+            .animations
+            .iter()
+            .map(|clip| Arc::new(animation(clip)))
+            .collect::<Vec<_>>();
+        if !animations.is_empty() {
+            let mut player = AnimationPlayer::new();
+            for animation in animations.iter() {
+                player.add_shared_clip(animation.clone());
+            }
+            world.insert_one(root, player)?;
+        }
+        Ok(Model {
+            animations,
+            entities: context.entities,
+            root,
+        })
+    }
+}
+
+impl Default for ModelLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn convert_mesh(scene: &Scene, mesh: &AssimpMesh, directory: &Path) -> Mesh {
+    let material = scene.materials.get(mesh.material_index as usize);
+    let texture_coordinates = |set: usize| {
+        mesh.texture_coords
+            .get(set)
+            .and_then(|coordinates| coordinates.as_ref())
+            .map(|coordinates| {
+                coordinates
+                    .iter()
+                    .map(|c| Vector2::new(c.x, c.y))
+                    .collect::<Vec<_>>()
+            })
+    };
+    let indices = mesh
+        .faces
+        .iter()
+        .filter(|face| face.0.len() == 3)
+        .flat_map(|face| face.0.iter().copied())
+        .collect::<Vec<_>>();
+    let textures = material
+        .map(|material| textures(material, directory))
+        .filter(|textures| !textures.is_empty());
+    let (joints, weights) = (!mesh.bones.is_empty()).then(|| skinning(mesh)).unzip();
+    Mesh {
+        bitangents: optional_vectors(&mesh.bitangents),
+        colors: None,
+        drawing_mode: DrawingMode::Triangles,
+        indices: Some(indices),
+        joints,
+        normals: optional_vectors(&mesh.normals),
+        revision: next_revision(),
+        secondary_texture_coordinates: texture_coordinates(1),
+        shininess: material.and_then(shininess),
+        tangents: optional_vectors(&mesh.tangents),
+        textures,
+        texture_coordinates: texture_coordinates(0),
+        vertices: mesh.vertices.iter().map(vector).collect(),
+        weights,
+    }
+}
+
+fn spawn_node(
+    world: &mut World,
+    node: &Rc<RefCell<Node>>,
+    meshes: &[Mesh],
+    parent: Entity,
+    parent_transform: &Transform,
+    context: &mut SpawnContext,
+) -> Result<(), MageError> {
+    let node = node.borrow();
+    let local = Transform::from_matrix(&matrix(&node.transformation));
+    let global = parent_transform.combine(&local);
+    let entity = world.spawn((
+        global.clone(),
+        Name(node.name.clone()),
+        Parent {
+            entity: parent,
+            local,
+        },
+        Children::default(),
+    ));
+    context.entities.push(entity);
+    context.names.entry(node.name.clone()).or_insert(entity);
+    world.get_mut::<Children>(parent)?.0.push(entity);
+
+    for index in node.meshes.iter() {
+        let mesh = match meshes.get(*index as usize) {
+            Some(mesh) => mesh.clone(),
+            None => continue,
+        };
+        let skinned = mesh.is_skinned();
+        let mesh_entity = world.spawn((
+            mesh,
+            global.clone(),
+            Parent {
+                entity,
+                local: Transform::identity(),
+            },
+        ));
+        if skinned {
+            context.skinned.push((mesh_entity, *index as usize));
+        }
+        context.entities.push(mesh_entity);
+        world.get_mut::<Children>(entity)?.0.push(mesh_entity);
+    }
+
+    for child in node.children.iter() {
+        spawn_node(world, child, meshes, entity, &global, context)?;
+    }
+    Ok(())
+}
@@ -1,13 +1,106 @@
 use std::collections::HashMap;
+use std::io::Cursor;
 use std::sync::Arc;
 
 use image::io::Reader;
-use image::{EncodableLayout, Rgb, RgbImage};
+use image::{DynamicImage, EncodableLayout, Rgb, RgbImage, Rgba32FImage};
+use nalgebra::Vector3;
+use num_traits::FloatConst;
+use thiserror::Error;
 
 use crate::rendering::model::mesh::{TextureInfo, TextureSource};
-use crate::rendering::opengl::texture::{Texture, TextureDimension, TextureFormat};
+use crate::rendering::opengl::texture::{
+    Texture, TextureDimension, TextureFormat, TextureParameter, TextureParameterValue,
+};
 use crate::MageError;
 
+const HORIZONTAL_CROSS_FACES: [(u32, u32); 6] = [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (3, 1)];
+const VERTICAL_CROSS_FACES: [(u32, u32); 6] = [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (1, 3)];
+
+#[derive(Debug, Error)]
+pub enum TextureLoaderError {
+    #[error("Unsupported texture source {0:?}")]
+    UnsupportedSource(TextureSource),
+    #[error("Invalid cube map cross of size {0}x{1}")]
+    InvalidCross(u32, u32),
+}
+
+fn face_direction(face: u32, x: u32, y: u32, size: u32) -> Vector3<f32> {
+    let u = 2f32 * (x as f32 + 0.5) / size as f32 - 1f32;
+    let v = 2f32 * (y as f32 + 0.5) / size as f32 - 1f32;
+    let direction = match face {
+        0 => Vector3::new(1f32, -v, -u),
+        1 => Vector3::new(-1f32, -v, u),
+        2 => Vector3::new(u, 1f32, v),
+        3 => Vector3::new(u, -1f32, -v),
+        4 => Vector3::new(u, -v, 1f32),
+        _ => Vector3::new(-u, -v, -1f32),
+    };
+    direction.normalize()
+}
+
+fn equirectangular_face(image: &Rgba32FImage, face: u32, size: u32) -> Vec<f32> {
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let direction = face_direction(face, x, y, size);
+            let s = direction.z.atan2(direction.x) / (2f32 * f32::PI()) + 0.5;
+            let t = 0.5 - direction.y.asin() / f32::PI();
+            let pixel = image.get_pixel(
+                ((s * image.width() as f32) as u32).min(image.width() - 1),
+                ((t * image.height() as f32) as u32).min(image.height() - 1),
+            );
+            data.extend(pixel.0);
+        }
+    }
+    data
+}
+
+fn set_image_2d(texture: &Texture, image: DynamicImage) {
+    match TextureFormat::try_from(image.color()) {
+        Ok(format) => {
+            texture.set_image_2d(
+                image.width() as u32,
+                image.height() as u32,
+                image.as_bytes(),
+                format,
+            );
+        }
+        Err(_) => {
+            let image = image.to_rgba8();
+            texture.set_image_2d(
+                image.width() as u32,
+                image.height() as u32,
+                image.as_bytes(),
+                TextureFormat::UnsignedByteWithAlpha,
+            );
+        }
+    };
+}
+
+fn cross_faces(image: &DynamicImage) -> Result<Vec<DynamicImage>, MageError> {
+    let (width, height) = (image.width(), image.height());
+    let (faces, size) = if width / 4 == height / 3 && width % 4 == 0 {
+        (HORIZONTAL_CROSS_FACES, width / 4)
+    } else if width / 3 == height / 4 && width % 3 == 0 {
+        (VERTICAL_CROSS_FACES, width / 3)
+    } else {
+        return Err(TextureLoaderError::InvalidCross(width, height).into());
+    };
+    Ok(faces
+        .iter()
+        .enumerate()
+        .map(|(face, (x, y))| {
+            let face_image = image.crop_imm(x * size, y * size, size, size);
+            if face == 5 && faces == VERTICAL_CROSS_FACES {
+                face_image.rotate180()
+            } else {
+                face_image
+            }
+        })
+        .collect())
+}
+
 pub struct TextureLoader {
     textures: HashMap<TextureSource, Arc<Texture>>,
 }
@@ -20,12 +113,78 @@ impl TextureLoader {
         }
     }
 
-    pub fn load_texture_cubemap(&mut self, texture_info: &TextureInfo) -> Arc<Texture> {
+    pub fn load_texture_cubemap(
+        &mut self,
+        texture_info: &TextureInfo,
+    ) -> Result<Arc<Texture>, MageError> {
         if let Some(source) = self.textures.get(&texture_info.source) {
-            source.clone()
-        } else {
-            todo!()
+            return Ok(source.clone());
+        }
+        let texture = Arc::new(Texture::new(TextureDimension::CubeMap));
+        texture.bind(texture_info.id as _);
+        match &texture_info.source {
+            TextureSource::CubeMap(paths) => {
+                for (face, path) in paths.iter().enumerate() {
+                    let image = Reader::open(path)?.decode()?.to_rgba8();
+                    texture.set_cube_map_face(
+                        face as u32,
+                        image.width() as usize,
+                        image.height() as usize,
+                        image.as_bytes(),
+                    );
+                }
+            }
+            TextureSource::CubeMapCross(path) => {
+                let image = Reader::open(path)?.decode()?;
+                for (face, face_image) in cross_faces(&image)?.into_iter().enumerate() {
+                    let face_image = face_image.to_rgba8();
+                    texture.set_cube_map_face(
+                        face as u32,
+                        face_image.width() as usize,
+                        face_image.height() as usize,
+                        face_image.as_bytes(),
+                    );
+                }
+            }
+            TextureSource::Equirectangular(path) => {
+                let image = Reader::open(path)?.decode()?.to_rgba32f();
+                let size = (image.width() / 4).max(1);
+                for face in 0..6 {
+                    texture.set_cube_map_face_with_format(
+                        face,
+                        size,
+                        size,
+                        &equirectangular_face(&image, face, size),
+                        TextureFormat::FloatingPoint,
+                    );
+                }
+            }
+            source => {
+                return Err(TextureLoaderError::UnsupportedSource(source.clone()).into());
+            }
         }
+        for parameter in [
+            TextureParameter::TextureWrapS,
+            TextureParameter::TextureWrapT,
+            TextureParameter::TextureWrapR,
+        ] {
+            texture.set_parameter(parameter, TextureParameterValue::ClampToEdge);
+        }
+        texture.set_parameter(
+            TextureParameter::TextureMinFilter,
+            TextureParameterValue::Linear,
+        );
+        texture.set_parameter(
+            TextureParameter::TextureMagFilter,
+            TextureParameterValue::Linear,
+        );
+        for (&k, &v) in &texture_info.parameters {
+            texture.set_parameter(k, v);
+        }
+        texture.generate_mipmap();
+        self.textures
+            .insert(texture_info.source.clone(), texture.clone());
+        Ok(texture)
     }
 
     pub fn load_texture_2d(
@@ -39,26 +198,13 @@ impl TextureLoader {
             texture.bind(texture_info.id as _);
             match &texture_info.source {
                 TextureSource::File(path) => {
-                    let image = Reader::open(path)?.decode()?.flipv();
-                    match TextureFormat::try_from(image.color()) {
-                        Ok(format) => {
-                            texture.set_image_2d(
-                                image.width() as u32,
-                                image.height() as u32,
-                                image.as_bytes(),
-                                format,
-                            );
-                        }
-                        Err(_) => {
-                            let image = image.to_rgba8();
-                            texture.set_image_2d(
-                                image.width() as u32,
-                                image.height() as u32,
-                                image.as_bytes(),
-                                TextureFormat::UnsignedByteWithAlpha,
-                            );
-                        }
-                    };
+                    set_image_2d(&texture, Reader::open(path)?.decode()?.flipv());
+                }
+                TextureSource::Memory(bytes) => {
+                    let image = Reader::new(Cursor::new(bytes.as_slice()))
+                        .with_guessed_format()?
+                        .decode()?;
+                    set_image_2d(&texture, image.flipv());
                 }
                 TextureSource::Color(color) => {
                     let mut image = RgbImage::new(1, 1);
@@ -70,6 +216,9 @@ impl TextureLoader {
                         TextureFormat::UnsignedByte,
                     );
                 }
+                source => {
+                    return Err(TextureLoaderError::UnsupportedSource(source.clone()).into());
+                }
             };
             self.textures
                 .insert(texture_info.source.clone(), texture.clone());
@@ -0,0 +1,174 @@
+use nalgebra::{DMatrix, Vector2, Vector3};
+use rapier3d::geometry::{Collider, ColliderBuilder};
+
+use crate::rendering::model::mesh::{next_revision, Mesh};
+use crate::rendering::opengl::DrawingMode;
+use crate::terrain::Terrain;
+
+fn steps(from: u32, to: u32, step: u32) -> Vec<u32> {
+    let mut samples = (from..to).step_by(step as usize).collect::<Vec<_>>();
+    samples.push(to);
+    samples
+}
+
+// Moves a sample on the border of the chunk onto the edge drawn by a
+// neighbour with a coarser step, so both chunks share the same silhouette.
+fn stitched_height(
+    terrain: &Terrain,
+    fixed: u32,
+    along: u32,
+    edge: (u32, u32),
+    step: u32,
+    x_axis: bool,
+) -> f32 {
+    let height = |along: u32| {
+        if x_axis {
+            terrain.heightmap().height(along, fixed)
+        } else {
+            terrain.heightmap().height(fixed, along)
+        }
+    };
+    let (from, to) = edge;
+    let start = from + (along - from) / step * step;
+    let end = (start + step).min(to);
+    if start == along || end == start {
+        return height(along);
+    }
+    let t = (along - start) as f32 / (end - start) as f32;
+    height(start) + (height(end) - height(start)) * t
+}
+
+// `neighbours` holds the level of detail of the chunks to the left, right,
+// near and far sides, in that order.
+pub(crate) fn chunk_mesh(
+    terrain: &Terrain,
+    chunk_x: u32,
+    chunk_z: u32,
+    lod: u32,
+    neighbours: [Option<u32>; 4],
+) -> Mesh {
+    let ((from_x, to_x), (from_z, to_z)) = terrain.chunk_samples(chunk_x, chunk_z);
+    let step = 1 << lod;
+    let xs = steps(from_x, to_x, step);
+    let zs = steps(from_z, to_z, step);
+    let heightmap = terrain.heightmap();
+    let size = terrain.size();
+    let coarser = |neighbour: Option<u32>| neighbour.filter(|&n| n > lod).map(|n| 1 << n);
+    let [left, right, near, far] = neighbours.map(coarser);
+
+    let mut vertices = Vec::with_capacity(xs.len() * zs.len());
+    let mut normals = Vec::with_capacity(xs.len() * zs.len());
+    let mut texture_coordinates = Vec::with_capacity(xs.len() * zs.len());
+    for &z in zs.iter() {
+        for &x in xs.iter() {
+            let mut height = heightmap.height(x, z);
+            let edges = [
+                (x == from_x, left, z, false),
+                (x == to_x, right, z, false),
+                (z == from_z, near, x, true),
+                (z == to_z, far, x, true),
+            ];
+            for (on_edge, neighbour_step, along, x_axis) in edges {
+                if let (true, Some(neighbour_step)) = (on_edge, neighbour_step) {
+                    let (fixed, edge) = if x_axis {
+                        (z, (from_x, to_x))
+                    } else {
+                        (x, (from_z, to_z))
+                    };
+                    height = stitched_height(terrain, fixed, along, edge, neighbour_step, x_axis);
+                }
+            }
+            let position = terrain.sample_to_local(x, z);
+            vertices.push(Vector3::new(position.x, height * size.y, position.z));
+            normals.push(terrain.sample_normal(x, z));
+            texture_coordinates.push(Vector2::new(
+                x as f32 / (heightmap.width() - 1) as f32,
+                1f32 - z as f32 / (heightmap.depth() - 1) as f32,
+            ));
+        }
+    }
+
+    let columns = xs.len() as u32;
+    let mut indices = Vec::with_capacity((xs.len() - 1) * (zs.len() - 1) * 6);
+    for row in 0..zs.len() as u32 - 1 {
+        for column in 0..columns - 1 {
+            let near_left = row * columns + column;
+            let near_right = near_left + 1;
+            let far_left = near_left + columns;
+            let far_right = far_left + 1;
+            indices.extend([near_left, far_left, near_right]);
+            indices.extend([far_left, far_right, near_right]);
+        }
+    }
+
+    let mut mesh = Mesh {
+        bitangents: None,
+        colors: None,
+        drawing_mode: DrawingMode::Triangles,
+        indices: Some(indices),
+        joints: None,
+        normals: Some(normals),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
+        shininess: None,
+        tangents: None,
+        textures: (!terrain.textures.is_empty()).then(|| terrain.textures.clone()),
+        texture_coordinates: Some(texture_coordinates),
+        vertices,
+        weights: None,
+    };
+    mesh.generate_tangents();
+    mesh
+}
+
+pub(crate) fn terrain_collider(terrain: &Terrain) -> Collider {
+    let heightmap = terrain.heightmap();
+    let heights = DMatrix::from_fn(
+        heightmap.depth() as usize,
+        heightmap.width() as usize,
+        |z, x| heightmap.height(x as u32, z as u32),
+    );
+    ColliderBuilder::heightfield(heights, terrain.size()).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+
+    use super::chunk_mesh;
+    use crate::terrain::{Heightmap, Terrain};
+
+    fn edge(vertices: &[Vector3<f32>], x: f32) -> Vec<Vector3<f32>> {
+        let mut edge = vertices
+            .iter()
+            .filter(|vertex| (vertex.x - x).abs() < 1e-5)
+            .copied()
+            .collect::<Vec<_>>();
+        edge.sort_by(|a, b| a.z.total_cmp(&b.z));
+        edge
+    }
+
+    #[test]
+    fn finer_chunks_follow_the_edge_of_coarser_neighbours() {
+        let heightmap =
+            Heightmap::from_fn(9, 5, |u, v| ((u * 7f32 + v * 13f32).sin() + 1f32) / 2f32).unwrap();
+        let terrain = Terrain::new_with_chunk_size(heightmap, Vector3::new(8f32, 1f32, 4f32), 4);
+        let fine = chunk_mesh(&terrain, 0, 0, 0, [None, Some(1), None, None]);
+        let coarse = chunk_mesh(&terrain, 1, 0, 1, [Some(0), None, None, None]);
+        let seam = terrain.sample_to_local(4, 0).x;
+        let fine_edge = edge(&fine.vertices, seam);
+        let coarse_edge = edge(&coarse.vertices, seam);
+        assert_eq!(fine_edge.len(), 5);
+        assert_eq!(coarse_edge.len(), 3);
+        for (i, vertex) in fine_edge.iter().enumerate() {
+            let (a, b) = (coarse_edge[i / 2], coarse_edge[i.div_ceil(2)]);
+            let expected = (a.y + b.y) / 2f32;
+            assert!(
+                (vertex.y - expected).abs() < 1e-5,
+                "{} != {}",
+                vertex.y,
+                expected
+            );
+        }
+    }
+}
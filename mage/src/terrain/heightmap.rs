@@ -0,0 +1,89 @@
+use image::io::Reader;
+
+use crate::terrain::noise::FractalNoise;
+use crate::terrain::TerrainError;
+use crate::MageError;
+
+#[derive(Clone, Debug)]
+pub struct Heightmap {
+    depth: u32,
+    heights: Vec<f32>,
+    width: u32,
+}
+
+impl Heightmap {
+    pub fn new(width: u32, depth: u32) -> Result<Heightmap, TerrainError> {
+        if width < 2 || depth < 2 {
+            return Err(TerrainError::HeightmapTooSmall(width, depth));
+        }
+        Ok(Heightmap {
+            depth,
+            heights: vec![0f32; (width * depth) as usize],
+            width,
+        })
+    }
+
+    pub fn from_fn<F: Fn(f32, f32) -> f32>(
+        width: u32,
+        depth: u32,
+        height: F,
+    ) -> Result<Heightmap, TerrainError> {
+        let mut heightmap = Heightmap::new(width, depth)?;
+        for z in 0..depth {
+            for x in 0..width {
+                let u = x as f32 / (width - 1) as f32;
+                let v = z as f32 / (depth - 1) as f32;
+                heightmap.set_height(x, z, height(u, v));
+            }
+        }
+        Ok(heightmap)
+    }
+
+    pub fn from_noise(
+        width: u32,
+        depth: u32,
+        noise: &FractalNoise,
+    ) -> Result<Heightmap, TerrainError> {
+        Heightmap::from_fn(width, depth, |u, v| noise.sample(u, v))
+    }
+
+    pub fn from_image(path: &str) -> Result<Heightmap, MageError> {
+        let image = Reader::open(path)?.decode()?.into_luma16();
+        let mut heightmap = Heightmap::new(image.width(), image.height())?;
+        for (x, z, pixel) in image.enumerate_pixels() {
+            heightmap.set_height(x, z, pixel.0[0] as f32 / u16::MAX as f32);
+        }
+        Ok(heightmap)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn height(&self, x: u32, z: u32) -> f32 {
+        let x = x.min(self.width - 1);
+        let z = z.min(self.depth - 1);
+        self.heights[(z * self.width + x) as usize]
+    }
+
+    pub fn set_height(&mut self, x: u32, z: u32, height: f32) {
+        if x < self.width && z < self.depth {
+            self.heights[(z * self.width + x) as usize] = height.clamp(0f32, 1f32);
+        }
+    }
+
+    pub fn sample(&self, u: f32, v: f32) -> f32 {
+        let x = u.clamp(0f32, 1f32) * (self.width - 1) as f32;
+        let z = v.clamp(0f32, 1f32) * (self.depth - 1) as f32;
+        let (x0, z0) = (x.floor() as u32, z.floor() as u32);
+        let (tx, tz) = (x - x0 as f32, z - z0 as f32);
+        let near = self.height(x0, z0) + (self.height(x0 + 1, z0) - self.height(x0, z0)) * tx;
+        let far =
+            self.height(x0, z0 + 1) + (self.height(x0 + 1, z0 + 1) - self.height(x0, z0 + 1)) * tx;
+        near + (far - near) * tz
+    }
+}
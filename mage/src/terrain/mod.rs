@@ -0,0 +1,288 @@
+use std::collections::{HashMap, HashSet};
+
+use hecs::Entity;
+use nalgebra::{Vector2, Vector3};
+use thiserror::Error;
+
+use crate::rendering::model::mesh::TextureInfo;
+
+const DEFAULT_CHUNK_SIZE: u32 = 32;
+const DEFAULT_LOD_LEVELS: u32 = 3;
+
+#[derive(Debug, Error)]
+pub enum TerrainError {
+    #[error("A heightmap needs at least 2x2 samples, got {0}x{1}")]
+    HeightmapTooSmall(u32, u32),
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TerrainViewer;
+
+#[derive(Clone, Debug)]
+pub struct TerrainChunk {
+    pub lod: u32,
+    pub terrain: Entity,
+    pub x: u32,
+    pub z: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Terrain {
+    pub(crate) chunk_entities: HashMap<(u32, u32), Entity>,
+    chunk_size: u32,
+    pub collider: bool,
+    pub(crate) collider_dirty: bool,
+    pub(crate) dirty: HashSet<(u32, u32)>,
+    heightmap: Heightmap,
+    pub lod_distances: Vec<f32>,
+    pub(crate) lods: HashMap<(u32, u32), u32>,
+    pub(crate) removed: Vec<Entity>,
+    size: Vector3<f32>,
+    pub splat: Option<SplatMaterial>,
+    pub textures: Vec<TextureInfo>,
+}
+
+impl Terrain {
+    pub fn new(heightmap: Heightmap, size: Vector3<f32>) -> Terrain {
+        Terrain::new_with_chunk_size(heightmap, size, DEFAULT_CHUNK_SIZE)
+    }
+
+    pub fn new_with_chunk_size(
+        heightmap: Heightmap,
+        size: Vector3<f32>,
+        chunk_size: u32,
+    ) -> Terrain {
+        let mut terrain = Terrain {
+            chunk_entities: HashMap::new(),
+            chunk_size: chunk_size.max(1),
+            collider: true,
+            collider_dirty: true,
+            dirty: HashSet::new(),
+            heightmap,
+            lod_distances: vec![],
+            lods: HashMap::new(),
+            removed: vec![],
+            size,
+            splat: None,
+            textures: vec![],
+        };
+        let extent = terrain.chunk_extent();
+        terrain.lod_distances = (1..=DEFAULT_LOD_LEVELS)
+            .map(|level| extent.x.max(extent.y) * (1 << level) as f32)
+            .collect();
+        terrain.mark_all_dirty();
+        terrain
+    }
+
+    pub fn heightmap(&self) -> &Heightmap {
+        &self.heightmap
+    }
+
+    pub fn size(&self) -> Vector3<f32> {
+        self.size
+    }
+
+    pub fn chunk_size(&self) -> u32 {
+        self.chunk_size
+    }
+
+    pub fn chunks(&self) -> (u32, u32) {
+        let cells_x = self.heightmap.width() - 1;
+        let cells_z = self.heightmap.depth() - 1;
+        (
+            cells_x.div_ceil(self.chunk_size),
+            cells_z.div_ceil(self.chunk_size),
+        )
+    }
+
+    pub fn max_lod(&self) -> u32 {
+        let mut lod = 0;
+        while (2 << lod) <= self.chunk_size {
+            lod += 1;
+        }
+        lod.min(self.lod_distances.len() as u32)
+    }
+
+    pub fn set_heightmap(&mut self, heightmap: Heightmap) {
+        self.heightmap = heightmap;
+        let (columns, rows) = self.chunks();
+        let removed = self
+            .chunk_entities
+            .iter()
+            .filter(|(&(x, z), _)| x >= columns || z >= rows)
+            .map(|(&key, &entity)| (key, entity))
+            .collect::<Vec<_>>();
+        for (key, entity) in removed {
+            self.chunk_entities.remove(&key);
+            self.removed.push(entity);
+        }
+        self.lods.clear();
+        self.mark_all_dirty();
+    }
+
+    pub fn set_height(&mut self, x: u32, z: u32, height: f32) {
+        self.heightmap.set_height(x, z, height);
+        let (columns, rows) = self.chunks();
+        // Normals are sampled from the neighbouring heights, so every chunk
+        // touching the surrounding samples needs to be rebuilt.
+        for sample_z in z.saturating_sub(1)..=z + 1 {
+            for sample_x in x.saturating_sub(1)..=x + 1 {
+                for chunk_z in [sample_z.saturating_sub(1), sample_z] {
+                    for chunk_x in [sample_x.saturating_sub(1), sample_x] {
+                        let chunk = (chunk_x / self.chunk_size, chunk_z / self.chunk_size);
+                        if chunk.0 < columns && chunk.1 < rows {
+                            self.dirty.insert(chunk);
+                        }
+                    }
+                }
+            }
+        }
+        self.collider_dirty = true;
+    }
+
+    pub fn sample_to_local(&self, x: u32, z: u32) -> Vector3<f32> {
+        let u = x as f32 / (self.heightmap.width() - 1) as f32;
+        let v = z as f32 / (self.heightmap.depth() - 1) as f32;
+        Vector3::new(
+            (u - 0.5f32) * self.size.x,
+            self.heightmap.height(x, z) * self.size.y,
+            (v - 0.5f32) * self.size.z,
+        )
+    }
+
+    pub fn local_to_sample(&self, x: f32, z: f32) -> Option<Vector2<f32>> {
+        let u = x / self.size.x + 0.5f32;
+        let v = z / self.size.z + 0.5f32;
+        if !(0f32..=1f32).contains(&u) || !(0f32..=1f32).contains(&v) {
+            return None;
+        }
+        Some(Vector2::new(
+            u * (self.heightmap.width() - 1) as f32,
+            v * (self.heightmap.depth() - 1) as f32,
+        ))
+    }
+
+    // Interpolates over the same triangles used by the heightfield collider,
+    // so objects placed with it rest exactly on the physical surface.
+    pub fn height_at(&self, x: f32, z: f32) -> Option<f32> {
+        let sample = self.local_to_sample(x, z)?;
+        let x0 = (sample.x.floor() as u32).min(self.heightmap.width() - 2);
+        let z0 = (sample.y.floor() as u32).min(self.heightmap.depth() - 2);
+        let (fx, fz) = (sample.x - x0 as f32, sample.y - z0 as f32);
+        let h = |x: u32, z: u32| self.heightmap.height(x, z);
+        let height = if fx + fz <= 1f32 {
+            h(x0, z0) + (h(x0 + 1, z0) - h(x0, z0)) * fx + (h(x0, z0 + 1) - h(x0, z0)) * fz
+        } else {
+            h(x0 + 1, z0 + 1)
+                + (h(x0, z0 + 1) - h(x0 + 1, z0 + 1)) * (1f32 - fx)
+                + (h(x0 + 1, z0) - h(x0 + 1, z0 + 1)) * (1f32 - fz)
+        };
+        Some(height * self.size.y)
+    }
+
+    pub fn normal_at(&self, x: f32, z: f32) -> Option<Vector3<f32>> {
+        let sample = self.local_to_sample(x, z)?;
+        Some(self.sample_normal(sample.x.round() as u32, sample.y.round() as u32))
+    }
+
+    pub(crate) fn sample_normal(&self, x: u32, z: u32) -> Vector3<f32> {
+        let cell_x = self.size.x / (self.heightmap.width() - 1) as f32;
+        let cell_z = self.size.z / (self.heightmap.depth() - 1) as f32;
+        let (left, right) = (x.saturating_sub(1), (x + 1).min(self.heightmap.width() - 1));
+        let (near, far) = (z.saturating_sub(1), (z + 1).min(self.heightmap.depth() - 1));
+        let slope_x = (self.heightmap.height(right, z) - self.heightmap.height(left, z))
+            * self.size.y
+            / ((right - left) as f32 * cell_x);
+        let slope_z = (self.heightmap.height(x, far) - self.heightmap.height(x, near))
+            * self.size.y
+            / ((far - near) as f32 * cell_z);
+        Vector3::new(-slope_x, 1f32, -slope_z)
+            .try_normalize(0f32)
+            .unwrap_or_else(Vector3::y)
+    }
+
+    pub(crate) fn chunk_samples(&self, chunk_x: u32, chunk_z: u32) -> ((u32, u32), (u32, u32)) {
+        let from_x = chunk_x * self.chunk_size;
+        let from_z = chunk_z * self.chunk_size;
+        (
+            (
+                from_x,
+                (from_x + self.chunk_size).min(self.heightmap.width() - 1),
+            ),
+            (
+                from_z,
+                (from_z + self.chunk_size).min(self.heightmap.depth() - 1),
+            ),
+        )
+    }
+
+    pub(crate) fn chunk_center(&self, chunk_x: u32, chunk_z: u32) -> Vector3<f32> {
+        let ((from_x, to_x), (from_z, to_z)) = self.chunk_samples(chunk_x, chunk_z);
+        let from = self.sample_to_local(from_x, from_z);
+        let to = self.sample_to_local(to_x, to_z);
+        Vector3::new(
+            (from.x + to.x) / 2f32,
+            self.size.y / 2f32,
+            (from.z + to.z) / 2f32,
+        )
+    }
+
+    fn chunk_extent(&self) -> Vector2<f32> {
+        Vector2::new(
+            self.size.x * self.chunk_size as f32 / (self.heightmap.width() - 1) as f32,
+            self.size.z * self.chunk_size as f32 / (self.heightmap.depth() - 1) as f32,
+        )
+    }
+
+    fn mark_all_dirty(&mut self) {
+        let (columns, rows) = self.chunks();
+        self.dirty = (0..rows)
+            .flat_map(|z| (0..columns).map(move |x| (x, z)))
+            .collect();
+        self.collider_dirty = true;
+    }
+}
+
+mod chunk;
+
+mod heightmap;
+pub use heightmap::Heightmap;
+
+mod noise;
+pub use noise::FractalNoise;
+
+mod splat;
+pub use splat::{RenderingSplatMaterial, SplatLayer, SplatMaterial, MAX_SPLAT_LAYERS};
+
+mod system;
+pub use system::TerrainSystem;
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+
+    use super::{Heightmap, Terrain};
+
+    fn terrain() -> Terrain {
+        let mut heightmap = Heightmap::new(2, 2).unwrap();
+        heightmap.set_height(1, 1, 1f32);
+        Terrain::new(heightmap, Vector3::new(2f32, 4f32, 2f32))
+    }
+
+    #[test]
+    fn height_at_follows_the_collider_triangles() {
+        let terrain = terrain();
+        assert_eq!(terrain.height_at(-1f32, -1f32), Some(0f32));
+        assert_eq!(terrain.height_at(1f32, 1f32), Some(4f32));
+        // The near triangle does not touch the raised corner
+        assert_eq!(terrain.height_at(0f32, 0f32), Some(0f32));
+        assert_eq!(terrain.height_at(0.5f32, 0.5f32), Some(2f32));
+    }
+
+    #[test]
+    fn height_at_is_none_outside_the_terrain() {
+        let terrain = terrain();
+        assert_eq!(terrain.height_at(1.5f32, 0f32), None);
+        assert_eq!(terrain.height_at(0f32, -1.5f32), None);
+    }
+}
@@ -0,0 +1,71 @@
+#[derive(Clone, Copy, Debug)]
+pub struct FractalNoise {
+    pub frequency: f32,
+    pub lacunarity: f32,
+    pub octaves: u32,
+    pub persistence: f32,
+    pub seed: u32,
+}
+
+impl Default for FractalNoise {
+    fn default() -> FractalNoise {
+        FractalNoise {
+            frequency: 4f32,
+            lacunarity: 2f32,
+            octaves: 5,
+            persistence: 0.5f32,
+            seed: 0,
+        }
+    }
+}
+
+fn lattice(seed: u32, x: i32, z: i32) -> f32 {
+    let mut hash = seed
+        .wrapping_mul(0x27d4_eb2d)
+        .wrapping_add((x as u32).wrapping_mul(0x85eb_ca6b))
+        .wrapping_add((z as u32).wrapping_mul(0xc2b2_ae35));
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2c1b_3c6d);
+    hash ^= hash >> 12;
+    hash = hash.wrapping_mul(0x297a_2d39);
+    hash ^= hash >> 15;
+    hash as f32 / u32::MAX as f32
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3f32 - 2f32 * t)
+}
+
+fn value_noise(seed: u32, x: f32, z: f32) -> f32 {
+    let (x0, z0) = (x.floor(), z.floor());
+    let (tx, tz) = (smoothstep(x - x0), smoothstep(z - z0));
+    let (x0, z0) = (x0 as i32, z0 as i32);
+    let near = lattice(seed, x0, z0) + (lattice(seed, x0 + 1, z0) - lattice(seed, x0, z0)) * tx;
+    let far = lattice(seed, x0, z0 + 1)
+        + (lattice(seed, x0 + 1, z0 + 1) - lattice(seed, x0, z0 + 1)) * tx;
+    near + (far - near) * tz
+}
+
+impl FractalNoise {
+    pub fn new(seed: u32) -> FractalNoise {
+        FractalNoise {
+            seed,
+            ..FractalNoise::default()
+        }
+    }
+
+    pub fn sample(&self, x: f32, z: f32) -> f32 {
+        let mut amplitude = 1f32;
+        let mut frequency = self.frequency;
+        let mut total = 0f32;
+        let mut range = 0f32;
+        for octave in 0..self.octaves.max(1) {
+            let seed = self.seed.wrapping_add(octave);
+            total += value_noise(seed, x * frequency, z * frequency) * amplitude;
+            range += amplitude;
+            amplitude *= self.persistence;
+            frequency *= self.lacunarity;
+        }
+        total / range
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use crate::rendering::model::mesh::{hash_textures, TextureInfo};
+use crate::rendering::opengl::program::Program;
+use crate::rendering::opengl::texture::Texture;
+use crate::resources::texture::TextureLoader;
+use crate::MageError;
+
+pub const MAX_SPLAT_LAYERS: usize = 4;
+const SPLAT_MAP_UNIT: u32 = 8;
+const SPLAT_LAYERS_UNIT: u32 = 9;
+
+#[derive(Clone, Debug)]
+pub struct SplatLayer {
+    pub texture: TextureInfo,
+    pub tiling: f32,
+}
+
+// Each channel of the splat map weights the layer with the same index.
+#[derive(Clone, Debug)]
+pub struct SplatMaterial {
+    pub layers: Vec<SplatLayer>,
+    pub splat_map: TextureInfo,
+}
+
+impl SplatMaterial {
+    pub fn new(splat_map: TextureInfo) -> SplatMaterial {
+        SplatMaterial {
+            layers: vec![],
+            splat_map,
+        }
+    }
+
+    pub fn with_layer(mut self, texture: TextureInfo, tiling: f32) -> SplatMaterial {
+        if self.layers.len() < MAX_SPLAT_LAYERS {
+            self.layers.push(SplatLayer { texture, tiling });
+        }
+        self
+    }
+
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        let textures = std::iter::once(&self.splat_map)
+            .chain(self.layers.iter().map(|layer| &layer.texture))
+            .cloned()
+            .collect();
+        hash_textures(&Some(textures), &mut hasher);
+        for layer in self.layers.iter() {
+            layer.tiling.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct RenderingSplatMaterial {
+    fingerprint: u64,
+    layers: Vec<(Arc<Texture>, f32)>,
+    splat_map: Arc<Texture>,
+}
+
+impl RenderingSplatMaterial {
+    pub(crate) fn new(
+        material: &SplatMaterial,
+        loader: &mut TextureLoader,
+    ) -> Result<RenderingSplatMaterial, MageError> {
+        let layers = material
+            .layers
+            .iter()
+            .take(MAX_SPLAT_LAYERS)
+            .map(|layer| Ok((loader.load_texture_2d(&layer.texture)?, layer.tiling)))
+            .collect::<Result<Vec<_>, MageError>>()?;
+        Ok(RenderingSplatMaterial {
+            fingerprint: material.fingerprint(),
+            layers,
+            splat_map: loader.load_texture_2d(&material.splat_map)?,
+        })
+    }
+
+    pub(crate) fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    pub(crate) fn attach_to_program(&self, program: &Program) {
+        program.set_uniform_i1("splatting", 1);
+        self.splat_map.bind(SPLAT_MAP_UNIT);
+        program.set_uniform_i1("splatMap", SPLAT_MAP_UNIT as i32);
+        program.set_uniform_i1("splatLayersCount", self.layers.len() as i32);
+        for (i, (texture, tiling)) in self.layers.iter().enumerate() {
+            let unit = SPLAT_LAYERS_UNIT + i as u32;
+            texture.bind(unit);
+            program.set_uniform_i1(&format!("splatLayers[{}]", i), unit as i32);
+            program.set_uniform_f1(&format!("splatTiling[{}]", i), *tiling);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet};
+
+use hecs::World;
+use nalgebra::{Point3, Vector3};
+
+use crate::core::system::System;
+use crate::physics::PendingColliders;
+use crate::rendering::hierarchy::{spawn_child, Children};
+use crate::rendering::model::dynamic::DynamicMesh;
+use crate::rendering::Transform;
+use crate::terrain::chunk::{chunk_mesh, terrain_collider};
+use crate::terrain::{SplatMaterial, Terrain, TerrainChunk, TerrainViewer};
+use crate::MageError;
+
+fn chunk_lods(terrain: &Terrain, viewer: Option<Vector3<f32>>) -> HashMap<(u32, u32), u32> {
+    let (columns, rows) = terrain.chunks();
+    let max_lod = terrain.max_lod();
+    (0..rows)
+        .flat_map(|z| (0..columns).map(move |x| (x, z)))
+        .map(|(x, z)| {
+            let lod = viewer
+                .map(|viewer| {
+                    let distance = (terrain.chunk_center(x, z) - viewer).norm();
+                    terrain
+                        .lod_distances
+                        .iter()
+                        .filter(|&&threshold| distance > threshold)
+                        .count() as u32
+                })
+                .unwrap_or(0);
+            ((x, z), lod.min(max_lod))
+        })
+        .collect()
+}
+
+fn neighbours((x, z): (u32, u32)) -> [Option<(u32, u32)>; 4] {
+    [
+        x.checked_sub(1).map(|x| (x, z)),
+        Some((x + 1, z)),
+        z.checked_sub(1).map(|z| (x, z)),
+        Some((x, z + 1)),
+    ]
+}
+
+pub struct TerrainSystem;
+
+impl System for TerrainSystem {
+    fn name(&self) -> &str {
+        "Terrain"
+    }
+
+    fn start(&self, _world: &mut World) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn early_update(&self, world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        let viewer = world
+            .query::<(&TerrainViewer, &Transform)>()
+            .iter()
+            .next()
+            .map(|(_, (_, transform))| transform.position);
+        let mut updates = vec![];
+        let mut colliders = vec![];
+        let mut removed = vec![];
+        for (e, (terrain, transform)) in world.query_mut::<(&mut Terrain, &Transform)>() {
+            let local_viewer = viewer.and_then(|viewer| {
+                transform
+                    .get_model_matrix()
+                    .try_inverse()
+                    .map(|inverse| inverse.transform_point(&Point3::from(viewer)).coords)
+            });
+            let lods = chunk_lods(terrain, local_viewer);
+            let mut rebuild = terrain.dirty.drain().collect::<HashSet<_>>();
+            for (key, lod) in lods.iter() {
+                if terrain.lods.get(key) != Some(lod) {
+                    rebuild.insert(*key);
+                    rebuild.extend(neighbours(*key).into_iter().flatten());
+                }
+            }
+            terrain.lods = lods;
+            for key in rebuild {
+                let lod = match terrain.lods.get(&key) {
+                    Some(lod) => *lod,
+                    None => continue,
+                };
+                let neighbour_lods =
+                    neighbours(key).map(|key| key.and_then(|key| terrain.lods.get(&key).copied()));
+                let mesh = chunk_mesh(terrain, key.0, key.1, lod, neighbour_lods);
+                updates.push((e, key, lod, mesh, terrain.splat.clone()));
+            }
+            if terrain.collider_dirty {
+                terrain.collider_dirty = false;
+                if terrain.collider {
+                    colliders.push((e, terrain_collider(terrain)));
+                }
+            }
+            removed.extend(terrain.removed.drain(..).map(|chunk| (e, chunk)));
+        }
+
+        for (terrain_entity, entity) in removed {
+            let _ = world.despawn(entity);
+            if let Ok(mut children) = world.get_mut::<Children>(terrain_entity) {
+                children.0.retain(|&child| child != entity);
+            }
+        }
+        for (entity, collider) in colliders {
+            world.insert_one(entity, PendingColliders(vec![collider]))?;
+        }
+        for (terrain_entity, key, lod, mesh, splat) in updates {
+            let existing = world
+                .get::<Terrain>(terrain_entity)?
+                .chunk_entities
+                .get(&key)
+                .copied();
+            let chunk = TerrainChunk {
+                lod,
+                terrain: terrain_entity,
+                x: key.0,
+                z: key.1,
+            };
+            let entity = match existing {
+                Some(entity) => {
+                    world.insert_one(entity, chunk)?;
+                    entity
+                }
+                None => {
+                    // Chunks follow the terrain through the transform hierarchy.
+                    let entity =
+                        spawn_child(world, terrain_entity, (chunk, DynamicMesh::dynamic()))?;
+                    world
+                        .get_mut::<Terrain>(terrain_entity)?
+                        .chunk_entities
+                        .insert(key, entity);
+                    entity
+                }
+            };
+            world.insert_one(entity, mesh)?;
+            match splat {
+                Some(splat) => world.insert_one(entity, splat)?,
+                None => {
+                    let _ = world.remove_one::<SplatMaterial>(entity);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn late_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::{HashMap, HashSet};
+
+use nalgebra::{Isometry3, Point3, Vector2, Vector3};
+use rapier3d::geometry::{Collider, ColliderBuilder, SharedShape};
+
+use crate::rendering::model::mesh::{next_revision, Mesh};
+use crate::rendering::opengl::DrawingMode;
+use crate::tilemap::{ColliderMode, TileFlags, Tilemap};
+
+pub(crate) fn chunk_mesh(
+    tilemap: &Tilemap,
+    layer: usize,
+    chunk_x: u32,
+    chunk_y: u32,
+) -> Option<Mesh> {
+    let tile_layer = tilemap.layer(layer)?;
+    if !tile_layer.visible {
+        return None;
+    }
+    let mut vertices = vec![];
+    let mut normals = vec![];
+    let mut texture_coordinates = vec![];
+    let mut indices = vec![];
+    for (x, y) in tilemap.chunk_tiles(chunk_x, chunk_y) {
+        let tile = match tilemap.tile(layer, x, y) {
+            Some(tile) => tile,
+            None => continue,
+        };
+        let region = match tilemap.tileset().frame(tile.index) {
+            Some(region) => region,
+            None => continue,
+        };
+        let (left, right) = if tile.flags.contains(TileFlags::FLIP_X) {
+            (region.max.x, region.min.x)
+        } else {
+            (region.min.x, region.max.x)
+        };
+        let (bottom, top) = if tile.flags.contains(TileFlags::FLIP_Y) {
+            (region.max.y, region.min.y)
+        } else {
+            (region.min.y, region.max.y)
+        };
+        let origin = tilemap.tile_to_local(x, y);
+        let first = vertices.len() as u32;
+        for (corner_x, corner_y, u, v) in [
+            (0f32, 0f32, left, bottom),
+            (1f32, 0f32, right, bottom),
+            (1f32, 1f32, right, top),
+            (0f32, 1f32, left, top),
+        ] {
+            vertices.push(Vector3::new(
+                origin.x + corner_x * tilemap.tile_size.x,
+                origin.y + corner_y * tilemap.tile_size.y,
+                tile_layer.z,
+            ));
+            normals.push(Vector3::z());
+            texture_coordinates.push(Vector2::new(u, v));
+        }
+        indices.extend([first, first + 1, first + 2, first + 2, first + 3, first]);
+    }
+    if vertices.is_empty() {
+        return None;
+    }
+    Some(Mesh {
+        bitangents: None,
+        colors: None,
+        drawing_mode: DrawingMode::Triangles,
+        indices: Some(indices),
+        joints: None,
+        normals: Some(normals),
+        revision: next_revision(),
+        secondary_texture_coordinates: None,
+        shininess: None,
+        tangents: None,
+        textures: Some(vec![tilemap.tileset().texture().clone()]),
+        texture_coordinates: Some(texture_coordinates),
+        vertices,
+        weights: None,
+    })
+}
+
+pub(crate) fn chunk_colliders(tilemap: &Tilemap, chunk_x: u32, chunk_y: u32) -> Vec<Collider> {
+    match tilemap.collider_mode {
+        ColliderMode::Cuboids => cuboid_colliders(tilemap, chunk_x, chunk_y),
+        ColliderMode::None => vec![],
+        ColliderMode::Polyline => polyline_colliders(tilemap, chunk_x, chunk_y),
+    }
+}
+
+fn cuboid_colliders(tilemap: &Tilemap, chunk_x: u32, chunk_y: u32) -> Vec<Collider> {
+    let tiles = tilemap.chunk_tiles(chunk_x, chunk_y);
+    let (from_x, from_y) = match tiles.first() {
+        Some(&first) => first,
+        None => return vec![],
+    };
+    let (to_x, to_y) = tiles.last().map(|&(x, y)| (x + 1, y + 1)).unwrap();
+    let mut visited = HashSet::new();
+    let mut shapes = vec![];
+    for y in from_y..to_y {
+        for x in from_x..to_x {
+            if visited.contains(&(x, y)) || !tilemap.is_solid(x, y) {
+                continue;
+            }
+            let free = |x: u32, y: u32| !visited.contains(&(x, y)) && tilemap.is_solid(x, y);
+            let mut width = 1;
+            while x + width < to_x && free(x + width, y) {
+                width += 1;
+            }
+            let mut height = 1;
+            while y + height < to_y && (x..x + width).all(|x| free(x, y + height)) {
+                height += 1;
+            }
+            for rectangle_y in y..y + height {
+                for rectangle_x in x..x + width {
+                    visited.insert((rectangle_x, rectangle_y));
+                }
+            }
+            let half_extents = Vector3::new(
+                width as f32 * tilemap.tile_size.x / 2f32,
+                height as f32 * tilemap.tile_size.y / 2f32,
+                tilemap.collider_depth / 2f32,
+            );
+            let center = Vector3::new(
+                x as f32 * tilemap.tile_size.x + half_extents.x,
+                (tilemap.height() - y - height) as f32 * tilemap.tile_size.y + half_extents.y,
+                0f32,
+            );
+            shapes.push((
+                Isometry3::translation(center.x, center.y, center.z),
+                SharedShape::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            ));
+        }
+    }
+    if shapes.is_empty() {
+        vec![]
+    } else {
+        vec![ColliderBuilder::compound(shapes).build()]
+    }
+}
+
+fn polyline_colliders(tilemap: &Tilemap, chunk_x: u32, chunk_y: u32) -> Vec<Collider> {
+    let mut corners: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut vertices = vec![];
+    let mut indices = vec![];
+    let mut corner = |x: u32, y: u32| {
+        *corners.entry((x, y)).or_insert_with(|| {
+            vertices.push(Point3::new(
+                x as f32 * tilemap.tile_size.x,
+                y as f32 * tilemap.tile_size.y,
+                0f32,
+            ));
+            vertices.len() as u32 - 1
+        })
+    };
+    let solid = |x: Option<u32>, y: Option<u32>| match (x, y) {
+        (Some(x), Some(y)) => tilemap.is_solid(x, y),
+        _ => false,
+    };
+    for (x, y) in tilemap.chunk_tiles(chunk_x, chunk_y) {
+        if !tilemap.is_solid(x, y) {
+            continue;
+        }
+        let top = tilemap.height() - y;
+        let bottom = top - 1;
+        if !solid(Some(x), y.checked_sub(1)) {
+            indices.push([corner(x, top), corner(x + 1, top)]);
+        }
+        if !solid(Some(x), Some(y + 1)) {
+            indices.push([corner(x, bottom), corner(x + 1, bottom)]);
+        }
+        if !solid(x.checked_sub(1), Some(y)) {
+            indices.push([corner(x, bottom), corner(x, top)]);
+        }
+        if !solid(Some(x + 1), Some(y)) {
+            indices.push([corner(x + 1, bottom), corner(x + 1, top)]);
+        }
+    }
+    if indices.is_empty() {
+        vec![]
+    } else {
+        vec![ColliderBuilder::polyline(vertices, Some(indices)).build()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use nalgebra::{Vector2, Vector3};
+    use russimp::texture::TextureType;
+
+    use super::cuboid_colliders;
+    use crate::rendering::model::mesh::{TextureInfo, TextureSource};
+    use crate::rendering::sprite::TextureAtlas;
+    use crate::tilemap::{Tile, Tilemap};
+
+    fn tilemap(rows: &[&str]) -> Tilemap {
+        let texture = TextureInfo {
+            id: 0,
+            texture_type: TextureType::Diffuse,
+            source: TextureSource::File("tiles.png".to_string()),
+            parameters: HashMap::new(),
+        };
+        let mut tilemap = Tilemap::new(
+            rows[0].len() as u32,
+            rows.len() as u32,
+            Vector2::new(1f32, 1f32),
+            TextureAtlas::new(texture, 1, 1),
+        );
+        let layer = tilemap.add_layer("ground");
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.chars().enumerate() {
+                if cell == '#' {
+                    tilemap
+                        .set_tile(layer, x as u32, y as u32, Some(Tile::solid(0)))
+                        .unwrap();
+                }
+            }
+        }
+        tilemap
+    }
+
+    fn rectangles(tilemap: &Tilemap) -> Vec<(Vector3<f32>, Vector3<f32>)> {
+        let colliders = cuboid_colliders(tilemap, 0, 0);
+        assert_eq!(colliders.len(), 1);
+        colliders[0]
+            .shape()
+            .as_compound()
+            .unwrap()
+            .shapes()
+            .iter()
+            .map(|(position, shape)| {
+                (
+                    position.translation.vector,
+                    shape.as_cuboid().unwrap().half_extents,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merges_solid_tiles_into_rectangles() {
+        let tilemap = tilemap(&["###.", "###.", "...#"]);
+        assert_eq!(
+            rectangles(&tilemap),
+            vec![
+                (
+                    Vector3::new(1.5f32, 2f32, 0f32),
+                    Vector3::new(1.5f32, 1f32, 0.5f32)
+                ),
+                (
+                    Vector3::new(3.5f32, 0.5f32, 0f32),
+                    Vector3::new(0.5f32, 0.5f32, 0.5f32)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rectangles_do_not_overlap() {
+        let tilemap = tilemap(&["##.", "###"]);
+        let rectangles = rectangles(&tilemap);
+        assert_eq!(rectangles.len(), 2);
+        let area = rectangles
+            .iter()
+            .map(|(_, half_extents)| half_extents.x * half_extents.y * 4f32)
+            .sum::<f32>();
+        assert_eq!(area, 5f32);
+    }
+
+    #[test]
+    fn empty_chunks_have_no_colliders() {
+        let tilemap = tilemap(&["...", "..."]);
+        assert!(cuboid_colliders(&tilemap, 0, 0).is_empty());
+    }
+}
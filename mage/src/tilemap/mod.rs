@@ -0,0 +1,328 @@
+use std::collections::{HashMap, HashSet};
+
+use hecs::Entity;
+use nalgebra::Vector2;
+use thiserror::Error;
+
+use crate::rendering::sprite::TextureAtlas;
+
+const DEFAULT_CHUNK_SIZE: u32 = 16;
+const LAYER_SPACING: f32 = 0.001;
+
+#[derive(Debug, Error)]
+pub enum TilemapError {
+    #[error("Tile {0}x{1} is outside of the tilemap")]
+    OutOfBounds(u32, u32),
+    #[error("Layer {0} doesn't exist")]
+    InvalidLayer(usize),
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TileFlags(u32);
+
+impl TileFlags {
+    pub const NONE: TileFlags = TileFlags(0);
+    pub const SOLID: TileFlags = TileFlags(1);
+    pub const FLIP_X: TileFlags = TileFlags(1 << 1);
+    pub const FLIP_Y: TileFlags = TileFlags(1 << 2);
+
+    pub fn contains(&self, flags: TileFlags) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+
+    pub fn insert(&mut self, flags: TileFlags) {
+        self.0 |= flags.0;
+    }
+
+    pub fn remove(&mut self, flags: TileFlags) {
+        self.0 &= !flags.0;
+    }
+}
+
+impl std::ops::BitOr for TileFlags {
+    type Output = TileFlags;
+
+    fn bitor(self, rhs: TileFlags) -> TileFlags {
+        TileFlags(self.0 | rhs.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Tile {
+    pub flags: TileFlags,
+    pub index: usize,
+}
+
+impl Tile {
+    pub fn new(index: usize) -> Tile {
+        Tile {
+            flags: TileFlags::NONE,
+            index,
+        }
+    }
+
+    pub fn solid(index: usize) -> Tile {
+        Tile {
+            flags: TileFlags::SOLID,
+            index,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TileLayer {
+    pub name: String,
+    tiles: Vec<Option<Tile>>,
+    pub visible: bool,
+    pub z: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColliderMode {
+    Cuboids,
+    None,
+    Polyline,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct ChunkKey {
+    pub(crate) layer: Option<usize>,
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct TilemapChunk {
+    pub layer: Option<usize>,
+    pub tilemap: Entity,
+    pub x: u32,
+    pub y: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Tilemap {
+    pub(crate) chunk_entities: HashMap<ChunkKey, Entity>,
+    chunk_size: u32,
+    pub collider_depth: f32,
+    pub collider_mode: ColliderMode,
+    pub(crate) dirty: HashSet<ChunkKey>,
+    height: u32,
+    layers: Vec<TileLayer>,
+    pub tile_size: Vector2<f32>,
+    tileset: TextureAtlas,
+    width: u32,
+}
+
+impl Tilemap {
+    pub fn new(width: u32, height: u32, tile_size: Vector2<f32>, tileset: TextureAtlas) -> Tilemap {
+        Tilemap::new_with_chunk_size(width, height, tile_size, tileset, DEFAULT_CHUNK_SIZE)
+    }
+
+    pub fn new_with_chunk_size(
+        width: u32,
+        height: u32,
+        tile_size: Vector2<f32>,
+        tileset: TextureAtlas,
+        chunk_size: u32,
+    ) -> Tilemap {
+        Tilemap {
+            chunk_entities: HashMap::new(),
+            chunk_size: chunk_size.max(1),
+            collider_depth: 1f32,
+            collider_mode: ColliderMode::Cuboids,
+            dirty: HashSet::new(),
+            height,
+            layers: vec![],
+            tile_size,
+            tileset,
+            width,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn chunk_size(&self) -> u32 {
+        self.chunk_size
+    }
+
+    pub fn tileset(&self) -> &TextureAtlas {
+        &self.tileset
+    }
+
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
+    }
+
+    pub fn add_layer(&mut self, name: &str) -> usize {
+        self.layers.push(TileLayer {
+            name: name.to_string(),
+            tiles: vec![None; (self.width * self.height) as usize],
+            visible: true,
+            z: self.layers.len() as f32 * LAYER_SPACING,
+        });
+        let layer = self.layers.len() - 1;
+        for (x, y) in self.chunks() {
+            self.dirty.insert(ChunkKey {
+                layer: Some(layer),
+                x,
+                y,
+            });
+        }
+        layer
+    }
+
+    pub fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    pub fn set_layer_visible(&mut self, layer: usize, visible: bool) -> Result<(), TilemapError> {
+        self.layers
+            .get_mut(layer)
+            .ok_or(TilemapError::InvalidLayer(layer))?
+            .visible = visible;
+        for (x, y) in self.chunks() {
+            self.dirty.insert(ChunkKey {
+                layer: Some(layer),
+                x,
+                y,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn tile(&self, layer: usize, x: u32, y: u32) -> Option<Tile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.layers
+            .get(layer)
+            .and_then(|layer| layer.tiles[(y * self.width + x) as usize])
+    }
+
+    pub fn set_tile(
+        &mut self,
+        layer: usize,
+        x: u32,
+        y: u32,
+        tile: Option<Tile>,
+    ) -> Result<(), TilemapError> {
+        if x >= self.width || y >= self.height {
+            return Err(TilemapError::OutOfBounds(x, y));
+        }
+        let width = self.width;
+        let tiles = &mut self
+            .layers
+            .get_mut(layer)
+            .ok_or(TilemapError::InvalidLayer(layer))?
+            .tiles;
+        tiles[(y * width + x) as usize] = tile;
+        self.mark_dirty(layer, x, y);
+        Ok(())
+    }
+
+    pub fn fill(
+        &mut self,
+        layer: usize,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        tile: Option<Tile>,
+    ) -> Result<(), TilemapError> {
+        for tile_y in y..y + height {
+            for tile_x in x..x + width {
+                self.set_tile(layer, tile_x, tile_y, tile)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_solid(&self, x: u32, y: u32) -> bool {
+        (0..self.layers.len()).any(|layer| {
+            self.tile(layer, x, y)
+                .map(|tile| tile.flags.contains(TileFlags::SOLID))
+                .unwrap_or(false)
+        })
+    }
+
+    pub fn tile_to_local(&self, x: u32, y: u32) -> Vector2<f32> {
+        Vector2::new(
+            x as f32 * self.tile_size.x,
+            (self.height - 1 - y) as f32 * self.tile_size.y,
+        )
+    }
+
+    pub fn local_to_tile(&self, position: Vector2<f32>) -> Option<(u32, u32)> {
+        let x = (position.x / self.tile_size.x).floor();
+        let y = self.height as f32 - 1f32 - (position.y / self.tile_size.y).floor();
+        if x < 0f32 || y < 0f32 || x >= self.width as f32 || y >= self.height as f32 {
+            None
+        } else {
+            Some((x as u32, y as u32))
+        }
+    }
+
+    fn chunks(&self) -> Vec<(u32, u32)> {
+        let columns = (0..self.width).step_by(self.chunk_size as usize).count() as u32;
+        let rows = (0..self.height).step_by(self.chunk_size as usize).count() as u32;
+        (0..rows)
+            .flat_map(|y| (0..columns).map(move |x| (x, y)))
+            .collect()
+    }
+
+    fn mark_dirty(&mut self, layer: usize, x: u32, y: u32) {
+        let chunk_x = x / self.chunk_size;
+        let chunk_y = y / self.chunk_size;
+        self.dirty.insert(ChunkKey {
+            layer: Some(layer),
+            x: chunk_x,
+            y: chunk_y,
+        });
+        let neighbours = [
+            (x.checked_sub(1), Some(y)),
+            (x.checked_add(1), Some(y)),
+            (Some(x), y.checked_sub(1)),
+            (Some(x), y.checked_add(1)),
+            (Some(x), Some(y)),
+        ];
+        for (neighbour_x, neighbour_y) in neighbours {
+            if let (Some(neighbour_x), Some(neighbour_y)) = (neighbour_x, neighbour_y) {
+                if neighbour_x < self.width && neighbour_y < self.height {
+                    self.dirty.insert(ChunkKey {
+                        layer: None,
+                        x: neighbour_x / self.chunk_size,
+                        y: neighbour_y / self.chunk_size,
+                    });
+                }
+            }
+        }
+    }
+
+    pub(crate) fn chunk_tiles(&self, chunk_x: u32, chunk_y: u32) -> Vec<(u32, u32)> {
+        let from_x = chunk_x * self.chunk_size;
+        let from_y = chunk_y * self.chunk_size;
+        let to_x = (from_x + self.chunk_size).min(self.width);
+        let to_y = (from_y + self.chunk_size).min(self.height);
+        (from_y..to_y)
+            .flat_map(|y| (from_x..to_x).map(move |x| (x, y)))
+            .collect()
+    }
+
+    pub(crate) fn layer(&self, layer: usize) -> Option<&TileLayer> {
+        self.layers.get(layer)
+    }
+}
+
+mod chunk;
+
+mod system;
+pub use system::TilemapSystem;
+
+mod tiled;
+pub use tiled::{TiledError, TiledLoader, TiledMap, TiledObject, TiledProperty, TiledShape};
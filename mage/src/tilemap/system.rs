@@ -0,0 +1,103 @@
+use hecs::World;
+use rapier3d::geometry::Collider;
+
+use crate::core::system::System;
+use crate::physics::PendingColliders;
+use crate::rendering::hierarchy::spawn_child;
+use crate::rendering::model::mesh::Mesh;
+use crate::rendering::transparent::RenderQueue;
+use crate::rendering::Transform;
+use crate::tilemap::chunk::{chunk_colliders, chunk_mesh};
+use crate::tilemap::{Tilemap, TilemapChunk};
+use crate::MageError;
+
+const ALPHA_CUTOFF: f32 = 0.5;
+
+enum ChunkUpdate {
+    Colliders(Vec<Collider>),
+    Mesh(Option<Box<Mesh>>),
+}
+
+pub struct TilemapSystem;
+
+impl System for TilemapSystem {
+    fn name(&self) -> &str {
+        "Tilemap"
+    }
+
+    fn start(&self, _world: &mut World) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn early_update(&self, world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        let mut updates = vec![];
+        for (e, tilemap) in world.query_mut::<&mut Tilemap>().with::<Transform>() {
+            let dirty = tilemap.dirty.drain().collect::<Vec<_>>();
+            for key in dirty {
+                let update = match key.layer {
+                    Some(layer) => {
+                        ChunkUpdate::Mesh(chunk_mesh(tilemap, layer, key.x, key.y).map(Box::new))
+                    }
+                    None => ChunkUpdate::Colliders(chunk_colliders(tilemap, key.x, key.y)),
+                };
+                updates.push((e, key, update));
+            }
+        }
+
+        // Chunks of despawned tilemaps are despawned with them.
+        let orphans = world
+            .query::<&TilemapChunk>()
+            .iter()
+            .filter(|(_, chunk)| world.get::<Tilemap>(chunk.tilemap).is_err())
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for entity in orphans {
+            let _ = world.despawn(entity);
+        }
+
+        for (tilemap_entity, key, update) in updates {
+            let existing = world
+                .get::<Tilemap>(tilemap_entity)?
+                .chunk_entities
+                .get(&key)
+                .copied();
+            let entity = match existing {
+                Some(entity) => entity,
+                None => {
+                    let chunk = TilemapChunk {
+                        layer: key.layer,
+                        tilemap: tilemap_entity,
+                        x: key.x,
+                        y: key.y,
+                    };
+                    let entity = spawn_child(world, tilemap_entity, (chunk,))?;
+                    world
+                        .get_mut::<Tilemap>(tilemap_entity)?
+                        .chunk_entities
+                        .insert(key, entity);
+                    entity
+                }
+            };
+            match update {
+                ChunkUpdate::Colliders(colliders) => {
+                    world.insert_one(entity, PendingColliders(colliders))?;
+                }
+                ChunkUpdate::Mesh(Some(mesh)) => {
+                    world.insert(entity, (*mesh, RenderQueue::AlphaTest(ALPHA_CUTOFF)))?;
+                }
+                ChunkUpdate::Mesh(None) => {
+                    let _ = world.remove_one::<Mesh>(entity);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn late_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use nalgebra::Vector2;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::tilemap::tiled::{
+    decode_base64_gids, relative_path, LayerData, MapData, TiledError, TiledObject, TiledProperty,
+    TiledShape, TilesetData,
+};
+use crate::MageError;
+
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+    #[serde(default, rename = "type")]
+    property_type: String,
+    value: Value,
+}
+
+#[derive(Deserialize)]
+struct JsonPoint {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Deserialize)]
+struct JsonObject {
+    #[serde(default)]
+    class: String,
+    #[serde(default)]
+    ellipse: bool,
+    gid: Option<u32>,
+    #[serde(default)]
+    height: f32,
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    point: bool,
+    polygon: Option<Vec<JsonPoint>>,
+    polyline: Option<Vec<JsonPoint>>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+    #[serde(default)]
+    rotation: f32,
+    #[serde(default, rename = "type")]
+    object_type: String,
+    #[serde(default)]
+    width: f32,
+    x: f32,
+    y: f32,
+}
+
+#[derive(Deserialize)]
+struct JsonLayer {
+    compression: Option<String>,
+    data: Option<Value>,
+    encoding: Option<String>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    objects: Vec<JsonObject>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+    #[serde(rename = "type")]
+    layer_type: String,
+    #[serde(default = "visible_default")]
+    visible: bool,
+}
+
+#[derive(Deserialize)]
+struct JsonTile {
+    id: u32,
+    objectgroup: Option<Value>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+struct JsonTileset {
+    #[serde(default)]
+    columns: u32,
+    firstgid: Option<u32>,
+    image: Option<String>,
+    #[serde(default)]
+    imageheight: u32,
+    #[serde(default)]
+    imagewidth: u32,
+    #[serde(default)]
+    margin: u32,
+    #[serde(default)]
+    name: String,
+    source: Option<String>,
+    #[serde(default)]
+    spacing: u32,
+    #[serde(default)]
+    tilecount: u32,
+    #[serde(default)]
+    tileheight: u32,
+    #[serde(default)]
+    tiles: Vec<JsonTile>,
+    #[serde(default)]
+    tilewidth: u32,
+}
+
+#[derive(Deserialize)]
+struct JsonMap {
+    height: u32,
+    #[serde(default)]
+    infinite: bool,
+    layers: Vec<JsonLayer>,
+    tileheight: u32,
+    tilesets: Vec<JsonTileset>,
+    tilewidth: u32,
+    width: u32,
+}
+
+fn visible_default() -> bool {
+    true
+}
+
+fn read<T: for<'a> Deserialize<'a>>(path: &Path) -> Result<T, MageError> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(serde_json::from_reader(reader)?)
+}
+
+fn properties(properties: &[JsonProperty]) -> Result<HashMap<String, TiledProperty>, MageError> {
+    properties
+        .iter()
+        .map(|property| {
+            let value = match &property.value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            Ok((
+                property.name.clone(),
+                TiledProperty::parse(&property.property_type, &value)?,
+            ))
+        })
+        .collect()
+}
+
+fn points(points: &[JsonPoint]) -> Vec<Vector2<f32>> {
+    points
+        .iter()
+        .map(|point| Vector2::new(point.x, point.y))
+        .collect()
+}
+
+fn object(object: &JsonObject, layer: &str) -> Result<TiledObject, MageError> {
+    let shape = if let Some(gid) = object.gid {
+        TiledShape::Tile(gid)
+    } else if let Some(polygon) = &object.polygon {
+        TiledShape::Polygon(points(polygon))
+    } else if let Some(polyline) = &object.polyline {
+        TiledShape::Polyline(points(polyline))
+    } else if object.ellipse {
+        TiledShape::Ellipse
+    } else if object.point {
+        TiledShape::Point
+    } else {
+        TiledShape::Rectangle
+    };
+    let class = if object.class.is_empty() {
+        object.object_type.clone()
+    } else {
+        object.class.clone()
+    };
+    Ok(TiledObject {
+        class,
+        height: object.height,
+        id: object.id,
+        layer: layer.to_string(),
+        name: object.name.clone(),
+        properties: properties(&object.properties)?,
+        rotation: object.rotation,
+        shape,
+        width: object.width,
+        x: object.x,
+        y: object.y,
+    })
+}
+
+fn tiles(layer: &JsonLayer) -> Result<Vec<u32>, MageError> {
+    match layer.encoding.as_deref() {
+        None | Some("csv") => {}
+        Some("base64") => {
+            return match &layer.data {
+                Some(Value::String(data)) => decode_base64_gids(data, layer.compression.as_deref()),
+                Some(data) => {
+                    Err(TiledError::InvalidValue("data".to_string(), data.to_string()).into())
+                }
+                None => Err(TiledError::MissingAttribute("data".to_string()).into()),
+            };
+        }
+        Some(encoding) => return Err(TiledError::UnsupportedEncoding(encoding.to_string()).into()),
+    }
+    match &layer.data {
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|value| {
+                value
+                    .as_u64()
+                    .map(|gid| gid as u32)
+                    .ok_or_else(|| TiledError::InvalidValue("data".to_string(), value.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.into()),
+        Some(data) => Err(TiledError::InvalidValue("data".to_string(), data.to_string()).into()),
+        None => Err(TiledError::MissingAttribute("data".to_string()).into()),
+    }
+}
+
+fn layers(layers: &[JsonLayer], data: &mut Vec<LayerData>) -> Result<(), MageError> {
+    for layer in layers {
+        match layer.layer_type.as_str() {
+            "tilelayer" => data.push(LayerData::Tiles {
+                name: layer.name.clone(),
+                properties: properties(&layer.properties)?,
+                tiles: tiles(layer)?,
+                visible: layer.visible,
+            }),
+            "objectgroup" => data.push(LayerData::Objects {
+                objects: layer
+                    .objects
+                    .iter()
+                    .map(|o| object(o, &layer.name))
+                    .collect::<Result<Vec<_>, _>>()?,
+                properties: properties(&layer.properties)?,
+            }),
+            "group" => self::layers(&layer.layers, data)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn tileset(path: &Path, tileset: &JsonTileset, first_gid: u32) -> Result<TilesetData, MageError> {
+    if let Some(source) = &tileset.source {
+        let source = relative_path(path, source);
+        let external = read::<JsonTileset>(&source)?;
+        return self::tileset(&source, &external, first_gid);
+    }
+    let image = tileset
+        .image
+        .as_ref()
+        .ok_or_else(|| TiledError::ImageCollection(tileset.name.clone()))?;
+    let mut solid = HashSet::new();
+    for tile in tileset.tiles.iter() {
+        let solid_property = properties(&tile.properties)?
+            .get("solid")
+            .and_then(TiledProperty::as_bool)
+            .unwrap_or(false);
+        if tile.objectgroup.is_some() || solid_property {
+            solid.insert(tile.id);
+        }
+    }
+    Ok(TilesetData {
+        columns: tileset.columns,
+        first_gid,
+        image: relative_path(path, image),
+        image_height: tileset.imageheight,
+        image_width: tileset.imagewidth,
+        margin: tileset.margin,
+        solid,
+        spacing: tileset.spacing,
+        tile_count: tileset.tilecount,
+        tile_height: tileset.tileheight,
+        tile_width: tileset.tilewidth,
+    })
+}
+
+pub(crate) fn load_map(path: &Path) -> Result<MapData, MageError> {
+    let map = read::<JsonMap>(path)?;
+    if map.infinite {
+        return Err(TiledError::InfiniteMap.into());
+    }
+    let mut data = vec![];
+    layers(&map.layers, &mut data)?;
+    let tilesets = map
+        .tilesets
+        .iter()
+        .map(|t| {
+            let first_gid = t
+                .firstgid
+                .ok_or_else(|| TiledError::MissingAttribute("firstgid".to_string()))?;
+            tileset(path, t, first_gid)
+        })
+        .collect::<Result<Vec<_>, MageError>>()?;
+    Ok(MapData {
+        height: map.height,
+        layers: data,
+        tile_height: map.tileheight,
+        tile_width: map.tilewidth,
+        tilesets,
+        width: map.width,
+    })
+}
@@ -0,0 +1,544 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use hecs::{Entity, World};
+use nalgebra::{Isometry3, Point3, UnitQuaternion, Vector2, Vector3};
+use rapier3d::geometry::{Collider, ColliderBuilder, SharedShape};
+use russimp::texture::TextureType;
+use thiserror::Error;
+
+use crate::physics::PendingColliders;
+use crate::rendering::model::mesh::{TextureInfo, TextureSource};
+use crate::rendering::opengl::texture::{TextureParameter, TextureParameterValue};
+use crate::rendering::sprite::{Sprite, TextureAtlas};
+use crate::rendering::Transform;
+use crate::tilemap::{ColliderMode, Tile, TileFlags, Tilemap};
+use crate::MageError;
+
+mod json;
+mod tmx;
+
+const FLIPPED_HORIZONTALLY: u32 = 0x80000000;
+const FLIPPED_VERTICALLY: u32 = 0x40000000;
+const GID_MASK: u32 = 0x0fffffff;
+
+// Splits a global tile id into the tile id and its horizontal and vertical flip.
+fn decode_gid(gid: u32) -> (u32, bool, bool) {
+    (
+        gid & GID_MASK,
+        gid & FLIPPED_HORIZONTALLY != 0,
+        gid & FLIPPED_VERTICALLY != 0,
+    )
+}
+
+// Uncompressed base64 layer data holds the global tile ids as little endian u32.
+pub(crate) fn decode_base64_gids(
+    data: &str,
+    compression: Option<&str>,
+) -> Result<Vec<u32>, MageError> {
+    if let Some(compression) = compression.filter(|compression| !compression.is_empty()) {
+        return Err(TiledError::UnsupportedCompression(compression.to_string()).into());
+    }
+    let data = data
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let bytes = base64::decode(&data)?;
+    if bytes.len() % 4 != 0 {
+        return Err(TiledError::InvalidValue("data".to_string(), data).into());
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+        .collect())
+}
+
+#[derive(Debug, Error)]
+pub enum TiledError {
+    #[error("Unsupported map format {0}")]
+    UnsupportedFormat(String),
+    #[error("Unsupported layer encoding {0}")]
+    UnsupportedEncoding(String),
+    #[error("Unsupported layer compression {0}")]
+    UnsupportedCompression(String),
+    #[error("Infinite maps are not supported")]
+    InfiniteMap,
+    #[error("Tileset {0} is an image collection, which is not supported")]
+    ImageCollection(String),
+    #[error("Missing attribute {0}")]
+    MissingAttribute(String),
+    #[error("Invalid value {1} for {0}")]
+    InvalidValue(String, String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TiledProperty {
+    Bool(bool),
+    Color(String),
+    File(String),
+    Float(f32),
+    Int(i64),
+    Object(u32),
+    String(String),
+}
+
+impl TiledProperty {
+    pub(crate) fn parse(property_type: &str, value: &str) -> Result<TiledProperty, MageError> {
+        let invalid = || TiledError::InvalidValue(property_type.to_string(), value.to_string());
+        Ok(match property_type {
+            "bool" => TiledProperty::Bool(value.parse().map_err(|_| invalid())?),
+            "color" => TiledProperty::Color(value.to_string()),
+            "file" => TiledProperty::File(value.to_string()),
+            "float" => TiledProperty::Float(value.parse().map_err(|_| invalid())?),
+            "int" => TiledProperty::Int(value.parse().map_err(|_| invalid())?),
+            "object" => TiledProperty::Object(value.parse().map_err(|_| invalid())?),
+            _ => TiledProperty::String(value.to_string()),
+        })
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            TiledProperty::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f32> {
+        match self {
+            TiledProperty::Float(value) => Some(*value),
+            TiledProperty::Int(value) => Some(*value as f32),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            TiledProperty::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TiledProperty::Color(value)
+            | TiledProperty::File(value)
+            | TiledProperty::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TiledShape {
+    Ellipse,
+    Point,
+    Polygon(Vec<Vector2<f32>>),
+    Polyline(Vec<Vector2<f32>>),
+    Rectangle,
+    Tile(u32),
+}
+
+#[derive(Clone, Debug)]
+pub struct TiledObject {
+    pub class: String,
+    pub height: f32,
+    pub id: u32,
+    pub layer: String,
+    pub name: String,
+    pub properties: HashMap<String, TiledProperty>,
+    pub rotation: f32,
+    pub shape: TiledShape,
+    pub width: f32,
+    pub x: f32,
+    pub y: f32,
+}
+
+impl TiledObject {
+    pub fn property(&self, name: &str) -> Option<&TiledProperty> {
+        self.properties.get(name)
+    }
+}
+
+pub(crate) struct TilesetData {
+    columns: u32,
+    first_gid: u32,
+    image: PathBuf,
+    image_height: u32,
+    image_width: u32,
+    margin: u32,
+    solid: HashSet<u32>,
+    spacing: u32,
+    tile_count: u32,
+    tile_height: u32,
+    tile_width: u32,
+}
+
+pub(crate) enum LayerData {
+    Objects {
+        objects: Vec<TiledObject>,
+        properties: HashMap<String, TiledProperty>,
+    },
+    Tiles {
+        name: String,
+        properties: HashMap<String, TiledProperty>,
+        tiles: Vec<u32>,
+        visible: bool,
+    },
+}
+
+pub(crate) struct MapData {
+    height: u32,
+    layers: Vec<LayerData>,
+    tile_height: u32,
+    tile_width: u32,
+    tilesets: Vec<TilesetData>,
+    width: u32,
+}
+
+pub(crate) fn relative_path(base: &Path, path: &str) -> PathBuf {
+    base.parent()
+        .map(|parent| parent.join(path))
+        .unwrap_or_else(|| PathBuf::from(path))
+}
+
+type ObjectCallback = Box<dyn Fn(&mut World, Entity, &TiledObject) -> Result<(), MageError>>;
+
+pub struct TiledMap {
+    pub objects: Vec<Entity>,
+    pub tilemaps: Vec<Entity>,
+}
+
+pub struct TiledLoader {
+    callbacks: HashMap<String, ObjectCallback>,
+    pub collider_mode: ColliderMode,
+    pub tile_size: Vector2<f32>,
+}
+
+impl TiledLoader {
+    pub fn new(tile_size: Vector2<f32>) -> TiledLoader {
+        TiledLoader {
+            callbacks: HashMap::new(),
+            collider_mode: ColliderMode::Cuboids,
+            tile_size,
+        }
+    }
+
+    pub fn register<F>(&mut self, class: &str, callback: F)
+    where
+        F: Fn(&mut World, Entity, &TiledObject) -> Result<(), MageError> + 'static,
+    {
+        self.callbacks.insert(class.to_string(), Box::new(callback));
+    }
+
+    pub fn load(
+        &self,
+        path: &str,
+        world: &mut World,
+        transform: &Transform,
+    ) -> Result<TiledMap, MageError> {
+        let path = Path::new(path);
+        let map = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("tmj") | Some("json") => json::load_map(path)?,
+            Some("tmx") => tmx::load_map(path)?,
+            extension => {
+                return Err(TiledError::UnsupportedFormat(
+                    extension.unwrap_or_default().to_string(),
+                )
+                .into())
+            }
+        };
+        let tilemaps = self.spawn_tilemaps(&map, world, transform);
+        let mut objects = vec![];
+        for layer in map.layers.iter() {
+            if let LayerData::Objects {
+                objects: layer_objects,
+                properties,
+            } = layer
+            {
+                for object in layer_objects {
+                    objects.push(self.spawn_object(&map, object, properties, world, transform)?);
+                }
+            }
+        }
+        Ok(TiledMap { objects, tilemaps })
+    }
+
+    fn pixel_scale(&self, map: &MapData) -> Vector2<f32> {
+        Vector2::new(
+            self.tile_size.x / map.tile_width as f32,
+            self.tile_size.y / map.tile_height as f32,
+        )
+    }
+
+    fn spawn_tilemaps(
+        &self,
+        map: &MapData,
+        world: &mut World,
+        transform: &Transform,
+    ) -> Vec<Entity> {
+        let mut entities = vec![];
+        for tileset in map.tilesets.iter() {
+            let mut tilemap = Tilemap::new(
+                map.width,
+                map.height,
+                self.tile_size,
+                tileset_atlas(tileset),
+            );
+            tilemap.collider_mode = self.collider_mode;
+            let mut used = false;
+            for layer in map.layers.iter() {
+                if let LayerData::Tiles {
+                    name,
+                    properties,
+                    tiles,
+                    visible,
+                } = layer
+                {
+                    let index = tilemap.add_layer(name);
+                    let solid_layer = properties
+                        .get("solid")
+                        .and_then(TiledProperty::as_bool)
+                        .unwrap_or(false);
+                    for (i, &gid) in tiles.iter().enumerate() {
+                        let (id, flip_x, flip_y) = decode_gid(gid);
+                        if id < tileset.first_gid || id >= tileset.first_gid + tileset.tile_count {
+                            continue;
+                        }
+                        let local_id = id - tileset.first_gid;
+                        let mut tile = Tile::new(local_id as usize);
+                        if flip_x {
+                            tile.flags.insert(TileFlags::FLIP_X);
+                        }
+                        if flip_y {
+                            tile.flags.insert(TileFlags::FLIP_Y);
+                        }
+                        if solid_layer || tileset.solid.contains(&local_id) {
+                            tile.flags.insert(TileFlags::SOLID);
+                        }
+                        let x = i as u32 % map.width;
+                        let y = i as u32 / map.width;
+                        if tilemap.set_tile(index, x, y, Some(tile)).is_ok() {
+                            used = true;
+                        }
+                    }
+                    if !visible {
+                        let _ = tilemap.set_layer_visible(index, false);
+                    }
+                }
+            }
+            if used {
+                entities.push(world.spawn((tilemap, transform.clone())));
+            }
+        }
+        entities
+    }
+
+    fn spawn_object(
+        &self,
+        map: &MapData,
+        object: &TiledObject,
+        layer_properties: &HashMap<String, TiledProperty>,
+        world: &mut World,
+        transform: &Transform,
+    ) -> Result<Entity, MageError> {
+        let scale = self.pixel_scale(map);
+        let map_height = (map.height * map.tile_height) as f32;
+        let rotation =
+            UnitQuaternion::from_axis_angle(&Vector3::z_axis(), -object.rotation.to_radians());
+        let offset = Vector3::new(object.x * scale.x, (map_height - object.y) * scale.y, 0f32);
+        let object_transform = Transform {
+            position: transform.position + transform.rotation * offset,
+            rotation: transform.rotation * rotation,
+            scale: Vector3::new(1f32, 1f32, 1f32),
+        };
+        let entity = world.spawn((object_transform, object.clone()));
+
+        let has_collider = object
+            .property("collider")
+            .or_else(|| layer_properties.get("collider"))
+            .and_then(TiledProperty::as_bool)
+            .unwrap_or(false);
+        if has_collider {
+            if let Some(collider) = self.object_collider(object, scale) {
+                world.insert_one(entity, PendingColliders(vec![collider]))?;
+            }
+        }
+
+        if let TiledShape::Tile(gid) = object.shape {
+            if let Some(sprite) = self.object_sprite(map, object, gid, scale) {
+                world.insert_one(entity, sprite)?;
+            }
+        }
+
+        if let Some(callback) = self.callbacks.get(&object.class) {
+            callback(world, entity, object)?;
+        }
+        Ok(entity)
+    }
+
+    fn object_collider(&self, object: &TiledObject, scale: Vector2<f32>) -> Option<Collider> {
+        let depth = self.tile_size.x.max(self.tile_size.y) / 2f32;
+        let width = object.width * scale.x;
+        let height = object.height * scale.y;
+        let sensor = object
+            .property("sensor")
+            .and_then(TiledProperty::as_bool)
+            .unwrap_or(false);
+        let to_local = |point: &Vector2<f32>| Vector2::new(point.x * scale.x, -point.y * scale.y);
+        let builder = match &object.shape {
+            TiledShape::Rectangle => ColliderBuilder::compound(vec![(
+                Isometry3::translation(width / 2f32, -height / 2f32, 0f32),
+                SharedShape::cuboid(width / 2f32, height / 2f32, depth),
+            )]),
+            TiledShape::Tile(_) => ColliderBuilder::compound(vec![(
+                Isometry3::translation(width / 2f32, height / 2f32, 0f32),
+                SharedShape::cuboid(width / 2f32, height / 2f32, depth),
+            )]),
+            TiledShape::Ellipse => ColliderBuilder::compound(vec![(
+                Isometry3::translation(width / 2f32, -height / 2f32, 0f32),
+                SharedShape::ball(width.min(height) / 2f32),
+            )]),
+            TiledShape::Polygon(points) => {
+                let points = points
+                    .iter()
+                    .map(to_local)
+                    .flat_map(|point| {
+                        [
+                            Point3::new(point.x, point.y, -depth),
+                            Point3::new(point.x, point.y, depth),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                ColliderBuilder::convex_hull(&points)?
+            }
+            TiledShape::Polyline(points) => ColliderBuilder::polyline(
+                points
+                    .iter()
+                    .map(to_local)
+                    .map(|point| Point3::new(point.x, point.y, 0f32))
+                    .collect(),
+                None,
+            ),
+            TiledShape::Point => return None,
+        };
+        Some(builder.sensor(sensor).build())
+    }
+
+    fn object_sprite(
+        &self,
+        map: &MapData,
+        object: &TiledObject,
+        gid: u32,
+        scale: Vector2<f32>,
+    ) -> Option<Sprite> {
+        let (id, flip_x, flip_y) = decode_gid(gid);
+        let tileset = map.tilesets.iter().find(|tileset| {
+            id >= tileset.first_gid && id < tileset.first_gid + tileset.tile_count
+        })?;
+        let mut sprite = tileset_atlas(tileset).sprite(
+            (id - tileset.first_gid) as usize,
+            Vector2::new(object.width * scale.x, object.height * scale.y),
+        )?;
+        sprite.anchor = Vector2::new(0f32, 0f32);
+        sprite.flip_x = flip_x;
+        sprite.flip_y = flip_y;
+        Some(sprite)
+    }
+}
+
+fn tileset_atlas(tileset: &TilesetData) -> TextureAtlas {
+    let texture = TextureInfo {
+        id: 0,
+        texture_type: TextureType::Diffuse,
+        source: TextureSource::File(tileset.image.to_string_lossy().to_string()),
+        parameters: HashMap::from([
+            (
+                TextureParameter::TextureWrapS,
+                TextureParameterValue::ClampToEdge,
+            ),
+            (
+                TextureParameter::TextureWrapT,
+                TextureParameterValue::ClampToEdge,
+            ),
+            (
+                TextureParameter::TextureMinFilter,
+                TextureParameterValue::Nearest,
+            ),
+            (
+                TextureParameter::TextureMagFilter,
+                TextureParameterValue::Nearest,
+            ),
+        ]),
+    };
+    let mut atlas = TextureAtlas::new(texture, tileset.image_width, tileset.image_height);
+    for tile in 0..tileset.tile_count {
+        let column = tile % tileset.columns.max(1);
+        let row = tile / tileset.columns.max(1);
+        atlas.add_frame(
+            tileset.margin + column * (tileset.tile_width + tileset.spacing),
+            tileset.margin + row * (tileset.tile_height + tileset.spacing),
+            tileset.tile_width,
+            tileset.tile_height,
+        );
+    }
+    atlas
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    use super::{decode_base64_gids, decode_gid, tileset_atlas, TilesetData};
+    use crate::rendering::sprite::TextureRegion;
+
+    #[test]
+    fn decode_gid_masks_flip_bits() {
+        assert_eq!(decode_gid(7), (7, false, false));
+        assert_eq!(decode_gid(0x80000007), (7, true, false));
+        assert_eq!(decode_gid(0x40000007), (7, false, true));
+        assert_eq!(decode_gid(0xe0000007), (7, true, true));
+    }
+
+    #[test]
+    fn decode_base64_gids_reads_little_endian_ids() {
+        // 1, 2 flipped horizontally and 0, as Tiled writes them.
+        let gids = decode_base64_gids("AQAAAAIAAIAAAAAA\n", None).unwrap();
+        assert_eq!(gids, vec![1, 0x80000002, 0]);
+        assert_eq!(decode_gid(gids[1]), (2, true, false));
+        assert_eq!(decode_base64_gids("AQAAAA==", Some("")).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn decode_base64_gids_rejects_compressed_data() {
+        assert!(decode_base64_gids("AQAAAA==", Some("zlib")).is_err());
+        assert!(decode_base64_gids("AQAA", None).is_err());
+    }
+
+    #[test]
+    fn tileset_atlas_skips_margin_and_spacing() {
+        let tileset = TilesetData {
+            columns: 2,
+            first_gid: 1,
+            image: PathBuf::from("tiles.png"),
+            image_height: 40,
+            image_width: 40,
+            margin: 2,
+            solid: HashSet::new(),
+            spacing: 4,
+            tile_count: 4,
+            tile_height: 16,
+            tile_width: 16,
+        };
+        let atlas = tileset_atlas(&tileset);
+        assert_eq!(atlas.len(), 4);
+        assert_eq!(
+            atlas.frame(0),
+            Some(TextureRegion::from_pixels(2, 2, 16, 16, 40, 40))
+        );
+        assert_eq!(
+            atlas.frame(3),
+            Some(TextureRegion::from_pixels(22, 22, 16, 16, 40, 40))
+        );
+    }
+}
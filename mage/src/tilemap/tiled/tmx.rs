@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::read_to_string;
+use std::path::Path;
+use std::str::FromStr;
+
+use nalgebra::Vector2;
+use roxmltree::{Document, Node};
+
+use crate::tilemap::tiled::{
+    decode_base64_gids, relative_path, LayerData, MapData, TiledError, TiledObject, TiledProperty,
+    TiledShape, TilesetData,
+};
+use crate::MageError;
+
+fn child<'a, 'input>(node: &Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn attribute<T: FromStr>(node: &Node, name: &str) -> Result<T, MageError> {
+    let value = node
+        .attribute(name)
+        .ok_or_else(|| TiledError::MissingAttribute(name.to_string()))?;
+    value
+        .parse()
+        .map_err(|_| TiledError::InvalidValue(name.to_string(), value.to_string()).into())
+}
+
+fn attribute_or<T: FromStr>(node: &Node, name: &str, default: T) -> Result<T, MageError> {
+    match node.attribute(name) {
+        Some(_) => attribute(node, name),
+        None => Ok(default),
+    }
+}
+
+fn properties(node: &Node) -> Result<HashMap<String, TiledProperty>, MageError> {
+    let mut properties = HashMap::new();
+    if let Some(node) = child(node, "properties") {
+        for property in node.children().filter(|n| n.has_tag_name("property")) {
+            let name = attribute::<String>(&property, "name")?;
+            let value = property
+                .attribute("value")
+                .or_else(|| property.text())
+                .unwrap_or_default();
+            let property_type = property.attribute("type").unwrap_or("string");
+            properties.insert(name, TiledProperty::parse(property_type, value)?);
+        }
+    }
+    Ok(properties)
+}
+
+fn points(node: &Node) -> Result<Vec<Vector2<f32>>, MageError> {
+    let points = node.attribute("points").unwrap_or_default();
+    points
+        .split_whitespace()
+        .map(|point| {
+            let invalid = || TiledError::InvalidValue("points".to_string(), point.to_string());
+            let (x, y) = point.split_once(',').ok_or_else(invalid)?;
+            Ok(Vector2::new(
+                x.parse().map_err(|_| invalid())?,
+                y.parse().map_err(|_| invalid())?,
+            ))
+        })
+        .collect()
+}
+
+fn object(node: &Node, layer: &str) -> Result<TiledObject, MageError> {
+    let shape = if let Some(gid) = node.attribute("gid") {
+        TiledShape::Tile(
+            gid.parse()
+                .map_err(|_| TiledError::InvalidValue("gid".to_string(), gid.to_string()))?,
+        )
+    } else if let Some(polygon) = child(node, "polygon") {
+        TiledShape::Polygon(points(&polygon)?)
+    } else if let Some(polyline) = child(node, "polyline") {
+        TiledShape::Polyline(points(&polyline)?)
+    } else if child(node, "ellipse").is_some() {
+        TiledShape::Ellipse
+    } else if child(node, "point").is_some() {
+        TiledShape::Point
+    } else {
+        TiledShape::Rectangle
+    };
+    let class = node
+        .attribute("class")
+        .or_else(|| node.attribute("type"))
+        .unwrap_or_default();
+    Ok(TiledObject {
+        class: class.to_string(),
+        height: attribute_or(node, "height", 0f32)?,
+        id: attribute(node, "id")?,
+        layer: layer.to_string(),
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        properties: properties(node)?,
+        rotation: attribute_or(node, "rotation", 0f32)?,
+        shape,
+        width: attribute_or(node, "width", 0f32)?,
+        x: attribute(node, "x")?,
+        y: attribute(node, "y")?,
+    })
+}
+
+fn tiles(node: &Node) -> Result<Vec<u32>, MageError> {
+    let data =
+        child(node, "data").ok_or_else(|| TiledError::MissingAttribute("data".to_string()))?;
+    match data.attribute("encoding") {
+        Some("csv") => data
+            .text()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|gid| !gid.is_empty())
+            .map(|gid| {
+                gid.parse().map_err(|_| {
+                    TiledError::InvalidValue("data".to_string(), gid.to_string()).into()
+                })
+            })
+            .collect(),
+        Some("base64") => decode_base64_gids(
+            data.text().unwrap_or_default(),
+            data.attribute("compression"),
+        ),
+        Some(encoding) => Err(TiledError::UnsupportedEncoding(encoding.to_string()).into()),
+        None => data
+            .children()
+            .filter(|n| n.has_tag_name("tile"))
+            .map(|tile| attribute_or(&tile, "gid", 0))
+            .collect(),
+    }
+}
+
+fn layers(node: &Node, data: &mut Vec<LayerData>) -> Result<(), MageError> {
+    for layer in node.children().filter(Node::is_element) {
+        let name = layer.attribute("name").unwrap_or_default();
+        match layer.tag_name().name() {
+            "layer" => data.push(LayerData::Tiles {
+                name: name.to_string(),
+                properties: properties(&layer)?,
+                tiles: tiles(&layer)?,
+                visible: attribute_or(&layer, "visible", 1u8)? != 0,
+            }),
+            "objectgroup" => data.push(LayerData::Objects {
+                objects: layer
+                    .children()
+                    .filter(|n| n.has_tag_name("object"))
+                    .map(|o| object(&o, name))
+                    .collect::<Result<Vec<_>, _>>()?,
+                properties: properties(&layer)?,
+            }),
+            "group" => self::layers(&layer, data)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn tileset(path: &Path, node: &Node, first_gid: u32) -> Result<TilesetData, MageError> {
+    if let Some(source) = node.attribute("source") {
+        let source = relative_path(path, source);
+        let text = read_to_string(&source)?;
+        let document = Document::parse(&text)?;
+        return self::tileset(&source, &document.root_element(), first_gid);
+    }
+    let name = node.attribute("name").unwrap_or_default();
+    let image =
+        child(node, "image").ok_or_else(|| TiledError::ImageCollection(name.to_string()))?;
+    let mut solid = HashSet::new();
+    for tile in node.children().filter(|n| n.has_tag_name("tile")) {
+        let solid_property = properties(&tile)?
+            .get("solid")
+            .and_then(TiledProperty::as_bool)
+            .unwrap_or(false);
+        if child(&tile, "objectgroup").is_some() || solid_property {
+            solid.insert(attribute(&tile, "id")?);
+        }
+    }
+    Ok(TilesetData {
+        columns: attribute(node, "columns")?,
+        first_gid,
+        image: relative_path(path, image.attribute("source").unwrap_or_default()),
+        image_height: attribute(&image, "height")?,
+        image_width: attribute(&image, "width")?,
+        margin: attribute_or(node, "margin", 0)?,
+        solid,
+        spacing: attribute_or(node, "spacing", 0)?,
+        tile_count: attribute(node, "tilecount")?,
+        tile_height: attribute(node, "tileheight")?,
+        tile_width: attribute(node, "tilewidth")?,
+    })
+}
+
+pub(crate) fn load_map(path: &Path) -> Result<MapData, MageError> {
+    let text = read_to_string(path)?;
+    let document = Document::parse(&text)?;
+    let map = document.root_element();
+    if attribute_or(&map, "infinite", 0u8)? != 0 {
+        return Err(TiledError::InfiniteMap.into());
+    }
+    let mut data = vec![];
+    layers(&map, &mut data)?;
+    let tilesets = map
+        .children()
+        .filter(|n| n.has_tag_name("tileset"))
+        .map(|t| tileset(path, &t, attribute(&t, "firstgid")?))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(MapData {
+        height: attribute(&map, "height")?,
+        layers: data,
+        tile_height: attribute(&map, "tileheight")?,
+        tile_width: attribute(&map, "tilewidth")?,
+        tilesets,
+        width: attribute(&map, "width")?,
+    })
+}
@@ -0,0 +1,129 @@
+use std::f32::consts::PI;
+
+const BACK_OVERSHOOT: f32 = 1.70158;
+const ELASTIC_PERIOD: f32 = 2f32 * PI / 3f32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Easing {
+    BackIn,
+    BackInOut,
+    BackOut,
+    BounceIn,
+    BounceInOut,
+    BounceOut,
+    CubicIn,
+    CubicInOut,
+    CubicOut,
+    ElasticIn,
+    ElasticInOut,
+    ElasticOut,
+    #[default]
+    Linear,
+    QuadIn,
+    QuadInOut,
+    QuadOut,
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1f32 / D {
+        N * t * t
+    } else if t < 2f32 / D {
+        let t = t - 1.5f32 / D;
+        N * t * t + 0.75
+    } else if t < 2.5f32 / D {
+        let t = t - 2.25f32 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625f32 / D;
+        N * t * t + 0.984375
+    }
+}
+
+fn elastic_out(t: f32) -> f32 {
+    if t <= 0f32 || t >= 1f32 {
+        return t.clamp(0f32, 1f32);
+    }
+    2f32.powf(-10f32 * t) * ((t * 10f32 - 0.75) * ELASTIC_PERIOD).sin() + 1f32
+}
+
+fn back_in(t: f32) -> f32 {
+    t * t * ((BACK_OVERSHOOT + 1f32) * t - BACK_OVERSHOOT)
+}
+
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        ease_in(t * 2f32) / 2f32
+    } else {
+        1f32 - ease_in((1f32 - t) * 2f32) / 2f32
+    }
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0f32, 1f32);
+        match self {
+            Easing::BackIn => back_in(t),
+            Easing::BackInOut => in_out(t, back_in),
+            Easing::BackOut => 1f32 - back_in(1f32 - t),
+            Easing::BounceIn => 1f32 - bounce_out(1f32 - t),
+            Easing::BounceInOut => in_out(t, |t| 1f32 - bounce_out(1f32 - t)),
+            Easing::BounceOut => bounce_out(t),
+            Easing::CubicIn => t * t * t,
+            Easing::CubicInOut => in_out(t, |t| t * t * t),
+            Easing::CubicOut => 1f32 - (1f32 - t).powi(3),
+            Easing::ElasticIn => 1f32 - elastic_out(1f32 - t),
+            Easing::ElasticInOut => in_out(t, |t| 1f32 - elastic_out(1f32 - t)),
+            Easing::ElasticOut => elastic_out(t),
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadInOut => in_out(t, |t| t * t),
+            Easing::QuadOut => 1f32 - (1f32 - t) * (1f32 - t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Easing;
+
+    const EASINGS: [Easing; 16] = [
+        Easing::BackIn,
+        Easing::BackInOut,
+        Easing::BackOut,
+        Easing::BounceIn,
+        Easing::BounceInOut,
+        Easing::BounceOut,
+        Easing::CubicIn,
+        Easing::CubicInOut,
+        Easing::CubicOut,
+        Easing::ElasticIn,
+        Easing::ElasticInOut,
+        Easing::ElasticOut,
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadInOut,
+        Easing::QuadOut,
+    ];
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for easing in EASINGS {
+            assert!(easing.apply(0f32).abs() < 1e-5, "{:?} at 0", easing);
+            assert!(
+                (easing.apply(1f32) - 1f32).abs() < 1e-5,
+                "{:?} at 1",
+                easing
+            );
+        }
+    }
+
+    #[test]
+    fn easings_clamp_progress() {
+        for easing in EASINGS {
+            assert_eq!(easing.apply(-1f32), easing.apply(0f32), "{:?}", easing);
+            assert_eq!(easing.apply(2f32), easing.apply(1f32), "{:?}", easing);
+        }
+    }
+}
@@ -0,0 +1,11 @@
+mod easing;
+pub use easing::Easing;
+
+mod track;
+pub use track::{ComponentSetter, Track, Tween, TweenProperty};
+
+mod tweener;
+pub use tweener::{Repeat, TweenEvent, Tweener};
+
+mod system;
+pub use system::TweenSystem;
@@ -0,0 +1,41 @@
+use hecs::World;
+
+use crate::core::system::System;
+use crate::core::time::time_scale;
+use crate::tween::Tweener;
+use crate::MageError;
+
+pub struct TweenSystem;
+
+impl System for TweenSystem {
+    fn name(&self) -> &str {
+        "Tween"
+    }
+
+    fn start(&self, _world: &mut World) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn early_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn update(&self, world: &mut World, delta_time: u64) -> Result<(), MageError> {
+        let time_scale = time_scale(world);
+        let tweeners = world
+            .query::<&Tweener>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for entity in tweeners {
+            world
+                .get_mut::<Tweener>(entity)?
+                .advance(world, entity, delta_time, time_scale);
+        }
+        Ok(())
+    }
+
+    fn late_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use hecs::{Component, Entity, World};
+use nalgebra::{UnitQuaternion, Vector3, Vector4};
+
+use crate::rendering::hierarchy::Parent;
+use crate::rendering::sprite::Sprite;
+use crate::rendering::Transform;
+use crate::tween::easing::Easing;
+
+type Setter = dyn Fn(&World, Entity, f32) + Send + Sync;
+
+#[derive(Clone)]
+pub struct ComponentSetter(Arc<Setter>);
+
+impl Debug for ComponentSetter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ComponentSetter")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum TweenProperty {
+    Component(ComponentSetter),
+    Position(Vector3<f32>),
+    Rotation(UnitQuaternion<f32>),
+    Scale(Vector3<f32>),
+    Tint(Vector4<f32>),
+}
+
+fn with_transform(world: &World, entity: Entity, update: impl FnOnce(&mut Transform)) {
+    if let Ok(mut parent) = world.get_mut::<Parent>(entity) {
+        update(&mut parent.local);
+    } else if let Ok(mut transform) = world.get_mut::<Transform>(entity) {
+        update(&mut transform);
+    }
+}
+
+fn current_transform(world: &World, entity: Entity) -> Option<Transform> {
+    match world.get::<Parent>(entity) {
+        Ok(parent) => Some(parent.local.clone()),
+        Err(_) => world
+            .get::<Transform>(entity)
+            .ok()
+            .map(|transform| (*transform).clone()),
+    }
+}
+
+impl TweenProperty {
+    fn current(&self, world: &World, entity: Entity) -> Option<TweenProperty> {
+        match self {
+            TweenProperty::Component(setter) => Some(TweenProperty::Component(setter.clone())),
+            TweenProperty::Position(_) => {
+                current_transform(world, entity).map(|t| TweenProperty::Position(t.position))
+            }
+            TweenProperty::Rotation(_) => {
+                current_transform(world, entity).map(|t| TweenProperty::Rotation(t.rotation))
+            }
+            TweenProperty::Scale(_) => {
+                current_transform(world, entity).map(|t| TweenProperty::Scale(t.scale))
+            }
+            TweenProperty::Tint(_) => world
+                .get::<Sprite>(entity)
+                .ok()
+                .map(|sprite| TweenProperty::Tint(sprite.tint)),
+        }
+    }
+
+    fn apply(&self, from: &TweenProperty, world: &World, entity: Entity, t: f32) {
+        match (from, self) {
+            (_, TweenProperty::Component(setter)) => (setter.0)(world, entity, t),
+            (TweenProperty::Position(from), TweenProperty::Position(to)) => {
+                with_transform(world, entity, |transform| {
+                    transform.position = from.lerp(to, t);
+                })
+            }
+            (TweenProperty::Rotation(from), TweenProperty::Rotation(to)) => {
+                with_transform(world, entity, |transform| {
+                    transform.rotation = from.try_slerp(to, t, f32::EPSILON).unwrap_or(*to);
+                })
+            }
+            (TweenProperty::Scale(from), TweenProperty::Scale(to)) => {
+                with_transform(world, entity, |transform| {
+                    transform.scale = from.lerp(to, t);
+                })
+            }
+            (TweenProperty::Tint(from), TweenProperty::Tint(to)) => {
+                if let Ok(mut sprite) = world.get_mut::<Sprite>(entity) {
+                    sprite.tint = from.lerp(to, t);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Track {
+    duration: u64,
+    easing: Easing,
+    from: Option<TweenProperty>,
+    property: TweenProperty,
+}
+
+impl Track {
+    fn apply(&mut self, world: &World, entity: Entity, time: f32) {
+        if self.from.is_none() {
+            self.from = self.property.current(world, entity);
+        }
+        let progress = if self.duration == 0 {
+            1f32
+        } else {
+            (time / self.duration as f32).clamp(0f32, 1f32)
+        };
+        if let Some(from) = &self.from {
+            self.property
+                .apply(from, world, entity, self.easing.apply(progress));
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Tween {
+    Delay(u64),
+    Parallel(Vec<Tween>),
+    Sequence(Vec<Tween>),
+    Track(Track),
+}
+
+impl Tween {
+    pub fn property(property: TweenProperty, duration: u64, easing: Easing) -> Tween {
+        Tween::Track(Track {
+            duration,
+            easing,
+            from: None,
+            property,
+        })
+    }
+
+    pub fn position(to: Vector3<f32>, duration: u64, easing: Easing) -> Tween {
+        Tween::property(TweenProperty::Position(to), duration, easing)
+    }
+
+    pub fn rotation(to: UnitQuaternion<f32>, duration: u64, easing: Easing) -> Tween {
+        Tween::property(TweenProperty::Rotation(to), duration, easing)
+    }
+
+    pub fn scale(to: Vector3<f32>, duration: u64, easing: Easing) -> Tween {
+        Tween::property(TweenProperty::Scale(to), duration, easing)
+    }
+
+    pub fn tint(to: Vector4<f32>, duration: u64, easing: Easing) -> Tween {
+        Tween::property(TweenProperty::Tint(to), duration, easing)
+    }
+
+    pub fn component<C, F>(duration: u64, easing: Easing, apply: F) -> Tween
+    where
+        C: Component,
+        F: Fn(&mut C, f32) + Send + Sync + 'static,
+    {
+        let setter = move |world: &World, entity: Entity, t: f32| {
+            if let Ok(mut component) = world.get_mut::<C>(entity) {
+                apply(&mut component, t);
+            }
+        };
+        Tween::property(
+            TweenProperty::Component(ComponentSetter(Arc::new(setter))),
+            duration,
+            easing,
+        )
+    }
+
+    pub fn delay(duration: u64) -> Tween {
+        Tween::Delay(duration)
+    }
+
+    pub fn sequence(tweens: Vec<Tween>) -> Tween {
+        Tween::Sequence(tweens)
+    }
+
+    pub fn parallel(tweens: Vec<Tween>) -> Tween {
+        Tween::Parallel(tweens)
+    }
+
+    pub fn then(self, next: Tween) -> Tween {
+        match self {
+            Tween::Sequence(mut tweens) => {
+                tweens.push(next);
+                Tween::Sequence(tweens)
+            }
+            tween => Tween::Sequence(vec![tween, next]),
+        }
+    }
+
+    pub fn with(self, other: Tween) -> Tween {
+        match self {
+            Tween::Parallel(mut tweens) => {
+                tweens.push(other);
+                Tween::Parallel(tweens)
+            }
+            tween => Tween::Parallel(vec![tween, other]),
+        }
+    }
+
+    pub fn duration(&self) -> u64 {
+        match self {
+            Tween::Delay(duration) => *duration,
+            Tween::Parallel(tweens) => tweens.iter().map(Tween::duration).max().unwrap_or(0),
+            Tween::Sequence(tweens) => tweens.iter().map(Tween::duration).sum(),
+            Tween::Track(track) => track.duration,
+        }
+    }
+
+    fn started(&self) -> bool {
+        match self {
+            Tween::Delay(_) => false,
+            Tween::Parallel(tweens) | Tween::Sequence(tweens) => tweens.iter().any(Tween::started),
+            Tween::Track(track) => track.from.is_some(),
+        }
+    }
+
+    pub(crate) fn apply(&mut self, world: &World, entity: Entity, time: f32) {
+        match self {
+            Tween::Delay(_) => {}
+            Tween::Parallel(tweens) => {
+                for tween in tweens.iter_mut() {
+                    tween.apply(world, entity, time);
+                }
+            }
+            Tween::Sequence(tweens) => {
+                let mut start = 0f32;
+                let starts = tweens
+                    .iter()
+                    .map(|tween| {
+                        let current = start;
+                        start += tween.duration() as f32;
+                        current
+                    })
+                    .collect::<Vec<_>>();
+                for (tween, start) in tweens.iter_mut().zip(starts.iter()).rev() {
+                    if *start > time && tween.started() {
+                        tween.apply(world, entity, 0f32);
+                    }
+                }
+                for (tween, start) in tweens.iter_mut().zip(starts.iter()) {
+                    if *start <= time {
+                        tween.apply(world, entity, time - start);
+                    }
+                }
+            }
+            Tween::Track(track) => track.apply(world, entity, time),
+        }
+    }
+}
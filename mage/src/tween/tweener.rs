@@ -0,0 +1,236 @@
+use hecs::{Entity, World};
+
+use crate::tween::track::Tween;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Repeat {
+    Count(u32),
+    Forever,
+    #[default]
+    Once,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TweenEvent {
+    Completed(String),
+    Looped(String),
+}
+
+#[derive(Clone, Debug)]
+struct ActiveTween {
+    elapsed: f32,
+    finished: bool,
+    forward: bool,
+    label: String,
+    remaining: Option<u32>,
+    tween: Tween,
+    yoyo: bool,
+}
+
+impl ActiveTween {
+    fn cycle_completed(&mut self, duration: f32, events: &mut Vec<TweenEvent>) {
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining = remaining.saturating_sub(1);
+        }
+        if self.remaining == Some(0) {
+            self.elapsed = if self.yoyo { 0f32 } else { duration };
+            self.finished = true;
+            events.push(TweenEvent::Completed(self.label.clone()));
+            return;
+        }
+        if self.yoyo {
+            self.forward = true;
+            self.elapsed = -self.elapsed;
+        } else {
+            self.elapsed -= duration;
+        }
+        events.push(TweenEvent::Looped(self.label.clone()));
+    }
+
+    fn advance(&mut self, world: &World, entity: Entity, delta: f32, events: &mut Vec<TweenEvent>) {
+        let duration = self.tween.duration() as f32;
+        if duration <= 0f32 {
+            self.tween.apply(world, entity, 0f32);
+            self.finished = true;
+            events.push(TweenEvent::Completed(self.label.clone()));
+            return;
+        }
+        if self.forward {
+            self.elapsed += delta;
+        } else {
+            self.elapsed -= delta;
+        }
+        while !self.finished {
+            if self.forward && self.elapsed >= duration {
+                if self.yoyo {
+                    self.forward = false;
+                    self.elapsed = 2f32 * duration - self.elapsed;
+                } else {
+                    self.cycle_completed(duration, events);
+                }
+            } else if !self.forward && self.elapsed <= 0f32 {
+                self.cycle_completed(duration, events);
+            } else {
+                break;
+            }
+        }
+        self.tween
+            .apply(world, entity, self.elapsed.clamp(0f32, duration));
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Tweener {
+    events: Vec<TweenEvent>,
+    pub paused: bool,
+    pub speed: f32,
+    tweens: Vec<ActiveTween>,
+}
+
+impl Tweener {
+    pub fn new() -> Tweener {
+        Tweener {
+            events: vec![],
+            paused: false,
+            speed: 1f32,
+            tweens: vec![],
+        }
+    }
+
+    pub fn play(&mut self, label: &str, tween: Tween) {
+        self.play_repeated(label, tween, Repeat::Once, false);
+    }
+
+    pub fn play_repeated(&mut self, label: &str, tween: Tween, repeat: Repeat, yoyo: bool) {
+        self.stop(label);
+        self.tweens.push(ActiveTween {
+            elapsed: 0f32,
+            finished: false,
+            forward: true,
+            label: label.to_string(),
+            remaining: match repeat {
+                Repeat::Count(count) => Some(count.max(1)),
+                Repeat::Forever => None,
+                Repeat::Once => Some(1),
+            },
+            tween,
+            yoyo,
+        });
+    }
+
+    pub fn stop(&mut self, label: &str) {
+        self.tweens.retain(|tween| tween.label != label);
+    }
+
+    pub fn stop_all(&mut self) {
+        self.tweens.clear();
+    }
+
+    pub fn is_playing(&self, label: &str) -> bool {
+        self.tweens.iter().any(|tween| tween.label == label)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.tweens.is_empty()
+    }
+
+    pub fn events(&self) -> &[TweenEvent] {
+        &self.events
+    }
+
+    pub(crate) fn advance(
+        &mut self,
+        world: &World,
+        entity: Entity,
+        delta_time: u64,
+        time_scale: f32,
+    ) {
+        self.events.clear();
+        if self.paused {
+            return;
+        }
+        let delta = delta_time as f32 * self.speed * time_scale;
+        for tween in self.tweens.iter_mut() {
+            tween.advance(world, entity, delta, &mut self.events);
+        }
+        self.tweens.retain(|tween| !tween.finished);
+    }
+}
+
+impl Default for Tweener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hecs::World;
+    use nalgebra::Vector3;
+
+    use super::{Repeat, TweenEvent, Tweener};
+    use crate::rendering::Transform;
+    use crate::tween::{Easing, Tween};
+
+    fn position_tween() -> Tween {
+        Tween::position(Vector3::new(10f32, 0f32, 0f32), 100, Easing::Linear)
+    }
+
+    fn position_x(world: &World, entity: hecs::Entity) -> f32 {
+        world.get::<Transform>(entity).unwrap().position.x
+    }
+
+    #[test]
+    fn loops_until_the_count_is_reached() {
+        let mut world = World::new();
+        let entity = world.spawn((Transform::identity(),));
+        let mut tweener = Tweener::new();
+        tweener.play_repeated("move", position_tween(), Repeat::Count(3), false);
+
+        tweener.advance(&world, entity, 250, 1f32);
+        assert_eq!(
+            tweener.events(),
+            [
+                TweenEvent::Looped("move".to_string()),
+                TweenEvent::Looped("move".to_string()),
+            ]
+        );
+        assert!((position_x(&world, entity) - 5f32).abs() < 1e-5);
+
+        tweener.advance(&world, entity, 50, 1f32);
+        assert_eq!(
+            tweener.events(),
+            [TweenEvent::Completed("move".to_string())]
+        );
+        assert!((position_x(&world, entity) - 10f32).abs() < 1e-5);
+        assert!(tweener.is_idle());
+    }
+
+    #[test]
+    fn yoyo_loops_after_returning_to_the_start() {
+        let mut world = World::new();
+        let entity = world.spawn((Transform::identity(),));
+        let mut tweener = Tweener::new();
+        tweener.play_repeated("move", position_tween(), Repeat::Count(2), true);
+
+        tweener.advance(&world, entity, 100, 1f32);
+        assert!(tweener.events().is_empty());
+        assert!((position_x(&world, entity) - 10f32).abs() < 1e-5);
+
+        tweener.advance(&world, entity, 50, 1f32);
+        assert!(tweener.events().is_empty());
+        assert!((position_x(&world, entity) - 5f32).abs() < 1e-5);
+
+        tweener.advance(&world, entity, 50, 1f32);
+        assert_eq!(tweener.events(), [TweenEvent::Looped("move".to_string())]);
+        assert!(position_x(&world, entity).abs() < 1e-5);
+
+        tweener.advance(&world, entity, 200, 1f32);
+        assert_eq!(
+            tweener.events(),
+            [TweenEvent::Completed("move".to_string())]
+        );
+        assert!(position_x(&world, entity).abs() < 1e-5);
+        assert!(tweener.is_idle());
+    }
+}
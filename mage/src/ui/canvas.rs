@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use hecs::Entity;
+use nalgebra::Vector2;
+use sdl2::controller::Button;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+
+use crate::rendering::text::Font;
+use crate::ui::{Rect, UiStyle};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Navigation {
+    Activate,
+    Cancel,
+    Down,
+    Left,
+    Next,
+    Previous,
+    Right,
+    Up,
+}
+
+fn key_navigation(keycode: Keycode) -> Option<Navigation> {
+    match keycode {
+        Keycode::Tab => Some(Navigation::Next),
+        Keycode::Up => Some(Navigation::Up),
+        Keycode::Down => Some(Navigation::Down),
+        Keycode::Left => Some(Navigation::Left),
+        Keycode::Right => Some(Navigation::Right),
+        Keycode::Return | Keycode::Space => Some(Navigation::Activate),
+        Keycode::Backspace => Some(Navigation::Cancel),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CanvasInput {
+    pub(crate) mouse: Vector2<f32>,
+    pub(crate) mouse_down: bool,
+    pub(crate) mouse_moved: bool,
+    pub(crate) mouse_pressed: bool,
+    pub(crate) mouse_released: bool,
+    pub(crate) navigation: Vec<Navigation>,
+}
+
+pub struct UiCanvas {
+    pub(crate) blocking: Vec<Rect>,
+    pub(crate) focused: Option<Entity>,
+    font: Arc<Font>,
+    pub(crate) hovered: Option<Entity>,
+    pub(crate) input: CanvasInput,
+    // Lets navigation focus the first widget when nothing is focused
+    pub interactive: bool,
+    pub(crate) pressed: Option<Entity>,
+    pub resolution: Option<Vector2<f32>>,
+    pub(crate) size: Vector2<f32>,
+    pub style: UiStyle,
+    pub(crate) viewport: Vector2<f32>,
+}
+
+impl UiCanvas {
+    pub fn new(font: Arc<Font>) -> UiCanvas {
+        UiCanvas::new_with_resolution(font, None)
+    }
+
+    pub fn new_with_resolution(font: Arc<Font>, resolution: Option<Vector2<f32>>) -> UiCanvas {
+        UiCanvas {
+            blocking: vec![],
+            focused: None,
+            font,
+            hovered: None,
+            input: CanvasInput::default(),
+            interactive: false,
+            pressed: None,
+            resolution,
+            size: resolution.unwrap_or_else(|| Vector2::new(1f32, 1f32)),
+            style: UiStyle::default(),
+            viewport: Vector2::new(1f32, 1f32),
+        }
+    }
+
+    pub fn font(&self) -> &Arc<Font> {
+        &self.font
+    }
+
+    pub fn focused(&self) -> Option<Entity> {
+        self.focused
+    }
+
+    pub fn focus(&mut self, entity: Option<Entity>) {
+        self.focused = entity;
+    }
+
+    pub fn hovered(&self) -> Option<Entity> {
+        self.hovered
+    }
+
+    pub fn size(&self) -> Vector2<f32> {
+        self.size
+    }
+
+    pub fn wants_mouse(&self) -> bool {
+        self.pressed.is_some() || self.blocking.iter().any(|r| r.contains(self.input.mouse))
+    }
+
+    pub fn wants_key(&self, event: &Event) -> bool {
+        match event {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => (self.focused.is_some() || self.interactive) && key_navigation(*keycode).is_some(),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn resize(&mut self, viewport: Vector2<f32>) {
+        self.viewport = viewport;
+        self.size = self.resolution.unwrap_or(viewport);
+    }
+
+    fn to_canvas(&self, x: i32, y: i32) -> Vector2<f32> {
+        Vector2::new(
+            x as f32 * self.size.x / self.viewport.x,
+            y as f32 * self.size.y / self.viewport.y,
+        )
+    }
+
+    pub(crate) fn handle_events(&mut self, events: &[Event]) {
+        self.input.mouse_moved = false;
+        self.input.mouse_pressed = false;
+        self.input.mouse_released = false;
+        self.input.navigation.clear();
+        for event in events {
+            match event {
+                Event::MouseMotion { x, y, .. } => {
+                    self.input.mouse = self.to_canvas(*x, *y);
+                    self.input.mouse_moved = true;
+                }
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } => {
+                    self.input.mouse = self.to_canvas(*x, *y);
+                    self.input.mouse_down = true;
+                    self.input.mouse_pressed = true;
+                }
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } => {
+                    self.input.mouse = self.to_canvas(*x, *y);
+                    self.input.mouse_down = false;
+                    self.input.mouse_released = true;
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } => {
+                    self.input.navigation.extend(key_navigation(*keycode));
+                }
+                Event::ControllerButtonDown { button, .. } => {
+                    let navigation = match button {
+                        Button::DPadUp => Some(Navigation::Up),
+                        Button::DPadDown => Some(Navigation::Down),
+                        Button::DPadLeft => Some(Navigation::Left),
+                        Button::DPadRight => Some(Navigation::Right),
+                        Button::A => Some(Navigation::Activate),
+                        Button::B => Some(Navigation::Cancel),
+                        Button::RightShoulder => Some(Navigation::Next),
+                        Button::LeftShoulder => Some(Navigation::Previous),
+                        _ => None,
+                    };
+                    self.input.navigation.extend(navigation);
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use nalgebra::{Vector2, Vector4};
+
+use crate::rendering::model::mesh::TextureInfo;
+use crate::rendering::sprite::TextureRegion;
+use crate::rendering::text::Font;
+
+pub(crate) const VERTEX_SIZE: usize = 9;
+pub(crate) const VERTICES_PER_QUAD: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub max: Vector2<f32>,
+    pub min: Vector2<f32>,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            max: Vector2::new(x + width, y + height),
+            min: Vector2::new(x, y),
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn contains(&self, point: Vector2<f32>) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DrawTexture {
+    Font,
+    Image(Box<TextureInfo>),
+}
+
+impl DrawTexture {
+    fn same(&self, other: &DrawTexture) -> bool {
+        match (self, other) {
+            (DrawTexture::Font, DrawTexture::Font) => true,
+            (DrawTexture::Image(a), DrawTexture::Image(b)) => a.source == b.source,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DrawBatch {
+    pub count: usize,
+    pub start: usize,
+    pub texture: DrawTexture,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DrawList {
+    batches: Vec<DrawBatch>,
+    vertices: Vec<f32>,
+}
+
+impl DrawList {
+    pub fn new() -> DrawList {
+        DrawList {
+            batches: vec![],
+            vertices: vec![],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.batches.clear();
+        self.vertices.clear();
+    }
+
+    pub fn batches(&self) -> &[DrawBatch] {
+        &self.batches
+    }
+
+    pub fn quads(&self) -> usize {
+        self.vertices.len() / (VERTEX_SIZE * VERTICES_PER_QUAD)
+    }
+
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    pub fn extend(&mut self, other: &DrawList) {
+        let quad_size = VERTEX_SIZE * VERTICES_PER_QUAD;
+        for batch in other.batches.iter() {
+            self.batch(&batch.texture, batch.count);
+            self.vertices.extend(
+                &other.vertices[batch.start * quad_size..(batch.start + batch.count) * quad_size],
+            );
+        }
+    }
+
+    fn batch(&mut self, texture: &DrawTexture, count: usize) {
+        let start = self.quads();
+        match self.batches.last_mut() {
+            Some(batch) if batch.texture.same(texture) => batch.count += count,
+            _ => self.batches.push(DrawBatch {
+                count,
+                start,
+                texture: texture.clone(),
+            }),
+        }
+    }
+
+    fn quad(
+        &mut self,
+        corners: [Vector2<f32>; 4],
+        uvs: [Vector2<f32>; 4],
+        color: Vector4<f32>,
+        texture: &DrawTexture,
+        textured: bool,
+    ) {
+        self.batch(texture, 1);
+        for (corner, uv) in corners.iter().zip(uvs.iter()) {
+            self.vertices.extend([
+                corner.x,
+                corner.y,
+                uv.x,
+                uv.y,
+                color.x,
+                color.y,
+                color.z,
+                color.w,
+                textured as i32 as f32,
+            ]);
+        }
+    }
+
+    pub fn rect(&mut self, rect: Rect, color: Vector4<f32>) -> usize {
+        let index = self.quads();
+        let zero = Vector2::new(0f32, 0f32);
+        self.quad(
+            [
+                Vector2::new(rect.min.x, rect.max.y),
+                rect.max,
+                Vector2::new(rect.max.x, rect.min.y),
+                rect.min,
+            ],
+            [zero; 4],
+            color,
+            &DrawTexture::Font,
+            false,
+        );
+        index
+    }
+
+    pub fn image(
+        &mut self,
+        rect: Rect,
+        texture: &TextureInfo,
+        region: TextureRegion,
+        tint: Vector4<f32>,
+    ) {
+        self.quad(
+            [
+                Vector2::new(rect.min.x, rect.max.y),
+                rect.max,
+                Vector2::new(rect.max.x, rect.min.y),
+                rect.min,
+            ],
+            [
+                region.min,
+                Vector2::new(region.max.x, region.min.y),
+                region.max,
+                Vector2::new(region.min.x, region.max.y),
+            ],
+            tint,
+            &DrawTexture::Image(Box::new(texture.clone())),
+            true,
+        );
+    }
+
+    pub fn set_rect(&mut self, index: usize, rect: Rect, color: Vector4<f32>) {
+        let mut replacement = DrawList::new();
+        replacement.rect(rect, color);
+        let start = index * VERTEX_SIZE * VERTICES_PER_QUAD;
+        self.vertices[start..start + replacement.vertices.len()]
+            .copy_from_slice(&replacement.vertices);
+    }
+
+    pub fn outline(&mut self, rect: Rect, thickness: f32, color: Vector4<f32>) {
+        let Rect { max, min } = rect;
+        self.rect(Rect::new(min.x, min.y, rect.width(), thickness), color);
+        self.rect(
+            Rect::new(min.x, max.y - thickness, rect.width(), thickness),
+            color,
+        );
+        self.rect(Rect::new(min.x, min.y, thickness, rect.height()), color);
+        self.rect(
+            Rect::new(max.x - thickness, min.y, thickness, rect.height()),
+            color,
+        );
+    }
+
+    pub fn line(
+        &mut self,
+        from: Vector2<f32>,
+        to: Vector2<f32>,
+        thickness: f32,
+        color: Vector4<f32>,
+    ) {
+        let direction = to - from;
+        if direction.norm() == 0f32 {
+            return;
+        }
+        let normal = Vector2::new(-direction.y, direction.x).normalize() * thickness / 2f32;
+        let zero = Vector2::new(0f32, 0f32);
+        self.quad(
+            [from + normal, to + normal, to - normal, from - normal],
+            [zero; 4],
+            color,
+            &DrawTexture::Font,
+            false,
+        );
+    }
+
+    pub fn text(
+        &mut self,
+        font: &Arc<Font>,
+        position: Vector2<f32>,
+        text: &str,
+        size: f32,
+        color: Vector4<f32>,
+    ) -> f32 {
+        let scale = size / font.size();
+        let baseline = position.y + font.ascent() * scale;
+        let mut x = position.x;
+        let mut previous = None;
+        for c in text.chars() {
+            if let Some(previous) = previous {
+                x += font.kerning(previous, c) * scale;
+            }
+            previous = Some(c);
+            let glyph = match font.glyph(c) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            let left = x + glyph.offset.x * scale;
+            let bottom = baseline - glyph.offset.y * scale;
+            let right = left + glyph.size.x * scale;
+            let top = bottom - glyph.size.y * scale;
+            let region = glyph.region;
+            self.quad(
+                [
+                    Vector2::new(left, bottom),
+                    Vector2::new(right, bottom),
+                    Vector2::new(right, top),
+                    Vector2::new(left, top),
+                ],
+                [
+                    region.min,
+                    Vector2::new(region.max.x, region.min.y),
+                    region.max,
+                    Vector2::new(region.min.x, region.max.y),
+                ],
+                color,
+                &DrawTexture::Font,
+                true,
+            );
+            x += glyph.advance * scale;
+        }
+        x - position.x
+    }
+}
+
+pub fn text_width(font: &Font, text: &str, size: f32) -> f32 {
+    let mut width = 0f32;
+    let mut previous = None;
+    for c in text.chars() {
+        if let Some(previous) = previous {
+            width += font.kerning(previous, c);
+        }
+        width += font.glyph(c).map(|glyph| glyph.advance).unwrap_or(0f32);
+        previous = Some(c);
+    }
+    width * size / font.size()
+}
@@ -0,0 +1,265 @@
+use std::collections::{HashMap, HashSet};
+
+use hecs::{Entity, World};
+use nalgebra::Vector2;
+
+use crate::ui::node::{Align, Direction, Justify, UiNode, UiSize, Widget};
+use crate::ui::{text_width, Rect, UiCanvas};
+
+pub(crate) const WIDGET_PADDING: f32 = 6f32;
+pub(crate) const DEFAULT_WIDTH: f32 = 160f32;
+pub(crate) const BAR_HEIGHT: f32 = 16f32;
+pub(crate) const IMAGE_SIZE: f32 = 64f32;
+
+struct Layout<'a> {
+    canvas: &'a UiCanvas,
+    rects: HashMap<Entity, Rect>,
+    world: &'a World,
+}
+
+pub(crate) fn roots(world: &World) -> Vec<Entity> {
+    let children = world
+        .query::<&UiNode>()
+        .iter()
+        .flat_map(|(_e, node)| node.children.clone())
+        .collect::<HashSet<_>>();
+    let mut roots = world
+        .query::<&UiNode>()
+        .iter()
+        .map(|(e, _node)| e)
+        .filter(|e| !children.contains(e))
+        .collect::<Vec<_>>();
+    roots.sort_by_key(|e| e.id());
+    roots
+}
+
+pub(crate) fn line_height(canvas: &UiCanvas) -> f32 {
+    canvas.style.text_size * canvas.font().line_height() / canvas.font().size()
+}
+
+pub(crate) fn layout(world: &World, canvas: &UiCanvas) -> HashMap<Entity, Rect> {
+    let mut layout = Layout {
+        canvas,
+        rects: HashMap::new(),
+        world,
+    };
+    let screen = Rect::new(0f32, 0f32, canvas.size.x, canvas.size.y);
+    for root in roots(world) {
+        if let Some(size) = layout.size(root, screen.max - screen.min) {
+            let rect = layout.anchored(root, screen, size);
+            layout.place(root, rect);
+        }
+    }
+    layout.rects
+}
+
+impl<'a> Layout<'a> {
+    fn node(&self, entity: Entity) -> Option<UiNode> {
+        self.world
+            .get::<UiNode>(entity)
+            .ok()
+            .filter(|node| node.visible)
+            .map(|node| (*node).clone())
+    }
+
+    fn widget_size(&self, entity: Entity) -> Vector2<f32> {
+        let widget = match self.world.get::<Widget>(entity) {
+            Ok(widget) => widget,
+            Err(_) => return Vector2::new(0f32, 0f32),
+        };
+        let font = self.canvas.font();
+        let text_size = self.canvas.style.text_size;
+        let line = line_height(self.canvas);
+        match &*widget {
+            Widget::Button(text) | Widget::Label(text) => Vector2::new(
+                text_width(font, text, text_size) + 2f32 * WIDGET_PADDING,
+                line + 2f32 * WIDGET_PADDING,
+            ),
+            Widget::Image { .. } => Vector2::new(IMAGE_SIZE, IMAGE_SIZE),
+            Widget::List { items, .. } => Vector2::new(
+                items
+                    .iter()
+                    .map(|item| text_width(font, item, text_size))
+                    .fold(DEFAULT_WIDTH, f32::max)
+                    + 2f32 * WIDGET_PADDING,
+                items.len() as f32 * (line + WIDGET_PADDING) + WIDGET_PADDING,
+            ),
+            Widget::ProgressBar(_) | Widget::Slider { .. } => {
+                Vector2::new(DEFAULT_WIDTH, BAR_HEIGHT.max(line))
+            }
+        }
+    }
+
+    fn content_size(&self, node: &UiNode, entity: Entity) -> Vector2<f32> {
+        let mut size = Vector2::new(0f32, 0f32);
+        let mut count = 0;
+        for child in node.children.iter() {
+            let child_node = match self.node(*child) {
+                Some(child_node) => child_node,
+                None => continue,
+            };
+            let child_size = self
+                .size(*child, Vector2::new(0f32, 0f32))
+                .unwrap_or_default()
+                + Vector2::new(child_node.margin.horizontal(), child_node.margin.vertical());
+            match node.direction {
+                Direction::Column => {
+                    size.x = size.x.max(child_size.x);
+                    size.y += child_size.y;
+                }
+                Direction::Row => {
+                    size.x += child_size.x;
+                    size.y = size.y.max(child_size.y);
+                }
+                Direction::Overlay => {
+                    size.x = size.x.max(child_size.x);
+                    size.y = size.y.max(child_size.y);
+                }
+            }
+            count += 1;
+        }
+        let gaps = node.spacing * (count as f32 - 1f32).max(0f32);
+        match node.direction {
+            Direction::Column => size.y += gaps,
+            Direction::Row => size.x += gaps,
+            Direction::Overlay => {}
+        }
+        let widget = self.widget_size(entity);
+        Vector2::new(size.x.max(widget.x), size.y.max(widget.y))
+    }
+
+    fn size(&self, entity: Entity, available: Vector2<f32>) -> Option<Vector2<f32>> {
+        let node = self.node(entity)?;
+        let content = || {
+            self.content_size(&node, entity)
+                + Vector2::new(node.padding.horizontal(), node.padding.vertical())
+        };
+        let width = match node.width {
+            UiSize::Auto => content().x,
+            UiSize::Percent(percent) => (available.x - node.margin.horizontal()) * percent / 100f32,
+            UiSize::Pixels(pixels) => pixels,
+        };
+        let height = match node.height {
+            UiSize::Auto => content().y,
+            UiSize::Percent(percent) => (available.y - node.margin.vertical()) * percent / 100f32,
+            UiSize::Pixels(pixels) => pixels,
+        };
+        Some(Vector2::new(width, height))
+    }
+
+    fn anchored(&self, entity: Entity, parent: Rect, size: Vector2<f32>) -> Rect {
+        let node = match self.node(entity) {
+            Some(node) => node,
+            None => return Rect::new(parent.min.x, parent.min.y, size.x, size.y),
+        };
+        let factors = node.anchor.factors();
+        let min = parent.min + Vector2::new(node.margin.left, node.margin.top);
+        let max = parent.max - Vector2::new(node.margin.right, node.margin.bottom);
+        let free = max - min - size;
+        Rect::new(
+            min.x + free.x * factors.x,
+            min.y + free.y * factors.y,
+            size.x,
+            size.y,
+        )
+    }
+
+    fn place(&mut self, entity: Entity, rect: Rect) {
+        self.rects.insert(entity, rect);
+        let node = match self.node(entity) {
+            Some(node) => node,
+            None => return,
+        };
+        let content = Rect {
+            max: rect.max - Vector2::new(node.padding.right, node.padding.bottom),
+            min: rect.min + Vector2::new(node.padding.left, node.padding.top),
+        };
+        let available = content.max - content.min;
+        let children = node
+            .children
+            .iter()
+            .filter_map(|child| {
+                let child_node = self.node(*child)?;
+                let size = self.size(*child, available)?;
+                Some((*child, child_node, size))
+            })
+            .collect::<Vec<_>>();
+        if node.direction == Direction::Overlay {
+            for (child, _child_node, size) in children {
+                let rect = self.anchored(child, content, size);
+                self.place(child, rect);
+            }
+            return;
+        }
+
+        let column = node.direction == Direction::Column;
+        let main = |v: Vector2<f32>| if column { v.y } else { v.x };
+        let cross = |v: Vector2<f32>| if column { v.x } else { v.y };
+        let used = children
+            .iter()
+            .map(|(_child, child_node, size)| {
+                let margin = if column {
+                    child_node.margin.vertical()
+                } else {
+                    child_node.margin.horizontal()
+                };
+                main(*size) + margin
+            })
+            .sum::<f32>()
+            + node.spacing * (children.len() as f32 - 1f32).max(0f32);
+        let free = (main(available) - used).max(0f32);
+        let (mut offset, gap) = match node.justify {
+            Justify::Start => (0f32, node.spacing),
+            Justify::Center => (free / 2f32, node.spacing),
+            Justify::End => (free, node.spacing),
+            Justify::SpaceBetween if children.len() > 1 => {
+                (0f32, node.spacing + free / (children.len() - 1) as f32)
+            }
+            Justify::SpaceBetween => (0f32, node.spacing),
+        };
+        for (child, child_node, size) in children {
+            let margin = child_node.margin;
+            let (main_before, main_after, cross_before, cross_after) = if column {
+                (margin.top, margin.bottom, margin.left, margin.right)
+            } else {
+                (margin.left, margin.right, margin.top, margin.bottom)
+            };
+            let cross_available = cross(available) - cross_before - cross_after;
+            let stretch = node.align == Align::Stretch
+                && if column {
+                    child_node.width == UiSize::Auto
+                } else {
+                    child_node.height == UiSize::Auto
+                };
+            let cross_size = if stretch {
+                cross_available
+            } else {
+                cross(size)
+            };
+            let cross_offset = cross_before
+                + match node.align {
+                    Align::Start | Align::Stretch => 0f32,
+                    Align::Center => (cross_available - cross_size) / 2f32,
+                    Align::End => cross_available - cross_size,
+                };
+            offset += main_before;
+            let child_rect = if column {
+                Rect::new(
+                    content.min.x + cross_offset,
+                    content.min.y + offset,
+                    cross_size,
+                    size.y,
+                )
+            } else {
+                Rect::new(
+                    content.min.x + offset,
+                    content.min.y + cross_offset,
+                    size.x,
+                    cross_size,
+                )
+            };
+            offset += main(size) + main_after + gap;
+            self.place(child, child_rect);
+        }
+    }
+}
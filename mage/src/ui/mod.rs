@@ -0,0 +1,355 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use nalgebra::{Vector2, Vector4};
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+
+use crate::rendering::text::Font;
+
+const WINDOW_WIDTH: f32 = 260f32;
+const TITLE_HEIGHT: f32 = 22f32;
+const PADDING: f32 = 6f32;
+const SPACING: f32 = 4f32;
+const ROW_HEIGHT: f32 = 20f32;
+const TEXT_SIZE: f32 = 14f32;
+
+#[derive(Clone, Copy, Debug)]
+pub struct UiStyle {
+    pub active: Vector4<f32>,
+    pub background: Vector4<f32>,
+    pub hovered: Vector4<f32>,
+    pub text: Vector4<f32>,
+    pub text_size: f32,
+    pub title: Vector4<f32>,
+    pub widget: Vector4<f32>,
+}
+
+impl Default for UiStyle {
+    fn default() -> Self {
+        UiStyle {
+            active: Vector4::new(0.35, 0.55, 0.85, 1.0),
+            background: Vector4::new(0.1, 0.1, 0.12, 0.9),
+            hovered: Vector4::new(0.3, 0.3, 0.36, 1.0),
+            text: Vector4::new(0.95, 0.95, 0.95, 1.0),
+            text_size: TEXT_SIZE,
+            title: Vector4::new(0.2, 0.3, 0.5, 1.0),
+            widget: Vector4::new(0.22, 0.22, 0.27, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct UiInput {
+    characters: String,
+    keys: Vec<Keycode>,
+    mouse: Vector2<f32>,
+    mouse_delta: Vector2<f32>,
+    mouse_down: bool,
+    mouse_pressed: bool,
+    mouse_released: bool,
+}
+
+struct UiWindow {
+    background: usize,
+    collapsed: bool,
+    draw_list: DrawList,
+    position: Vector2<f32>,
+    rect: Rect,
+    visible: bool,
+}
+
+pub struct Ui {
+    active: Option<u64>,
+    current: Option<String>,
+    cursor: Vector2<f32>,
+    focused: Option<u64>,
+    font: Arc<Font>,
+    hovered_window: Option<String>,
+    input: UiInput,
+    open: HashSet<u64>,
+    order: Vec<String>,
+    pub style: UiStyle,
+    windows: HashMap<String, UiWindow>,
+}
+
+impl Ui {
+    pub fn new(font: Arc<Font>) -> Ui {
+        Ui::new_with_style(font, UiStyle::default())
+    }
+
+    pub fn new_with_style(font: Arc<Font>, style: UiStyle) -> Ui {
+        Ui {
+            active: None,
+            current: None,
+            cursor: Vector2::new(0f32, 0f32),
+            focused: None,
+            font,
+            hovered_window: None,
+            input: UiInput::default(),
+            open: HashSet::new(),
+            order: vec![],
+            style,
+            windows: HashMap::new(),
+        }
+    }
+
+    pub fn font(&self) -> &Arc<Font> {
+        &self.font
+    }
+
+    pub fn wants_mouse(&self) -> bool {
+        self.active.is_some() || self.hovered_window.is_some()
+    }
+
+    pub fn wants_keyboard(&self) -> bool {
+        self.focused.is_some()
+    }
+
+    pub(crate) fn handle_events(&mut self, events: &[Event]) {
+        let previous = self.input.mouse;
+        self.input.characters.clear();
+        self.input.keys.clear();
+        self.input.mouse_pressed = false;
+        self.input.mouse_released = false;
+        for event in events {
+            match event {
+                Event::MouseMotion { x, y, .. } => {
+                    self.input.mouse = Vector2::new(*x as f32, *y as f32);
+                }
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } => {
+                    self.input.mouse = Vector2::new(*x as f32, *y as f32);
+                    self.input.mouse_down = true;
+                    self.input.mouse_pressed = true;
+                }
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } => {
+                    self.input.mouse = Vector2::new(*x as f32, *y as f32);
+                    self.input.mouse_down = false;
+                    self.input.mouse_released = true;
+                }
+                Event::TextInput { text, .. } => self.input.characters.push_str(text),
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } => self.input.keys.push(*keycode),
+                _ => {}
+            }
+        }
+        self.input.mouse_delta = self.input.mouse - previous;
+        self.hovered_window = self
+            .order
+            .iter()
+            .rev()
+            .find(|title| {
+                self.windows
+                    .get(*title)
+                    .map(|window| window.visible && window.rect.contains(self.input.mouse))
+                    .unwrap_or(false)
+            })
+            .cloned();
+        if self.input.mouse_pressed {
+            if let Some(title) = &self.hovered_window {
+                self.order.retain(|t| t != title);
+                self.order.push(title.clone());
+            }
+            self.focused = None;
+        }
+        for window in self.windows.values_mut() {
+            window.visible = false;
+            window.draw_list.clear();
+        }
+    }
+
+    pub(crate) fn draw_list(&self) -> DrawList {
+        let mut draw_list = DrawList::new();
+        for title in self.order.iter() {
+            if let Some(window) = self.windows.get(title) {
+                if window.visible {
+                    draw_list.extend(&window.draw_list);
+                }
+            }
+        }
+        draw_list
+    }
+
+    pub(crate) fn end_frame(&mut self) {
+        if !self.input.mouse_down {
+            self.active = None;
+        }
+    }
+
+    fn id(&self, label: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.current.hash(&mut hasher);
+        label.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn window_hovered(&self) -> bool {
+        self.hovered_window.is_some() && self.hovered_window == self.current
+    }
+
+    fn hovered(&self, rect: Rect) -> bool {
+        self.window_hovered() && rect.contains(self.input.mouse)
+    }
+
+    fn interact(&mut self, id: u64, rect: Rect) -> (bool, bool) {
+        let hovered = self.hovered(rect);
+        if hovered && self.input.mouse_pressed && self.active.is_none() {
+            self.active = Some(id);
+        }
+        (hovered, self.active == Some(id))
+    }
+
+    fn draw(&mut self) -> &mut DrawList {
+        let title = self
+            .current
+            .as_ref()
+            .expect("Widgets must be inside a window");
+        &mut self.windows.get_mut(title).unwrap().draw_list
+    }
+
+    fn content_width(&self) -> f32 {
+        let left = self
+            .current
+            .as_ref()
+            .and_then(|title| self.windows.get(title))
+            .map(|window| window.position.x)
+            .unwrap_or(0f32);
+        left + WINDOW_WIDTH - PADDING - self.cursor.x
+    }
+
+    fn row(&mut self, height: f32) -> Rect {
+        let rect = Rect::new(self.cursor.x, self.cursor.y, self.content_width(), height);
+        self.cursor.y += height + SPACING;
+        rect
+    }
+
+    fn text(&mut self, position: Vector2<f32>, text: &str, color: Vector4<f32>) {
+        let font = self.font.clone();
+        let size = self.style.text_size;
+        let offset = (ROW_HEIGHT - size * font.line_height() / font.size()) / 2f32;
+        self.draw().text(
+            &font,
+            Vector2::new(position.x, position.y + offset),
+            text,
+            size,
+            color,
+        );
+    }
+
+    fn text_width(&self, text: &str) -> f32 {
+        text_width(&self.font, text, self.style.text_size)
+    }
+
+    pub fn window<F: FnOnce(&mut Ui)>(&mut self, title: &str, contents: F) {
+        let index = self.windows.len();
+        let window = self
+            .windows
+            .entry(title.to_string())
+            .or_insert_with(|| UiWindow {
+                background: 0,
+                collapsed: false,
+                draw_list: DrawList::new(),
+                position: Vector2::new(20f32 + 30f32 * index as f32, 20f32 + 30f32 * index as f32),
+                rect: Rect::new(0f32, 0f32, 0f32, 0f32),
+                visible: false,
+            });
+        if window.visible {
+            return;
+        }
+        window.visible = true;
+        if !self.order.iter().any(|t| t == title) {
+            self.order.push(title.to_string());
+        }
+        self.current = Some(title.to_string());
+
+        let id = self.id("#title");
+        let position = self.windows[title].position;
+        let title_rect = Rect::new(position.x, position.y, WINDOW_WIDTH, TITLE_HEIGHT);
+        let toggle_rect = Rect::new(position.x, position.y, TITLE_HEIGHT, TITLE_HEIGHT);
+        let (hovered, active) = self.interact(id, title_rect);
+        if hovered && self.input.mouse_pressed && toggle_rect.contains(self.input.mouse) {
+            let window = self.windows.get_mut(title).unwrap();
+            window.collapsed = !window.collapsed;
+        } else if active {
+            let delta = self.input.mouse_delta;
+            self.windows.get_mut(title).unwrap().position += delta;
+        }
+        let window = &self.windows[title];
+        let position = window.position;
+        let collapsed = window.collapsed;
+
+        let background = self.style.background;
+        let title_color = self.style.title;
+        let text_color = self.style.text;
+        let draw = self.draw();
+        let background = draw.rect(Rect::new(position.x, position.y, 0f32, 0f32), background);
+        draw.rect(
+            Rect::new(position.x, position.y, WINDOW_WIDTH, TITLE_HEIGHT),
+            title_color,
+        );
+        self.windows.get_mut(title).unwrap().background = background;
+        self.text(
+            Vector2::new(position.x + PADDING, position.y + 1f32),
+            &format!("{} {}", if collapsed { "+" } else { "-" }, title),
+            text_color,
+        );
+
+        self.cursor = Vector2::new(position.x + PADDING, position.y + TITLE_HEIGHT + PADDING);
+        if !collapsed {
+            contents(self);
+        }
+
+        let height = if collapsed {
+            TITLE_HEIGHT
+        } else {
+            self.cursor.y - position.y - SPACING + PADDING
+        };
+        let rect = Rect::new(position.x, position.y, WINDOW_WIDTH, height);
+        let background = self.style.background;
+        let window = self.windows.get_mut(title).unwrap();
+        window.rect = rect;
+        window
+            .draw_list
+            .set_rect(window.background, rect, background);
+        self.current = None;
+    }
+}
+
+mod canvas;
+pub use canvas::{Navigation, UiCanvas};
+
+mod draw;
+pub use draw::{text_width, DrawBatch, DrawList, DrawTexture, Rect};
+
+mod layout;
+
+mod node;
+pub use node::{
+    Align, Anchor, Direction, Edges, Justify, UiEvent, UiEvents, UiNode, UiSize, Widget,
+};
+
+mod paint;
+pub(crate) use paint::paint;
+
+mod renderer;
+pub use renderer::UiRenderer;
+
+mod system;
+pub use system::UiSystem;
+
+mod widgets;
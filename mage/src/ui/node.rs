@@ -0,0 +1,215 @@
+use hecs::Entity;
+use nalgebra::{Vector2, Vector4};
+
+use crate::rendering::model::mesh::TextureInfo;
+use crate::rendering::sprite::TextureRegion;
+use crate::ui::Rect;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum UiSize {
+    #[default]
+    Auto,
+    Percent(f32),
+    Pixels(f32),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Edges {
+    pub bottom: f32,
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+}
+
+impl Edges {
+    pub fn all(value: f32) -> Edges {
+        Edges::new(value, value, value, value)
+    }
+
+    pub fn new(top: f32, right: f32, bottom: f32, left: f32) -> Edges {
+        Edges {
+            bottom,
+            left,
+            right,
+            top,
+        }
+    }
+
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    pub(crate) fn factors(&self) -> Vector2<f32> {
+        match self {
+            Anchor::TopLeft => Vector2::new(0f32, 0f32),
+            Anchor::Top => Vector2::new(0.5f32, 0f32),
+            Anchor::TopRight => Vector2::new(1f32, 0f32),
+            Anchor::Left => Vector2::new(0f32, 0.5f32),
+            Anchor::Center => Vector2::new(0.5f32, 0.5f32),
+            Anchor::Right => Vector2::new(1f32, 0.5f32),
+            Anchor::BottomLeft => Vector2::new(0f32, 1f32),
+            Anchor::Bottom => Vector2::new(0.5f32, 1f32),
+            Anchor::BottomRight => Vector2::new(1f32, 1f32),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Direction {
+    #[default]
+    Column,
+    Overlay,
+    Row,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Align {
+    Center,
+    End,
+    #[default]
+    Start,
+    Stretch,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Justify {
+    Center,
+    End,
+    SpaceBetween,
+    #[default]
+    Start,
+}
+
+#[derive(Clone, Debug)]
+pub struct UiNode {
+    pub align: Align,
+    pub anchor: Anchor,
+    pub background: Option<Vector4<f32>>,
+    pub children: Vec<Entity>,
+    pub direction: Direction,
+    pub height: UiSize,
+    pub justify: Justify,
+    pub margin: Edges,
+    pub padding: Edges,
+    pub(crate) rect: Rect,
+    pub spacing: f32,
+    pub visible: bool,
+    pub width: UiSize,
+}
+
+impl UiNode {
+    pub fn new(width: UiSize, height: UiSize) -> UiNode {
+        UiNode {
+            align: Align::Start,
+            anchor: Anchor::TopLeft,
+            background: None,
+            children: vec![],
+            direction: Direction::Column,
+            height,
+            justify: Justify::Start,
+            margin: Edges::default(),
+            padding: Edges::default(),
+            rect: Rect::new(0f32, 0f32, 0f32, 0f32),
+            spacing: 0f32,
+            visible: true,
+            width,
+        }
+    }
+
+    pub fn auto() -> UiNode {
+        UiNode::new(UiSize::Auto, UiSize::Auto)
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Widget {
+    Button(String),
+    Image {
+        region: TextureRegion,
+        texture: Box<TextureInfo>,
+        tint: Vector4<f32>,
+    },
+    Label(String),
+    List {
+        items: Vec<String>,
+        selected: usize,
+    },
+    ProgressBar(f32),
+    Slider {
+        max: f32,
+        min: f32,
+        step: f32,
+        value: f32,
+    },
+}
+
+impl Widget {
+    pub fn image(texture: TextureInfo) -> Widget {
+        Widget::Image {
+            region: TextureRegion::full(),
+            texture: Box::new(texture),
+            tint: Vector4::new(1f32, 1f32, 1f32, 1f32),
+        }
+    }
+
+    pub fn list(items: &[&str]) -> Widget {
+        Widget::List {
+            items: items.iter().map(|item| item.to_string()).collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn slider(min: f32, max: f32, value: f32) -> Widget {
+        Widget::Slider {
+            max,
+            min,
+            step: (max - min) / 10f32,
+            value,
+        }
+    }
+
+    pub fn focusable(&self) -> bool {
+        matches!(
+            self,
+            Widget::Button(_) | Widget::List { .. } | Widget::Slider { .. }
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UiEvent {
+    Changed(f32),
+    Clicked,
+    FocusGained,
+    FocusLost,
+    HoverEntered,
+    HoverExited,
+    Selected(usize),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UiEvents(pub Vec<UiEvent>);
@@ -0,0 +1,122 @@
+use hecs::World;
+use nalgebra::Vector2;
+
+use crate::ui::layout::{line_height, WIDGET_PADDING};
+use crate::ui::node::{UiNode, Widget};
+use crate::ui::system::draw_order;
+use crate::ui::{text_width, DrawList, Rect, UiCanvas};
+
+const FOCUS_THICKNESS: f32 = 2f32;
+const HANDLE_WIDTH: f32 = 8f32;
+
+pub(crate) fn paint(world: &World, canvas: &UiCanvas) -> DrawList {
+    let mut draw_list = DrawList::new();
+    let style = canvas.style;
+    let font = canvas.font();
+    let line = line_height(canvas);
+    let text = |draw_list: &mut DrawList, x: f32, rect: Rect, content: &str| {
+        let y = rect.min.y + (rect.height() - line) / 2f32;
+        draw_list.text(
+            font,
+            Vector2::new(x, y),
+            content,
+            style.text_size,
+            style.text,
+        );
+    };
+    for (e, rect) in draw_order(world) {
+        if let Ok(node) = world.get::<UiNode>(e) {
+            if let Some(background) = node.background {
+                draw_list.rect(rect, background);
+            }
+        }
+        let widget = match world.get::<Widget>(e) {
+            Ok(widget) => widget,
+            Err(_) => continue,
+        };
+        let hovered = canvas.hovered == Some(e);
+        let pressed = canvas.pressed == Some(e);
+        let color = if pressed {
+            style.active
+        } else if hovered {
+            style.hovered
+        } else {
+            style.widget
+        };
+        match &*widget {
+            Widget::Button(label) => {
+                draw_list.rect(rect, color);
+                let width = text_width(font, label, style.text_size);
+                text(
+                    &mut draw_list,
+                    rect.min.x + (rect.width() - width) / 2f32,
+                    rect,
+                    label,
+                );
+            }
+            Widget::Image {
+                region,
+                texture,
+                tint,
+            } => draw_list.image(rect, texture, *region, *tint),
+            Widget::Label(label) => text(&mut draw_list, rect.min.x + WIDGET_PADDING, rect, label),
+            Widget::List { items, selected } => {
+                draw_list.rect(rect, style.widget);
+                let row = line + WIDGET_PADDING;
+                for (i, item) in items.iter().enumerate() {
+                    let item_rect = Rect::new(
+                        rect.min.x,
+                        rect.min.y + WIDGET_PADDING / 2f32 + i as f32 * row,
+                        rect.width(),
+                        row,
+                    );
+                    if i == *selected {
+                        draw_list.rect(item_rect, style.active);
+                    }
+                    text(&mut draw_list, rect.min.x + WIDGET_PADDING, item_rect, item);
+                }
+            }
+            Widget::ProgressBar(value) => {
+                draw_list.rect(rect, style.widget);
+                draw_list.rect(
+                    Rect::new(
+                        rect.min.x,
+                        rect.min.y,
+                        rect.width() * value.clamp(0f32, 1f32),
+                        rect.height(),
+                    ),
+                    style.active,
+                );
+            }
+            Widget::Slider {
+                max, min, value, ..
+            } => {
+                let t = ((value - min) / (max - min)).clamp(0f32, 1f32);
+                let track = Rect::new(
+                    rect.min.x,
+                    rect.min.y + rect.height() / 3f32,
+                    rect.width(),
+                    rect.height() / 3f32,
+                );
+                draw_list.rect(track, style.widget);
+                draw_list.rect(
+                    Rect::new(track.min.x, track.min.y, track.width() * t, track.height()),
+                    style.active,
+                );
+                draw_list.rect(
+                    Rect::new(
+                        rect.min.x + (rect.width() - HANDLE_WIDTH) * t,
+                        rect.min.y,
+                        HANDLE_WIDTH,
+                        rect.height(),
+                    ),
+                    color,
+                );
+            }
+        }
+        if canvas.focused == Some(e) {
+            draw_list.outline(rect, FOCUS_THICKNESS, style.active);
+        }
+    }
+    draw_list
+}
@@ -0,0 +1,197 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use nalgebra::{Matrix4, Vector2};
+
+use crate::rendering::engine::SHADER_LIBRARY;
+use crate::rendering::opengl::buffer::{Buffer, BufferType, BufferUsage};
+use crate::rendering::opengl::program::Program;
+use crate::rendering::opengl::shader::ShaderType;
+use crate::rendering::opengl::texture::Texture;
+use crate::rendering::opengl::vertex_array::{DataType, VertexArray};
+use crate::rendering::opengl::{
+    disable, draw_elements_from, enable, get_viewport, set_blend_mode, BlendMode, DrawingMode,
+    Feature, OpenGlType,
+};
+use crate::rendering::text::{font_texture, Font};
+use crate::resources::shader::ShaderLoader;
+use crate::resources::texture::TextureLoader;
+use crate::ui::draw::{DrawList, DrawTexture, VERTEX_SIZE, VERTICES_PER_QUAD};
+use crate::MageError;
+
+const VERTEX_SHADER: &str = "ui-vertex.glsl";
+const FRAGMENT_SHADER: &str = "ui-fragment.glsl";
+const INDICES_PER_QUAD: usize = 6;
+const GLYPHS_UNIT: u32 = 0;
+
+type FontTexture = (Weak<Font>, Arc<Texture>);
+
+pub struct UiRenderer {
+    array_buffer: Buffer,
+    capacity: Cell<usize>,
+    element_buffer: Buffer,
+    program: Program,
+    textures: RefCell<HashMap<usize, FontTexture>>,
+    vertex_array: VertexArray,
+}
+
+impl UiRenderer {
+    pub fn new() -> Result<UiRenderer, MageError> {
+        let shader_loader = ShaderLoader::new(&SHADER_LIBRARY)?;
+        let program = Program::new(
+            shader_loader.load(ShaderType::Vertex, VERTEX_SHADER)?,
+            shader_loader.load(ShaderType::Fragment, FRAGMENT_SHADER)?,
+        )?;
+        let vertex_array = VertexArray::new();
+        let array_buffer = Buffer::new(BufferType::Array);
+        let element_buffer = Buffer::new(BufferType::ElementArray);
+        vertex_array.bind();
+        array_buffer.bind();
+        element_buffer.bind();
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            0,
+            VERTEX_SIZE as u32,
+            2,
+            0,
+            false,
+        );
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            1,
+            VERTEX_SIZE as u32,
+            2,
+            2,
+            false,
+        );
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            2,
+            VERTEX_SIZE as u32,
+            4,
+            4,
+            false,
+        );
+        VertexArray::set_vertex_attrib_with_padding::<f32>(
+            DataType::Float,
+            3,
+            VERTEX_SIZE as u32,
+            1,
+            8,
+            false,
+        );
+        VertexArray::unbind();
+        array_buffer.unbind();
+        element_buffer.unbind();
+        Ok(UiRenderer {
+            array_buffer,
+            capacity: Cell::new(0),
+            element_buffer,
+            program,
+            textures: RefCell::new(HashMap::new()),
+            vertex_array,
+        })
+    }
+
+    fn reserve(&self, quads: usize) {
+        if quads <= self.capacity.get() {
+            return;
+        }
+        let capacity = quads.next_power_of_two();
+        let indices = (0..capacity as u32)
+            .flat_map(|quad| {
+                let first = quad * VERTICES_PER_QUAD as u32;
+                [first, first + 1, first + 2, first + 2, first + 3, first]
+            })
+            .collect::<Vec<u32>>();
+        self.vertex_array.bind();
+        self.array_buffer.bind();
+        self.array_buffer.allocate_data_with_usage::<f32>(
+            capacity * VERTICES_PER_QUAD * VERTEX_SIZE,
+            BufferUsage::DynamicDraw,
+        );
+        self.element_buffer.bind();
+        self.element_buffer
+            .set_data(&indices, BufferUsage::StaticDraw);
+        VertexArray::unbind();
+        self.array_buffer.unbind();
+        self.element_buffer.unbind();
+        self.capacity.set(capacity);
+    }
+
+    fn texture(&self, font: &Arc<Font>) -> Arc<Texture> {
+        let mut textures = self.textures.borrow_mut();
+        textures.retain(|_, (font, _)| font.strong_count() > 0);
+        let key = Arc::as_ptr(font) as usize;
+        if let Some((cached, texture)) = textures.get(&key) {
+            if Weak::ptr_eq(cached, &Arc::downgrade(font)) {
+                return texture.clone();
+            }
+        }
+        let texture = Arc::new(font_texture(font, GLYPHS_UNIT));
+        textures.insert(key, (Arc::downgrade(font), texture.clone()));
+        texture
+    }
+
+    pub fn render(
+        &self,
+        font: &Arc<Font>,
+        draw_list: &DrawList,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
+        let (_x, _y, width, height) = get_viewport();
+        self.render_with_size(
+            font,
+            draw_list,
+            Vector2::new(width as f32, height as f32),
+            texture_loader,
+        )
+    }
+
+    pub fn render_with_size(
+        &self,
+        font: &Arc<Font>,
+        draw_list: &DrawList,
+        size: Vector2<f32>,
+        texture_loader: &mut TextureLoader,
+    ) -> Result<(), MageError> {
+        if draw_list.quads() == 0 {
+            return Ok(());
+        }
+        let font_texture = self.texture(font);
+        self.reserve(draw_list.quads());
+        self.array_buffer.bind();
+        self.array_buffer
+            .set_sub_data(0, draw_list.vertices().len(), draw_list.vertices());
+        self.array_buffer.unbind();
+
+        self.program.use_program();
+        self.program.set_uniform_i1("glyphs", GLYPHS_UNIT as i32);
+        self.program.set_uniform_matrix4(
+            "projection",
+            Matrix4::new_orthographic(0f32, size.x, size.y, 0f32, -1f32, 1f32),
+        );
+        disable(Feature::Depth);
+        enable(Feature::Blend);
+        set_blend_mode(BlendMode::Alpha);
+        self.vertex_array.bind();
+        for batch in draw_list.batches() {
+            let texture = match &batch.texture {
+                DrawTexture::Font => font_texture.clone(),
+                DrawTexture::Image(texture) => texture_loader.load_texture_2d(texture)?,
+            };
+            texture.bind(GLYPHS_UNIT);
+            draw_elements_from(
+                DrawingMode::Triangles,
+                (batch.count * INDICES_PER_QUAD) as u32,
+                OpenGlType::UnsignedInt,
+                batch.start * INDICES_PER_QUAD,
+            );
+        }
+        VertexArray::unbind();
+        disable(Feature::Blend);
+        enable(Feature::Depth);
+        Ok(())
+    }
+}
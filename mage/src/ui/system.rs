@@ -0,0 +1,302 @@
+use hecs::{Entity, World};
+use nalgebra::Vector2;
+
+use crate::core::system::System;
+use crate::rendering::opengl::get_viewport;
+use crate::ui::canvas::Navigation;
+use crate::ui::layout::{layout, line_height, roots, WIDGET_PADDING};
+use crate::ui::node::{UiEvent, UiEvents, UiNode, Widget};
+use crate::ui::{Rect, UiCanvas};
+use crate::MageError;
+
+pub(crate) fn draw_order(world: &World) -> Vec<(Entity, Rect)> {
+    fn visit(world: &World, entity: Entity, order: &mut Vec<(Entity, Rect)>) {
+        let children = match world.get::<UiNode>(entity) {
+            Ok(node) if node.visible => {
+                order.push((entity, node.rect));
+                node.children.clone()
+            }
+            _ => return,
+        };
+        for child in children {
+            visit(world, child, order);
+        }
+    }
+    let mut order = vec![];
+    for root in roots(world) {
+        visit(world, root, &mut order);
+    }
+    order
+}
+
+fn focusable(world: &World, entity: Entity) -> bool {
+    world
+        .get::<Widget>(entity)
+        .map(|widget| widget.focusable())
+        .unwrap_or(false)
+}
+
+fn set_focus(canvas: &mut UiCanvas, focus: Option<Entity>, events: &mut Vec<(Entity, UiEvent)>) {
+    if canvas.focused == focus {
+        return;
+    }
+    if let Some(previous) = canvas.focused {
+        events.push((previous, UiEvent::FocusLost));
+    }
+    if let Some(current) = focus {
+        events.push((current, UiEvent::FocusGained));
+    }
+    canvas.focused = focus;
+}
+
+fn step(widget: &mut Widget, direction: f32) -> Option<UiEvent> {
+    match widget {
+        Widget::Slider {
+            max,
+            min,
+            step,
+            value,
+        } => {
+            let previous = *value;
+            *value = (*value + *step * direction).clamp(*min, *max);
+            (previous != *value).then_some(UiEvent::Changed(*value))
+        }
+        Widget::List { items, selected } => {
+            if items.is_empty() {
+                return None;
+            }
+            let previous = *selected;
+            let next = *selected as i64 + direction as i64;
+            *selected = next.clamp(0, items.len() as i64 - 1) as usize;
+            (previous != *selected).then_some(UiEvent::Selected(*selected))
+        }
+        _ => None,
+    }
+}
+
+fn point(
+    widget: &mut Widget,
+    rect: Rect,
+    mouse: Vector2<f32>,
+    row: f32,
+    pressed: bool,
+) -> Option<UiEvent> {
+    match widget {
+        Widget::Slider {
+            max, min, value, ..
+        } => {
+            let previous = *value;
+            let t = ((mouse.x - rect.min.x) / rect.width()).clamp(0f32, 1f32);
+            *value = *min + t * (*max - *min);
+            (previous != *value).then_some(UiEvent::Changed(*value))
+        }
+        Widget::List { items, selected } if pressed => {
+            let index = ((mouse.y - rect.min.y - WIDGET_PADDING / 2f32) / row).floor();
+            if index >= 0f32 && (index as usize) < items.len() {
+                *selected = index as usize;
+                Some(UiEvent::Selected(*selected))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+pub struct UiSystem;
+
+impl UiSystem {
+    fn interact(
+        &self,
+        world: &World,
+        canvas: &mut UiCanvas,
+        order: &[(Entity, Rect)],
+    ) -> Result<Vec<(Entity, UiEvent)>, MageError> {
+        let mut events = vec![];
+        let interactive = order
+            .iter()
+            .filter(|(e, _rect)| focusable(world, *e))
+            .cloned()
+            .collect::<Vec<_>>();
+        let mouse = canvas.input.mouse;
+
+        let hovered = interactive
+            .iter()
+            .rev()
+            .find(|(_e, rect)| rect.contains(mouse))
+            .map(|(e, _rect)| *e);
+        if hovered != canvas.hovered {
+            if let Some(previous) = canvas.hovered {
+                events.push((previous, UiEvent::HoverExited));
+            }
+            if let Some(current) = hovered {
+                events.push((current, UiEvent::HoverEntered));
+            }
+            canvas.hovered = hovered;
+        }
+
+        if canvas.input.mouse_pressed {
+            canvas.pressed = hovered;
+            set_focus(canvas, hovered, &mut events);
+        }
+        if let Some(pressed) = canvas.pressed {
+            if canvas.input.mouse_down || canvas.input.mouse_pressed {
+                if let Some((_e, rect)) = interactive.iter().find(|(e, _rect)| *e == pressed) {
+                    let row = line_height(canvas) + WIDGET_PADDING;
+                    let mut widget = world.get_mut::<Widget>(pressed)?;
+                    if let Some(event) =
+                        point(&mut widget, *rect, mouse, row, canvas.input.mouse_pressed)
+                    {
+                        events.push((pressed, event));
+                    }
+                }
+            }
+        }
+        if canvas.input.mouse_released {
+            if let Some(pressed) = canvas.pressed.take() {
+                let is_button = matches!(*world.get::<Widget>(pressed)?, Widget::Button(_));
+                if is_button && hovered == Some(pressed) {
+                    events.push((pressed, UiEvent::Clicked));
+                }
+            }
+        }
+
+        let navigation = canvas.input.navigation.clone();
+        for navigation in navigation {
+            // Navigation only takes focus on canvases that ask for it
+            if canvas.focused.is_none() && !canvas.interactive {
+                continue;
+            }
+            let index = canvas
+                .focused
+                .and_then(|focused| interactive.iter().position(|(e, _rect)| *e == focused));
+            let count = interactive.len();
+            let next = |offset: usize| match index {
+                Some(index) => interactive.get((index + offset) % count.max(1)),
+                None => interactive.first(),
+            };
+            match navigation {
+                Navigation::Next | Navigation::Down => {
+                    set_focus(canvas, next(1).map(|(e, _rect)| *e), &mut events)
+                }
+                Navigation::Previous | Navigation::Up => set_focus(
+                    canvas,
+                    next(count.max(1) - 1).map(|(e, _rect)| *e),
+                    &mut events,
+                ),
+                Navigation::Left | Navigation::Right => {
+                    let direction = if navigation == Navigation::Left {
+                        -1f32
+                    } else {
+                        1f32
+                    };
+                    if let Some(focused) = canvas.focused {
+                        if let Some(event) =
+                            step(&mut *world.get_mut::<Widget>(focused)?, direction)
+                        {
+                            events.push((focused, event));
+                        }
+                    }
+                }
+                Navigation::Activate => {
+                    if let Some(focused) = canvas.focused {
+                        match &*world.get::<Widget>(focused)? {
+                            Widget::Button(_) => events.push((focused, UiEvent::Clicked)),
+                            Widget::List { selected, .. } => {
+                                events.push((focused, UiEvent::Selected(*selected)))
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Navigation::Cancel => set_focus(canvas, None, &mut events),
+            }
+        }
+
+        canvas.blocking = order
+            .iter()
+            .filter(|(e, _rect)| {
+                world.get::<Widget>(*e).is_ok()
+                    || world
+                        .get::<UiNode>(*e)
+                        .map(|node| node.background.is_some())
+                        .unwrap_or(false)
+            })
+            .map(|(_e, rect)| *rect)
+            .collect();
+        Ok(events)
+    }
+}
+
+impl System for UiSystem {
+    fn name(&self) -> &str {
+        "Ui"
+    }
+
+    fn start(&self, _world: &mut World) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn early_update(&self, world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        for (_e, events) in world.query_mut::<&mut UiEvents>() {
+            events.0.clear();
+        }
+        let canvas = match world.query::<&UiCanvas>().iter().next() {
+            Some((e, _canvas)) => e,
+            None => return Ok(()),
+        };
+
+        let (_x, _y, width, height) = get_viewport();
+        world
+            .get_mut::<UiCanvas>(canvas)?
+            .resize(Vector2::new(width as f32, height as f32));
+        let rects = layout(world, &*world.get::<UiCanvas>(canvas)?);
+        for (e, node) in world.query_mut::<&mut UiNode>() {
+            if let Some(rect) = rects.get(&e) {
+                node.rect = *rect;
+            }
+        }
+
+        let order = draw_order(world);
+        let events = self.interact(world, &mut *world.get_mut::<UiCanvas>(canvas)?, &order)?;
+        for (e, event) in events {
+            if let Ok(mut events) = world.get_mut::<UiEvents>(e) {
+                events.0.push(event);
+                continue;
+            }
+            world.insert_one(e, UiEvents(vec![event]))?;
+        }
+        Ok(())
+    }
+
+    fn update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+
+    fn late_update(&self, _world: &mut World, _delta_time: u64) -> Result<(), MageError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::step;
+    use crate::ui::{UiEvent, Widget};
+
+    #[test]
+    fn step_ignores_empty_lists() {
+        let mut widget = Widget::list(&[]);
+        assert!(step(&mut widget, 1f32).is_none());
+        assert!(step(&mut widget, -1f32).is_none());
+    }
+
+    #[test]
+    fn step_clamps_list_selection() {
+        let mut widget = Widget::list(&["a", "b"]);
+        assert!(matches!(
+            step(&mut widget, 1f32),
+            Some(UiEvent::Selected(1))
+        ));
+        assert!(step(&mut widget, 1f32).is_none());
+    }
+}
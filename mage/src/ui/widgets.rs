@@ -0,0 +1,237 @@
+use nalgebra::{Vector2, Vector3};
+use sdl2::keyboard::Keycode;
+
+use crate::ui::{Rect, Ui, ROW_HEIGHT, SPACING};
+
+const LABEL_RATIO: f32 = 0.4f32;
+const PLOT_HEIGHT: f32 = 60f32;
+const INDENT: f32 = 10f32;
+
+impl Ui {
+    fn labeled_row(&mut self, label: &str) -> Rect {
+        let row = self.row(ROW_HEIGHT);
+        let text = self.style.text;
+        self.text(row.min, label, text);
+        let label_width = row.width() * LABEL_RATIO;
+        Rect::new(
+            row.min.x + label_width,
+            row.min.y,
+            row.width() - label_width,
+            ROW_HEIGHT,
+        )
+    }
+
+    fn widget_color(&self, hovered: bool, active: bool) -> nalgebra::Vector4<f32> {
+        if active {
+            self.style.active
+        } else if hovered {
+            self.style.hovered
+        } else {
+            self.style.widget
+        }
+    }
+
+    pub fn label(&mut self, text: &str) {
+        let row = self.row(ROW_HEIGHT);
+        let color = self.style.text;
+        self.text(row.min, text, color);
+    }
+
+    pub fn separator(&mut self) {
+        let row = self.row(1f32);
+        let color = self.style.widget;
+        self.draw().rect(row, color);
+    }
+
+    pub fn button(&mut self, label: &str) -> bool {
+        let id = self.id(label);
+        let rect = self.row(ROW_HEIGHT);
+        let (hovered, active) = self.interact(id, rect);
+        let color = self.widget_color(hovered, active);
+        self.draw().rect(rect, color);
+        let text = self.style.text;
+        let offset = (rect.width() - self.text_width(label)) / 2f32;
+        self.text(Vector2::new(rect.min.x + offset, rect.min.y), label, text);
+        hovered && active && self.input.mouse_released
+    }
+
+    pub fn checkbox(&mut self, label: &str, value: &mut bool) -> bool {
+        let id = self.id(label);
+        let row = self.row(ROW_HEIGHT);
+        let (hovered, _active) = self.interact(id, row);
+        let clicked = hovered && self.input.mouse_pressed;
+        if clicked {
+            *value = !*value;
+        }
+        let color = self.widget_color(hovered, false);
+        let active = self.style.active;
+        let text = self.style.text;
+        let check = Rect::new(row.min.x, row.min.y, ROW_HEIGHT, ROW_HEIGHT);
+        let draw = self.draw();
+        draw.rect(check, color);
+        if *value {
+            draw.rect(
+                Rect::new(
+                    check.min.x + 4f32,
+                    check.min.y + 4f32,
+                    ROW_HEIGHT - 8f32,
+                    ROW_HEIGHT - 8f32,
+                ),
+                active,
+            );
+        }
+        self.text(
+            Vector2::new(row.min.x + ROW_HEIGHT + SPACING, row.min.y),
+            label,
+            text,
+        );
+        clicked
+    }
+
+    pub fn slider(&mut self, label: &str, value: &mut f32, min: f32, max: f32) -> bool {
+        let id = self.id(label);
+        let rect = self.labeled_row(label);
+        let (hovered, active) = self.interact(id, rect);
+        let previous = *value;
+        if active {
+            let t = ((self.input.mouse.x - rect.min.x) / rect.width()).clamp(0f32, 1f32);
+            *value = min + t * (max - min);
+        }
+        let color = self.widget_color(hovered, false);
+        let handle_color = self.style.active;
+        let text = self.style.text;
+        let t = ((*value - min) / (max - min)).clamp(0f32, 1f32);
+        let draw = self.draw();
+        draw.rect(rect, color);
+        draw.rect(
+            Rect::new(rect.min.x, rect.min.y, rect.width() * t, rect.height()),
+            handle_color,
+        );
+        self.text(
+            Vector2::new(rect.min.x + SPACING, rect.min.y),
+            &format!("{:.3}", value),
+            text,
+        );
+        previous != *value
+    }
+
+    fn drag_field(&mut self, id: u64, rect: Rect, value: &mut f32, speed: f32) -> bool {
+        let (hovered, active) = self.interact(id, rect);
+        let previous = *value;
+        if active {
+            *value += self.input.mouse_delta.x * speed;
+        }
+        let color = self.widget_color(hovered, active);
+        let text = self.style.text;
+        self.draw().rect(rect, color);
+        self.text(
+            Vector2::new(rect.min.x + SPACING, rect.min.y),
+            &format!("{:.2}", value),
+            text,
+        );
+        previous != *value
+    }
+
+    pub fn drag(&mut self, label: &str, value: &mut f32, speed: f32) -> bool {
+        let id = self.id(label);
+        let rect = self.labeled_row(label);
+        self.drag_field(id, rect, value, speed)
+    }
+
+    pub fn vector3(&mut self, label: &str, value: &mut Vector3<f32>, speed: f32) -> bool {
+        let rect = self.labeled_row(label);
+        let width = (rect.width() - 2f32 * SPACING) / 3f32;
+        let mut changed = false;
+        for i in 0..3 {
+            let id = self.id(&format!("{}#{}", label, i));
+            let field = Rect::new(
+                rect.min.x + i as f32 * (width + SPACING),
+                rect.min.y,
+                width,
+                rect.height(),
+            );
+            changed |= self.drag_field(id, field, &mut value[i], speed);
+        }
+        changed
+    }
+
+    pub fn text_input(&mut self, label: &str, value: &mut String) -> bool {
+        let id = self.id(label);
+        let rect = self.labeled_row(label);
+        let (hovered, _active) = self.interact(id, rect);
+        if hovered && self.input.mouse_pressed {
+            self.focused = Some(id);
+        }
+        let focused = self.focused == Some(id);
+        let previous = value.clone();
+        if focused {
+            value.push_str(&self.input.characters);
+            for key in self.input.keys.iter() {
+                match key {
+                    Keycode::Backspace => {
+                        value.pop();
+                    }
+                    Keycode::Return | Keycode::Escape | Keycode::Tab => self.focused = None,
+                    _ => {}
+                }
+            }
+        }
+        let color = self.widget_color(hovered, focused);
+        let text = self.style.text;
+        self.draw().rect(rect, color);
+        let content = if focused {
+            format!("{}|", value)
+        } else {
+            value.clone()
+        };
+        self.text(
+            Vector2::new(rect.min.x + SPACING, rect.min.y),
+            &content,
+            text,
+        );
+        previous != *value
+    }
+
+    pub fn plot(&mut self, label: &str, values: &[f32], min: f32, max: f32) {
+        self.label(label);
+        let rect = self.row(PLOT_HEIGHT);
+        let background = self.style.widget;
+        let line = self.style.active;
+        let draw = self.draw();
+        draw.rect(rect, background);
+        if values.len() < 2 || max <= min {
+            return;
+        }
+        let step = rect.width() / (values.len() - 1) as f32;
+        let point = |i: usize, value: f32| {
+            let t = ((value - min) / (max - min)).clamp(0f32, 1f32);
+            Vector2::new(rect.min.x + i as f32 * step, rect.max.y - t * rect.height())
+        };
+        for (i, pair) in values.windows(2).enumerate() {
+            draw.line(point(i, pair[0]), point(i + 1, pair[1]), 1.5f32, line);
+        }
+    }
+
+    pub fn collapsing<F: FnOnce(&mut Ui)>(&mut self, label: &str, contents: F) {
+        let id = self.id(label);
+        let rect = self.row(ROW_HEIGHT);
+        let (hovered, _active) = self.interact(id, rect);
+        if hovered && self.input.mouse_pressed && !self.open.remove(&id) {
+            self.open.insert(id);
+        }
+        let open = self.open.contains(&id);
+        let color = self.widget_color(hovered, false);
+        let text = self.style.text;
+        self.draw().rect(rect, color);
+        self.text(
+            Vector2::new(rect.min.x + SPACING, rect.min.y),
+            &format!("{} {}", if open { "v" } else { ">" }, label),
+            text,
+        );
+        if open {
+            self.cursor.x += INDENT;
+            contents(self);
+            self.cursor.x -= INDENT;
+        }
+    }
+}